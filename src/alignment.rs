@@ -0,0 +1,74 @@
+use ndarray::Array1;
+
+use crate::dtw::DtwResult;
+
+/// The result of a DTW run: the distance between the two time series and the warp path aligning them.
+///
+/// This wraps the `DtwResult` tuple returned by the DTW functions with named accessors, and converts
+/// from and into it with `From` and `into_parts`. The DTW functions themselves still return the tuple;
+/// an alignment is returned by `DtwBuilder::run`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alignment {
+    distance: f64,
+    path: Array1<(usize, usize)>,
+}
+
+impl Alignment {
+    /// Create an alignment from a distance and a warp path of `(row, column)` pairs, i.e.
+    /// `(y index, x index)`, as 0 based indices.
+    pub fn new(distance: f64, path: Array1<(usize, usize)>) -> Self {
+        Self { distance, path }
+    }
+
+    /// The DTW distance between the two time series.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// The distance divided by the length of the warp path, i.e. the average cost per aligned pair of
+    /// samples, which is comparable across time series of different sizes. The normalized distance is
+    /// infinite if the warp path is empty.
+    pub fn normalized_distance(&self) -> f64 {
+        match self.path.is_empty() {
+            true => f64::INFINITY,
+            false => self.distance / self.path.len() as f64,
+        }
+    }
+
+    /// The warp path as `(row, column)` pairs, i.e. `(y index, x index)`, ordered from the first to the
+    /// last aligned pair.
+    pub fn path(&self) -> &Array1<(usize, usize)> {
+        &self.path
+    }
+
+    /// The index of x at each step of the warp path.
+    pub fn index_map_x(&self) -> Array1<usize> {
+        self.path.map(|&(_, column)| column)
+    }
+
+    /// The index of y at each step of the warp path.
+    pub fn index_map_y(&self) -> Array1<usize> {
+        self.path.map(|&(row, _)| row)
+    }
+
+    /// The number of aligned pairs in the warp path.
+    pub fn len(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Whether the warp path is empty, which is the case when the distance is infinite.
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Split the alignment into the distance and the warp path.
+    pub fn into_parts(self) -> DtwResult {
+        (self.distance, self.path)
+    }
+}
+
+impl From<DtwResult> for Alignment {
+    fn from((distance, path): DtwResult) -> Self {
+        Self::new(distance, path)
+    }
+}
@@ -0,0 +1,161 @@
+use ndarray::ArrayView1;
+
+use crate::dtw::{final_distance, local_cost, minimum, warp_path, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// Compute the accumulated costs of a run of cells of one anti-diagonal, where element `k` of each
+/// slice belongs to the same cell: its samples of x and y, and the accumulated costs of the cells
+/// above, to the left and above to the left of it.
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn diagonal_kernel(
+    x: &[f64],
+    y: &[f64],
+    (above, left, diagonal): (&[f64], &[f64], &[f64]),
+    out: &mut [f64],
+    distance_mode: DistanceMode,
+) {
+    for k in 0..out.len() {
+        let value = f64::min(above[k], f64::min(left[k], diagonal[k]));
+        out[k] = local_cost(x[k], y[k], distance_mode) + value;
+    }
+}
+
+/// SSE2 version of the kernel, processing two cells per instruction. SSE2 is part of the x86_64
+/// baseline, so no runtime detection is needed. The results are identical to the scalar kernel.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn diagonal_kernel(
+    x: &[f64],
+    y: &[f64],
+    (above, left, diagonal): (&[f64], &[f64], &[f64]),
+    out: &mut [f64],
+    distance_mode: DistanceMode,
+) {
+    use std::arch::x86_64::*;
+
+    let size = out.len();
+    assert!(x.len() >= size && y.len() >= size);
+    assert!(above.len() >= size && left.len() >= size && diagonal.len() >= size);
+    let lanes = size - size % 2;
+    // SAFETY: SSE2 is always available on x86_64, and all loads and stores are within the slices,
+    // whose lengths were checked above
+    unsafe {
+        let sign_mask = _mm_set1_pd(-0f64);
+        for k in (0..lanes).step_by(2) {
+            let difference = _mm_sub_pd(
+                _mm_loadu_pd(x.as_ptr().add(k)),
+                _mm_loadu_pd(y.as_ptr().add(k)),
+            );
+            let cost = match distance_mode {
+                DistanceMode::Manhattan | DistanceMode::Chebyshev => {
+                    _mm_andnot_pd(sign_mask, difference)
+                }
+                DistanceMode::Euclidean | DistanceMode::SquaredEuclidean => {
+                    _mm_mul_pd(difference, difference)
+                }
+                _ => _mm_set_pd(
+                    local_cost(x[k + 1], y[k + 1], distance_mode),
+                    local_cost(x[k], y[k], distance_mode),
+                ),
+            };
+            let value = _mm_min_pd(
+                _mm_loadu_pd(above.as_ptr().add(k)),
+                _mm_min_pd(
+                    _mm_loadu_pd(left.as_ptr().add(k)),
+                    _mm_loadu_pd(diagonal.as_ptr().add(k)),
+                ),
+            );
+            _mm_storeu_pd(out.as_mut_ptr().add(k), _mm_add_pd(cost, value));
+        }
+    }
+    for k in lanes..size {
+        let value = f64::min(above[k], f64::min(left[k], diagonal[k]));
+        out[k] = local_cost(x[k], y[k], distance_mode) + value;
+    }
+}
+
+/// Run DTW over the full window with a dense kernel which processes the cost matrix along its
+/// anti-diagonals, whose cells do not depend on each other.
+///
+/// The accumulated costs of each anti-diagonal only depend on the two previous anti-diagonals, which
+/// are kept in contiguous buffers indexed by row, so that the local costs and the three-way minimum of
+/// consecutive cells can be computed with SIMD instructions. With the `simd` feature on x86_64, the
+/// kernel uses SSE2 intrinsics; otherwise it is a scalar loop over the same layout. No actions are
+/// stored: the warp path is recovered from the accumulated costs, with the same tie-breaking as
+/// `dtw_ex`, so the distance and warp path are identical to those of `dtw_ex` over the full window.
+///
+/// # Returns
+///
+/// The distance and warp path, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_antidiagonal<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    // reversing x makes its samples contiguous and increasing with the row along an anti-diagonal
+    let reversed_x: Vec<f64> = x.iter().rev().map(|&v| v.into()).collect();
+    let y: Vec<f64> = y.iter().map(|&v| v.into()).collect();
+
+    /* element r + 1 of a diagonal buffer holds the accumulated cost of the cell on row r (0 based)
+     * element 0 is a sentinel for the row above the cost matrix, and cells which are not on the
+     * diagonal keep an infinite cost as long as they may be read
+     * the anti-diagonals are also appended to 'costs' for the backtracking
+     */
+    let mut previous2 = vec![f64::INFINITY; rows + 2];
+    let mut previous = vec![f64::INFINITY; rows + 2];
+    let mut current = vec![f64::INFINITY; rows + 2];
+    let mut costs = Vec::<f64>::with_capacity(rows * columns);
+    let mut offsets = Vec::<usize>::with_capacity(rows + columns - 1);
+    for d in 0..rows + columns - 1 {
+        let first_row = d.saturating_sub(columns - 1);
+        let last_row = usize::min(d, rows - 1);
+        let size = last_row - first_row + 1;
+        match d {
+            // the first cell is reached from the origin of the cost matrix
+            0 => current[1] = local_cost(reversed_x[columns - 1], y[0], distance_mode),
+            _ => diagonal_kernel(
+                &reversed_x[columns - 1 + first_row - d..],
+                &y[first_row..],
+                (
+                    &previous[first_row..],
+                    &previous[first_row + 1..],
+                    &previous2[first_row..],
+                ),
+                &mut current[first_row + 1..=last_row + 1],
+                distance_mode,
+            ),
+        }
+        offsets.push(costs.len());
+        costs.extend_from_slice(&current[first_row + 1..first_row + 1 + size]);
+        std::mem::swap(&mut previous2, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    // the accumulated cost of a cell, using 1 based indices and the virtual first row and column
+    let cost = |row: usize, column: usize| match (row, column) {
+        (0, 0) => 0f64,
+        (0, _) | (_, 0) => f64::INFINITY,
+        _ => {
+            let d = row + column - 2;
+            costs[offsets[d] + row - 1 - d.saturating_sub(columns - 1)]
+        }
+    };
+    let total = cost(rows, columns);
+    let path = warp_path(rows, columns, |row, column| {
+        let (_, action) = minimum(
+            cost(row - 1, column),
+            cost(row, column - 1),
+            cost(row - 1, column - 1),
+        );
+        Some(action)
+    })?;
+    Ok((final_distance(total, distance_mode), path))
+}
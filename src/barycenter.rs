@@ -0,0 +1,133 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::dtw::dtw_ex;
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::softdtw::softdtw_gradient;
+use crate::window::FullWindow;
+
+/// Compute one iteration of DBA: align each time series to `center` and average the samples aligned to
+/// each sample of the center, each sample weighted by the weight of its time series.
+fn dba_update(
+    series: &[ArrayView1<f64>],
+    weights: Option<&[f64]>,
+    center: &Array1<f64>,
+    distance_mode: DistanceMode,
+) -> Result<Array1<f64>, DtwError> {
+    let size = center.shape()[0];
+    let mut sums = Array1::<f64>::zeros(size);
+    let mut counts = Array1::<f64>::zeros(size);
+    for (i, ts) in series.iter().enumerate() {
+        let weight = weights.map_or(1f64, |weights| weights[i]);
+        let (_, path) = dtw_ex(
+            &center.view(),
+            ts,
+            FullWindow::new(ts.shape()[0], size),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )?;
+        for &(row, column) in path.iter() {
+            sums[column] += weight * ts[row];
+            counts[column] += weight;
+        }
+    }
+    /* every sample of the center is on each warp path, unless there are no time series at all or all
+     * weights are zero
+     */
+    Ok(Array1::from_iter((0..size).map(|i| match counts[i] {
+        0f64 => center[i],
+        count => sums[i] / count,
+    })))
+}
+
+/// Average a set of time series under DTW with DTW Barycenter Averaging (DBA), as described by
+/// Petitjean et al.
+///
+/// Starting from `initial`, each iteration aligns every time series to the current average with DTW
+/// over the full window, and replaces each sample of the average with the mean of all samples aligned
+/// to it. The average keeps the size of `initial`, and a common choice for it is the medoid of the set.
+/// With `weights`, the samples of each time series count with the weight of the time series in the
+/// mean, e.g. for fractional cluster memberships; equal weights give the same average as no weights.
+/// The iterations stop once no sample of the average changes by more than `tolerance` in an iteration,
+/// or after `max_iterations`; a tolerance of 0 runs until the average no longer changes. Each iteration
+/// does not increase the sum of the squared Euclidean DTW distances to the average, which DBA minimizes.
+///
+/// # Returns
+///
+/// The average, or `DtwError::EmptyTimeSeries` if `initial` or any time series has no samples.
+///
+/// # Panics
+///
+/// If `weights` does not have one non-negative weight per time series.
+pub fn dba(
+    series: &[ArrayView1<f64>],
+    weights: Option<&[f64]>,
+    initial: &ArrayView1<f64>,
+    max_iterations: usize,
+    tolerance: f64,
+    distance_mode: DistanceMode,
+) -> Result<Array1<f64>, DtwError> {
+    if initial.is_empty() || series.iter().any(|ts| ts.is_empty()) {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    if let Some(weights) = weights {
+        assert!(
+            weights.len() == series.len() && weights.iter().all(|&weight| weight >= 0f64),
+            "there must be one non-negative weight per time series"
+        );
+    }
+    let mut center = initial.to_owned();
+    for _ in 0..max_iterations {
+        let updated = dba_update(series, weights, &center, distance_mode)?;
+        let change = updated
+            .iter()
+            .zip(center.iter())
+            .fold(0f64, |change, (a, b)| change.max((a - b).abs()));
+        center = updated;
+        if change <= tolerance {
+            break;
+        }
+    }
+    Ok(center)
+}
+
+/// Average a set of time series under soft-DTW, by gradient descent on the mean of the soft-DTW values
+/// between the average and each time series, as described by Cuturi and Blondel.
+///
+/// Starting from `initial`, each of the `iterations` steps moves the average against the mean gradient
+/// of the loss, scaled by `learning_rate`. The average keeps the size of `initial`. Compared to `dba`,
+/// which averages the samples along a single warp path per time series, the gradient weighs all warp
+/// paths by their soft-DTW probabilities, which gives smoother averages for noisy time series. Larger
+/// values of `gamma` smooth more, while values close to 0 approach the DBA objective.
+///
+/// # Returns
+///
+/// The average, or `DtwError::EmptyTimeSeries` if `initial` or any time series has no samples.
+///
+/// # Panics
+///
+/// If `gamma` is not positive.
+pub fn softdtw_barycenter(
+    series: &[ArrayView1<f64>],
+    initial: &ArrayView1<f64>,
+    gamma: f64,
+    iterations: usize,
+    learning_rate: f64,
+) -> Result<Array1<f64>, DtwError> {
+    if initial.is_empty() || series.iter().any(|ts| ts.is_empty()) {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let mut center = initial.to_owned();
+    if series.is_empty() {
+        return Ok(center);
+    }
+    for _ in 0..iterations {
+        let mut gradient = Array1::<f64>::zeros(center.shape()[0]);
+        for ts in series {
+            gradient += &softdtw_gradient(&center.view(), ts, gamma)?.gradient_x;
+        }
+        center.scaled_add(-learning_rate / series.len() as f64, &gradient);
+    }
+    Ok(center)
+}
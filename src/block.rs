@@ -0,0 +1,125 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::dtw::{final_distance, local_cost, minimum, warp_path, DtwResult};
+use crate::enums::*;
+
+/// Stepwise DTW over two time series which both arrive in blocks.
+///
+/// Each call to `push_block` appends a block to the x time series (new columns) and a block to the y
+/// time series (new rows) and extends the dynamic programming over the new cells only. To do so, the
+/// accumulated costs of the last row and of the last column are carried over between blocks, together
+/// with the actions of all cells, which are needed for backtracking the warp path in `finish`.
+///
+/// After any sequence of blocks, `finish` returns the same result as a batch `dtw` over the
+/// concatenation of the blocks. The full window is always used.
+pub struct BlockDtw {
+    distance_mode: DistanceMode,
+    /// All samples of the x time series received so far.
+    x: Vec<f64>,
+    /// All samples of the y time series received so far.
+    y: Vec<f64>,
+    /// The accumulated cost of each column on the last row.
+    last_row: Vec<f64>,
+    /// The accumulated cost of each row on the last column.
+    last_column: Vec<f64>,
+    /// The actions of all cells computed so far, stored per row.
+    actions: Vec<Vec<Action>>,
+}
+
+impl BlockDtw {
+    pub fn new(distance_mode: DistanceMode) -> Self {
+        Self {
+            distance_mode,
+            x: Vec::new(),
+            y: Vec::new(),
+            last_row: Vec::new(),
+            last_column: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Append a block of samples to each of the two time series. Either block may be empty.
+    pub fn push_block<T>(&mut self, x_block: &ArrayView1<T>, y_block: &ArrayView1<T>)
+    where
+        T: std::marker::Copy + std::convert::Into<f64>,
+    {
+        let old_rows = self.y.len();
+        let old_columns = self.x.len();
+        self.x.extend(x_block.iter().map(|&v| v.into()));
+        self.y.extend(y_block.iter().map(|&v| v.into()));
+        let rows = self.y.len();
+        let columns = self.x.len();
+
+        /* the virtual first row and first column of the cost matrix
+         * only the origin has a zero cost, all other cells are unreachable
+         */
+        let boundary = |index: usize| match index {
+            0 => 0f64,
+            _ => f64::INFINITY,
+        };
+
+        /* step 1: extend the existing rows with the new columns
+         * the strip holds the accumulated costs of the new columns on the previous row
+         */
+        let mut strip = vec![f64::INFINITY; columns - old_columns];
+        let mut previous_last_column = boundary(old_columns);
+        for row in 1..old_rows + 1 {
+            let mut left = self.last_column[row - 1];
+            let mut above_left = previous_last_column;
+            previous_last_column = left;
+            for column in old_columns + 1..columns + 1 {
+                let above = strip[column - old_columns - 1];
+                let cost = local_cost(self.x[column - 1], self.y[row - 1], self.distance_mode);
+                let (value, action) = minimum(above, left, above_left);
+                strip[column - old_columns - 1] = cost + value;
+                self.actions[row - 1].push(action);
+                above_left = above;
+                left = cost + value;
+            }
+            if columns > old_columns {
+                self.last_column[row - 1] = left;
+            }
+        }
+        self.last_row.extend_from_slice(&strip);
+
+        // step 2: compute the new rows over all columns
+        for row in old_rows + 1..rows + 1 {
+            let mut left = boundary(row);
+            let mut above_left = boundary(row - 1);
+            let mut actions = Vec::with_capacity(columns);
+            for column in 1..columns + 1 {
+                let above = match row {
+                    1 => f64::INFINITY,
+                    _ => self.last_row[column - 1],
+                };
+                let cost = local_cost(self.x[column - 1], self.y[row - 1], self.distance_mode);
+                let (value, action) = minimum(above, left, above_left);
+                self.last_row[column - 1] = cost + value;
+                actions.push(action);
+                above_left = above;
+                left = cost + value;
+            }
+            self.actions.push(actions);
+            self.last_column.push(left);
+        }
+    }
+
+    /// Finish the computation and return the distance and warp path over all received samples.
+    pub fn finish(self) -> DtwResult {
+        let rows = self.y.len();
+        let columns = self.x.len();
+        let cost = match (rows, columns) {
+            (0, 0) => 0f64,
+            (0, _) | (_, 0) => f64::INFINITY,
+            _ => self.last_row[columns - 1],
+        };
+        let path = match cost.is_finite() {
+            true => warp_path(rows, columns, |row, column| {
+                Some(self.actions[row - 1][column - 1])
+            })
+            .expect("all cells of the cost matrix are computed"),
+            false => Array1::<(usize, usize)>::default(0),
+        };
+        (final_distance(cost, self.distance_mode), path)
+    }
+}
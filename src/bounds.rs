@@ -0,0 +1,215 @@
+use ndarray::{Array1, ArrayView1};
+use std::collections::VecDeque;
+
+use crate::dtw::{dtw_ex_with_cutoff, final_distance, local_cost};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::SakoeChibaWindow;
+
+/// The upper and lower envelope of a time series for a Sakoe-Chiba band, i.e. the running maximum and
+/// minimum over the `2 * band + 1` samples around each sample.
+///
+/// The envelope of a query only depends on the query and the band, so it is computed once and reused
+/// for the lower bounds of all candidates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope {
+    upper: Array1<f64>,
+    lower: Array1<f64>,
+}
+
+impl Envelope {
+    /// Compute the envelope of `ts` for a band of `band` samples on either side, in linear time with
+    /// the streaming algorithm of Lemire.
+    pub fn new(ts: &ArrayView1<f64>, band: usize) -> Self {
+        let size = ts.shape()[0];
+        let mut upper = Array1::<f64>::zeros(size);
+        let mut lower = Array1::<f64>::zeros(size);
+        /* the deques hold the indices of the candidates for the maximum (minimum) of the window, with
+         * decreasing (increasing) values, so that the front is the maximum (minimum)
+         */
+        let mut maxima = VecDeque::<usize>::new();
+        let mut minima = VecDeque::<usize>::new();
+        for i in 0..size + band {
+            if i < size {
+                while maxima.back().is_some_and(|&back| ts[back] <= ts[i]) {
+                    maxima.pop_back();
+                }
+                maxima.push_back(i);
+                while minima.back().is_some_and(|&back| ts[back] >= ts[i]) {
+                    minima.pop_back();
+                }
+                minima.push_back(i);
+            }
+            // the window of sample i - band is complete once sample i was added
+            if i >= band {
+                let center = i - band;
+                for deque in [&mut maxima, &mut minima] {
+                    while deque.front().is_some_and(|&front| front + band < center) {
+                        deque.pop_front();
+                    }
+                }
+                upper[center] = ts[maxima[0]];
+                lower[center] = ts[minima[0]];
+            }
+        }
+        Self { upper, lower }
+    }
+
+    /// The running maximum of the time series.
+    pub fn upper(&self) -> &Array1<f64> {
+        &self.upper
+    }
+
+    /// The running minimum of the time series.
+    pub fn lower(&self) -> &Array1<f64> {
+        &self.lower
+    }
+
+    /// Compute LB_Keogh of a candidate against the time series of this envelope, see `lb_keogh`.
+    ///
+    /// # Panics
+    ///
+    /// If the candidate and the time series of the envelope have different lengths.
+    pub fn lb_keogh(&self, candidate: &ArrayView1<f64>, distance_mode: DistanceMode) -> f64 {
+        assert_eq!(
+            candidate.shape()[0],
+            self.upper.shape()[0],
+            "LB_Keogh requires time series of the same length"
+        );
+        let cost = candidate
+            .iter()
+            .zip(self.upper.iter().zip(self.lower.iter()))
+            .fold(0f64, |cost, (&value, (&upper, &lower))| {
+                let bound = value.clamp(lower, upper);
+                cost + local_cost(value, bound, distance_mode)
+            });
+        final_distance(cost, distance_mode)
+    }
+}
+
+/// Compute the LB_Keogh lower bound of the DTW distance between `query` and `candidate`, for DTW
+/// constrained to a Sakoe-Chiba band of `band` samples (`SakoeChibaWindow` with a band width of
+/// `band`) on time series of the same length.
+///
+/// Each sample of the candidate is compared to the envelope of the query: samples within the envelope
+/// cost nothing, and samples outside of it cost their local cost to the nearest side of the envelope.
+/// Since every sample of the candidate is aligned to a sample of the query within the band, the bound
+/// never exceeds the DTW distance. When comparing a query to many candidates, compute its `Envelope`
+/// once and use `Envelope::lb_keogh` instead.
+pub fn lb_keogh(
+    query: &ArrayView1<f64>,
+    candidate: &ArrayView1<f64>,
+    band: usize,
+    distance_mode: DistanceMode,
+) -> f64 {
+    Envelope::new(query, band).lb_keogh(candidate, distance_mode)
+}
+
+/// Compute the LB_Kim lower bound of the DTW distance from the first and last samples (LB_Kim FL).
+///
+/// Every warp path aligns the first samples of the two time series with each other, and the last
+/// samples with each other, so the sum of these two local costs never exceeds the DTW distance. The
+/// bound costs constant time and holds for any window and for time series of different lengths.
+///
+/// # Panics
+///
+/// If either time series is empty.
+pub fn lb_kim_fl(x: &ArrayView1<f64>, y: &ArrayView1<f64>, distance_mode: DistanceMode) -> f64 {
+    let (x_size, y_size) = (x.shape()[0], y.shape()[0]);
+    assert!(
+        x_size > 0 && y_size > 0,
+        "LB_Kim requires non-empty time series"
+    );
+    let first = local_cost(x[0], y[0], distance_mode);
+    let cost = match x_size == 1 && y_size == 1 {
+        // the first and the last cell are the same
+        true => first,
+        false => first + local_cost(x[x_size - 1], y[y_size - 1], distance_mode),
+    };
+    final_distance(cost, distance_mode)
+}
+
+/// A cascade of lower bounds of increasing cost in front of DTW, for comparing a query to many
+/// candidates of the same length, as in the UCR Suite.
+///
+/// A candidate is checked against LB_Kim FL, then LB_Keogh against the envelope of the query, then
+/// LB_Keogh of the query against the envelope of the candidate, and finally DTW constrained to the
+/// Sakoe-Chiba band, which is abandoned as soon as it exceeds the best distance so far. Each stage is
+/// only reached if the previous ones could not discard the candidate.
+pub struct CascadingBounds {
+    query: Array1<f64>,
+    envelope: Envelope,
+    band: usize,
+    distance_mode: DistanceMode,
+}
+
+impl CascadingBounds {
+    /// Prepare the cascade for `query`, computing its envelope for a Sakoe-Chiba band of `band` samples.
+    pub fn new(query: &ArrayView1<f64>, band: usize, distance_mode: DistanceMode) -> Self {
+        Self {
+            query: query.to_owned(),
+            envelope: Envelope::new(query, band),
+            band,
+            distance_mode,
+        }
+    }
+
+    /// The envelope of the query.
+    pub fn envelope(&self) -> &Envelope {
+        &self.envelope
+    }
+
+    /// Run the cascade on a candidate.
+    ///
+    /// # Returns
+    ///
+    /// The DTW distance between the query and the candidate within the Sakoe-Chiba band, or the stage
+    /// which proved that the distance exceeds `best_so_far`. A distance equal to `best_so_far` is
+    /// returned rather than discarded.
+    ///
+    /// # Panics
+    ///
+    /// If the candidate and the query have different lengths, or are empty.
+    pub fn evaluate(
+        &self,
+        candidate: &ArrayView1<f64>,
+        best_so_far: f64,
+    ) -> Result<f64, PruningStage> {
+        let query = self.query.view();
+        if lb_kim_fl(&query, candidate, self.distance_mode) > best_so_far {
+            return Err(PruningStage::LbKim);
+        }
+        if self.envelope.lb_keogh(candidate, self.distance_mode) > best_so_far {
+            return Err(PruningStage::LbKeogh);
+        }
+        let candidate_envelope = Envelope::new(candidate, self.band);
+        if candidate_envelope.lb_keogh(&query, self.distance_mode) > best_so_far {
+            return Err(PruningStage::LbKeoghReversed);
+        }
+
+        let size = query.shape()[0];
+        let result = dtw_ex_with_cutoff(
+            candidate,
+            &query,
+            SakoeChibaWindow::new(size, size, self.band),
+            self.distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+            best_so_far,
+        );
+        match result {
+            Ok((distance, _)) => Ok(distance),
+            Err(DtwError::Abandoned) => Err(PruningStage::Abandoned),
+            Err(error) => panic!("the DTW of the cascade failed: {}", error),
+        }
+    }
+
+    /// Run the cascade on a candidate, see `evaluate`.
+    ///
+    /// # Returns
+    ///
+    /// The DTW distance, or `None` if the candidate was discarded by one of the stages.
+    pub fn distance(&self, candidate: &ArrayView1<f64>, best_so_far: f64) -> Option<f64> {
+        self.evaluate(candidate, best_so_far).ok()
+    }
+}
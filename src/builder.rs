@@ -0,0 +1,121 @@
+use ndarray::ArrayView1;
+
+use crate::alignment::Alignment;
+use crate::dtw::dtw_ex_with_cutoff;
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::measure::WindowFactory;
+use crate::preprocess::preprocess;
+use crate::window::FullWindow;
+
+/// Configuration of a DTW run, built up with chained setters and run with `run`, so that new options
+/// can be added without changing the signature of existing calls.
+///
+/// The defaults are those of `dtw`: the euclidean mode over the full window, with automatic storage,
+/// no preprocessing, no clamping of the local costs, and no cutoff.
+pub struct DtwBuilder {
+    distance_mode: DistanceMode,
+    window_factory: WindowFactory,
+    storage_strategy: StorageStrategy,
+    preprocessing: Preprocessing,
+    clamp_delta: f64,
+    cutoff: f64,
+}
+
+impl Default for DtwBuilder {
+    fn default() -> Self {
+        Self {
+            distance_mode: DistanceMode::Euclidean,
+            window_factory: Box::new(|rows, columns| Box::new(FullWindow::new(rows, columns))),
+            storage_strategy: StorageStrategy::Auto,
+            preprocessing: Preprocessing::None,
+            clamp_delta: f64::INFINITY,
+            cutoff: f64::INFINITY,
+        }
+    }
+}
+
+impl DtwBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the local cost and final transformation of the distance.
+    pub fn distance_mode(mut self, distance_mode: DistanceMode) -> Self {
+        self.distance_mode = distance_mode;
+        self
+    }
+
+    /// Set the window, built by `window_factory` from the number of rows and columns of the cost
+    /// matrix for each pair of time series.
+    pub fn window(mut self, window_factory: WindowFactory) -> Self {
+        self.window_factory = window_factory;
+        self
+    }
+
+    /// Set the backend storing the cost and action values, see `dtw_ex`.
+    pub fn storage(mut self, storage_strategy: StorageStrategy) -> Self {
+        self.storage_strategy = storage_strategy;
+        self
+    }
+
+    /// Transform both time series with `preprocessing` before computing the local costs, e.g. to
+    /// align their derivatives instead of their values. The window and the other options apply to the
+    /// transformed time series, which have the same sizes as the original ones.
+    pub fn preprocessing(mut self, preprocessing: Preprocessing) -> Self {
+        self.preprocessing = preprocessing;
+        self
+    }
+
+    /// Clamp the absolute difference between two samples before computing the local cost, see
+    /// `dtw_ex`.
+    pub fn clamp_delta(mut self, clamp_delta: f64) -> Self {
+        self.clamp_delta = clamp_delta;
+        self
+    }
+
+    /// Abandon the computation with `DtwError::Abandoned` if the distance is larger than `cutoff`. The
+    /// distance is known to exceed the cutoff as soon as all cells of a row of the cost matrix have a
+    /// larger accumulated cost, so most of the work is skipped for dissimilar time series, e.g. when
+    /// discarding the candidates of a nearest neighbor search.
+    pub fn cutoff(mut self, cutoff: f64) -> Self {
+        self.cutoff = cutoff;
+        self
+    }
+
+    /// Run DTW of `x` and `y` with the configured options.
+    ///
+    /// # Returns
+    ///
+    /// The alignment of the two time series, an error as for `dtw_ex`, or `DtwError::Abandoned` if the
+    /// distance exceeds the cutoff. The distance is infinite and the warp path empty when the window
+    /// does not connect the last cell of the cost matrix to its origin.
+    pub fn run<T>(&self, x: &ArrayView1<T>, y: &ArrayView1<T>) -> Result<Alignment, DtwError>
+    where
+        T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+    {
+        if self.preprocessing != Preprocessing::None {
+            let x = preprocess(x, self.preprocessing);
+            let y = preprocess(y, self.preprocessing);
+            return self.align(&x.view(), &y.view());
+        }
+        self.align(x, y)
+    }
+
+    fn align<T>(&self, x: &ArrayView1<T>, y: &ArrayView1<T>) -> Result<Alignment, DtwError>
+    where
+        T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+    {
+        let window = (self.window_factory)(y.shape()[0], x.shape()[0]);
+        let (distance, path) = dtw_ex_with_cutoff(
+            x,
+            y,
+            window,
+            self.distance_mode,
+            self.storage_strategy,
+            self.clamp_delta,
+            self.cutoff,
+        )?;
+        Ok(Alignment::new(distance, path))
+    }
+}
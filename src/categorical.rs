@@ -0,0 +1,84 @@
+use ndarray::Array2;
+
+use crate::dtw::{checked_dtw_cells, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// The 0/1 local cost between two symbols: 0 if they are equal and 1 otherwise.
+pub fn mismatch<S: PartialEq>(a: &S, b: &S) -> f64 {
+    match a == b {
+        true => 0f64,
+        false => 1f64,
+    }
+}
+
+/// The local costs of substituting a symbol by another, as used in the alignment of biological
+/// sequences. Element `[i, j]` of the costs is the local cost between `symbols[i]` and `symbols[j]`.
+pub struct SubstitutionMatrix<S> {
+    symbols: Vec<S>,
+    costs: Array2<f64>,
+}
+
+impl<S: PartialEq> SubstitutionMatrix<S> {
+    /// Create a substitution matrix over an alphabet of distinct symbols.
+    ///
+    /// # Panics
+    ///
+    /// If `costs` does not have a row and a column per symbol, or if a cost is negative.
+    pub fn new(symbols: Vec<S>, costs: Array2<f64>) -> Self {
+        let size = symbols.len();
+        assert_eq!(
+            costs.shape(),
+            [size, size],
+            "the substitution matrix must have a row and a column per symbol"
+        );
+        assert!(
+            costs.iter().all(|&cost| cost >= 0f64),
+            "the substitution costs must not be negative"
+        );
+        Self { symbols, costs }
+    }
+
+    /// The local cost between two symbols.
+    ///
+    /// # Panics
+    ///
+    /// If either symbol is not part of the alphabet of the matrix.
+    pub fn cost(&self, a: &S, b: &S) -> f64 {
+        let index = |symbol: &S| {
+            self.symbols
+                .iter()
+                .position(|s| s == symbol)
+                .expect("the symbol is not part of the substitution matrix")
+        };
+        self.costs[[index(a), index(b)]]
+    }
+}
+
+/// Run DTW on series of discrete symbols (e.g. `u8` labels or `&str` events), which cannot be
+/// converted to floats, with the local cost `cost(a, b)` between a symbol of x and a symbol of y.
+///
+/// Use `mismatch` for a 0/1 cost, in which case the distance is the number of samples aligned to a
+/// different symbol, or `SubstitutionMatrix::cost` for arbitrary costs between symbols. The local costs
+/// are accumulated as they are and the distance is the accumulated cost of the last cell.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::EmptyTimeSeries` if either series has no samples and
+/// `DtwError::MalformedWindow` if the window visits a cell outside of the cost matrix.
+pub fn dtw_categorical<S, W, C>(x: &[S], y: &[S], window: W, cost: C) -> Result<DtwResult, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+    C: Fn(&S, &S) -> f64,
+{
+    // the final distance of the manhattan mode is the accumulated cost itself
+    checked_dtw_cells(
+        y.len(),
+        x.len(),
+        window,
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| cost(&x[column - 1], &y[row - 1]),
+    )
+}
@@ -0,0 +1,177 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::bounds::CascadingBounds;
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::preprocess::znormalize;
+
+/// A k nearest neighbors classifier of time series under DTW, constrained to a Sakoe-Chiba band.
+///
+/// The training time series must all have the size of the queries. The neighbors of a query are found
+/// with the `CascadingBounds` of the query, which discard most training time series with cheap lower
+/// bounds, or abandon their DTW, once they cannot be closer than the k-th nearest neighbor found so far.
+/// With a single neighbor, this is the 1-NN-DTW baseline of time series classification.
+pub struct KnnDtw<L> {
+    neighbors: usize,
+    band: usize,
+    distance_mode: DistanceMode,
+    series: Vec<Array1<f64>>,
+    labels: Vec<L>,
+}
+
+impl<L: Clone + PartialEq> KnnDtw<L> {
+    /// Create a classifier voting among `neighbors` nearest neighbors, with DTW constrained to a
+    /// Sakoe-Chiba band of `band` samples.
+    ///
+    /// # Panics
+    ///
+    /// If `neighbors` is 0.
+    pub fn new(neighbors: usize, band: usize, distance_mode: DistanceMode) -> Self {
+        assert_ne!(neighbors, 0, "the number of neighbors must be positive");
+        Self {
+            neighbors,
+            band,
+            distance_mode,
+            series: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Set the training time series and their labels, replacing any previous ones.
+    ///
+    /// # Panics
+    ///
+    /// If there are not as many labels as time series, or if the time series have different sizes.
+    pub fn fit(&mut self, series: &[ArrayView1<f64>], labels: &[L]) {
+        assert_eq!(
+            series.len(),
+            labels.len(),
+            "each time series must have a label"
+        );
+        assert!(
+            series.windows(2).all(|pair| pair[0].len() == pair[1].len()),
+            "the time series must have the same size"
+        );
+        self.series = series.iter().map(|ts| ts.to_owned()).collect();
+        self.labels = labels.to_vec();
+    }
+
+    /// Find the nearest training time series of a query.
+    ///
+    /// # Returns
+    ///
+    /// The index and distance of at most `neighbors` training time series, ordered by increasing
+    /// distance and then by index, or `DtwError::EmptyTimeSeries` if the query has no samples.
+    ///
+    /// # Panics
+    ///
+    /// If the query and the training time series have different sizes.
+    pub fn nearest(&self, query: &ArrayView1<f64>) -> Result<Vec<(usize, f64)>, DtwError> {
+        if query.is_empty() {
+            return Err(DtwError::EmptyTimeSeries);
+        }
+        let bounds = CascadingBounds::new(query, self.band, self.distance_mode);
+        let mut nearest: Vec<(usize, f64)> = Vec::with_capacity(self.neighbors + 1);
+        for (i, ts) in self.series.iter().enumerate() {
+            assert_eq!(
+                ts.len(),
+                query.len(),
+                "the query must have the size of the training time series"
+            );
+            let best_so_far = match nearest.len() < self.neighbors {
+                true => f64::INFINITY,
+                false => nearest[nearest.len() - 1].1,
+            };
+            if let Some(distance) = bounds.distance(&ts.view(), best_so_far) {
+                // keep the first neighbor on equal distances
+                let position = nearest.partition_point(|&(_, d)| d <= distance);
+                nearest.insert(position, (i, distance));
+                nearest.truncate(self.neighbors);
+            }
+        }
+        Ok(nearest)
+    }
+
+    /// Predict the label of a query by a majority vote of its nearest neighbors. On a tie, the label of
+    /// the nearest neighbor among the tied labels is predicted.
+    ///
+    /// # Returns
+    ///
+    /// The predicted label, or `DtwError::EmptyTimeSeries` if the query has no samples.
+    ///
+    /// # Panics
+    ///
+    /// If the classifier has no training time series, or if the query and the training time series
+    /// have different sizes.
+    pub fn predict(&self, query: &ArrayView1<f64>) -> Result<L, DtwError> {
+        assert!(
+            !self.series.is_empty(),
+            "the classifier has no training time series"
+        );
+        let nearest = self.nearest(query)?;
+        let votes = |label: &L| {
+            nearest
+                .iter()
+                .filter(|&&(i, _)| self.labels[i] == *label)
+                .count()
+        };
+        // the neighbors are ordered by distance, so the first label with the most votes is the nearest
+        let (winner, _) = nearest.iter().fold((0, 0), |best, &(i, _)| {
+            let count = votes(&self.labels[i]);
+            match count > best.1 {
+                true => (i, count),
+                false => best,
+            }
+        });
+        Ok(self.labels[winner].clone())
+    }
+}
+
+/// Compare a query to a bank of prototypes, e.g. for template matching, under DTW constrained to a
+/// Sakoe-Chiba band of `band_radius` samples.
+///
+/// The query and the prototypes are z-normalized, as in `ucr_search`. The query side is prepared only
+/// once: its z-normalization, and its envelope within a single `CascadingBounds`, are shared by all
+/// prototypes. Since the distance to every prototype is reported, the cascade is not used to discard
+/// prototypes.
+///
+/// # Returns
+///
+/// The index of the nearest prototype, the first one on equal distances, and the distance to each
+/// prototype, or `DtwError::EmptyTimeSeries` if the query has no samples.
+///
+/// # Panics
+///
+/// If there are no prototypes, or if the query and the prototypes have different sizes.
+pub fn match_prototypes(
+    query: &ArrayView1<f64>,
+    prototypes: &[ArrayView1<f64>],
+    distance_mode: DistanceMode,
+    band_radius: usize,
+) -> Result<(usize, Array1<f64>), DtwError> {
+    if query.is_empty() {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    assert!(
+        !prototypes.is_empty(),
+        "there must be at least one prototype"
+    );
+    let bounds = CascadingBounds::new(&znormalize(query).view(), band_radius, distance_mode);
+    let distances = Array1::from_iter(prototypes.iter().map(|prototype| {
+        assert_eq!(
+            prototype.len(),
+            query.len(),
+            "the query must have the size of the prototypes"
+        );
+        bounds
+            .distance(&znormalize(prototype).view(), f64::INFINITY)
+            .expect("no candidate is discarded without a best distance so far")
+    }));
+    let best = (0..distances.len())
+        .reduce(|best, i| match distances[i] < distances[best] {
+            true => i,
+            false => best,
+        })
+        .expect("there is at least one prototype");
+    Ok((best, distances))
+}
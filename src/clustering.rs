@@ -0,0 +1,489 @@
+use ndarray::{Array1, ArrayView1, ArrayView2};
+
+use crate::barycenter::dba;
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::measure::ElasticMeasure;
+
+/// The result of a clustering of time series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clustering {
+    /// The index of the cluster of each time series.
+    pub labels: Vec<usize>,
+    /// The centroid of each cluster.
+    pub centroids: Vec<Array1<f64>>,
+    /// The sum of the squared distances between each time series and the centroid of its cluster.
+    pub inertia: f64,
+    /// The number of iterations run.
+    pub iterations: usize,
+}
+
+/// Configuration of the k-means clustering of time series under DTW, built up with chained setters and
+/// run with `fit`.
+///
+/// Each iteration assigns each time series to the nearest centroid under an `ElasticMeasure`, e.g.
+/// `Dtw` or its `FastDtw` approximation, and replaces each centroid with the DBA average of the time
+/// series of its cluster, starting from the previous centroid. The defaults are 50 iterations, a
+/// tolerance of 1e-6, 10 iterations of DBA per update and the euclidean mode for DBA.
+pub struct KMeans {
+    clusters: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    dba_iterations: usize,
+    distance_mode: DistanceMode,
+}
+
+impl KMeans {
+    /// Configure a clustering into `clusters` clusters.
+    pub fn new(clusters: usize) -> Self {
+        Self {
+            clusters,
+            max_iterations: 50,
+            tolerance: 1e-6,
+            dba_iterations: 10,
+            distance_mode: DistanceMode::Euclidean,
+        }
+    }
+
+    /// Set the maximum number of iterations of assignment and update.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stop once an iteration decreases the inertia by at most this fraction of the previous inertia.
+    /// The iterations always stop once no time series changes cluster.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the maximum number of DBA iterations of each centroid update, see `dba`.
+    pub fn dba_iterations(mut self, dba_iterations: usize) -> Self {
+        self.dba_iterations = dba_iterations;
+        self
+    }
+
+    /// Set the distance mode of the DTW alignments computed by DBA.
+    pub fn distance_mode(mut self, distance_mode: DistanceMode) -> Self {
+        self.distance_mode = distance_mode;
+        self
+    }
+
+    /// Cluster `series` with the distances of `measure`.
+    ///
+    /// The initial centroids are chosen deterministically by farthest point traversal: the first time
+    /// series, then repeatedly the time series farthest from its nearest centroid so far. A cluster
+    /// left without time series keeps its centroid.
+    ///
+    /// # Returns
+    ///
+    /// The clustering, or `DtwError::EmptyTimeSeries` if any time series has no samples, or the first
+    /// other error of `measure`.
+    ///
+    /// # Panics
+    ///
+    /// If the number of clusters is 0 or larger than the number of time series.
+    pub fn fit<M>(&self, measure: &M, series: &[ArrayView1<f64>]) -> Result<Clustering, DtwError>
+    where
+        M: ElasticMeasure,
+    {
+        assert!(
+            self.clusters > 0 && self.clusters <= series.len(),
+            "the number of clusters must be between 1 and the number of time series"
+        );
+        if series.iter().any(|ts| ts.is_empty()) {
+            return Err(DtwError::EmptyTimeSeries);
+        }
+
+        let mut centroids = vec![series[0].to_owned()];
+        let mut nearest: Vec<f64> = series
+            .iter()
+            .map(|ts| measure.distance(&centroids[0].view(), ts))
+            .collect::<Result<_, DtwError>>()?;
+        while centroids.len() < self.clusters {
+            let farthest = (0..series.len())
+                .reduce(|best, i| match nearest[i] > nearest[best] {
+                    true => i,
+                    false => best,
+                })
+                .expect("there is at least one time series");
+            centroids.push(series[farthest].to_owned());
+            for (i, ts) in series.iter().enumerate() {
+                let distance = measure.distance(&centroids[centroids.len() - 1].view(), ts)?;
+                nearest[i] = nearest[i].min(distance);
+            }
+        }
+
+        let mut labels = vec![usize::MAX; series.len()];
+        let mut inertia = f64::INFINITY;
+        let mut iterations = 0;
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let (new_labels, new_inertia) = assign(measure, series, &centroids)?;
+            let converged = new_labels == labels
+                || (inertia.is_finite() && inertia - new_inertia <= self.tolerance * inertia);
+            labels = new_labels;
+            inertia = new_inertia;
+            if converged {
+                break;
+            }
+            for (cluster, centroid) in centroids.iter_mut().enumerate() {
+                let members: Vec<ArrayView1<f64>> = (0..series.len())
+                    .filter(|&i| labels[i] == cluster)
+                    .map(|i| series[i].view())
+                    .collect();
+                if !members.is_empty() {
+                    *centroid = dba(
+                        &members,
+                        None,
+                        &centroid.view(),
+                        self.dba_iterations,
+                        0f64,
+                        self.distance_mode,
+                    )?;
+                }
+            }
+        }
+        // the labels and inertia match the final centroids unless the iterations ran out
+        if iterations == self.max_iterations {
+            (labels, inertia) = assign(measure, series, &centroids)?;
+        }
+
+        Ok(Clustering {
+            labels,
+            centroids,
+            inertia,
+            iterations,
+        })
+    }
+}
+
+/// Assign each time series to its nearest centroid, the first one on equal distances.
+///
+/// # Returns
+///
+/// The labels and the sum of the squared distances to the nearest centroids, or the first error of
+/// `measure`.
+fn assign<M>(
+    measure: &M,
+    series: &[ArrayView1<f64>],
+    centroids: &[Array1<f64>],
+) -> Result<(Vec<usize>, f64), DtwError>
+where
+    M: ElasticMeasure,
+{
+    let mut inertia = 0f64;
+    let labels = series
+        .iter()
+        .map(|ts| {
+            let distances = centroids
+                .iter()
+                .map(|centroid| measure.distance(&centroid.view(), ts))
+                .collect::<Result<Vec<_>, DtwError>>()?;
+            let (label, distance) = distances.into_iter().enumerate().fold(
+                (0, f64::INFINITY),
+                |best, (i, distance)| match distance < best.1 {
+                    true => (i, distance),
+                    false => best,
+                },
+            );
+            inertia += distance * distance;
+            Ok(label)
+        })
+        .collect::<Result<_, DtwError>>()?;
+    Ok((labels, inertia))
+}
+
+/// The result of a k-medoids clustering.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MedoidClustering {
+    /// The index of the cluster of each time series.
+    pub labels: Vec<usize>,
+    /// The index of the time series which is the medoid of each cluster.
+    pub medoids: Vec<usize>,
+    /// The sum of the distances between each time series and the medoid of its cluster.
+    pub cost: f64,
+}
+
+/// The nearest medoid of each time series, the first one on equal distances, and the sum of the
+/// distances to them.
+fn nearest_medoids(distances: &ArrayView2<f64>, medoids: &[usize]) -> (Vec<usize>, f64) {
+    let mut cost = 0f64;
+    let labels =
+        (0..distances.shape()[0])
+            .map(|i| {
+                let (label, distance) = medoids.iter().enumerate().fold(
+                    (0, f64::INFINITY),
+                    |best, (label, &medoid)| match distances[[i, medoid]] < best.1 {
+                        true => (label, distances[[i, medoid]]),
+                        false => best,
+                    },
+                );
+                cost += distance;
+                label
+            })
+            .collect();
+    (labels, cost)
+}
+
+/// The distance of each time series to its nearest and second nearest medoid, and the position of the
+/// nearest medoid in `medoids`, the first one on equal distances. The second nearest distance is
+/// infinite if there is a single medoid.
+fn nearest_two_medoids(
+    distances: &ArrayView2<f64>,
+    medoids: &[usize],
+) -> (Vec<usize>, Vec<f64>, Vec<f64>) {
+    let size = distances.shape()[0];
+    let mut positions = vec![0; size];
+    let mut nearest = vec![f64::INFINITY; size];
+    let mut second = vec![f64::INFINITY; size];
+    for i in 0..size {
+        for (position, &medoid) in medoids.iter().enumerate() {
+            let distance = distances[[i, medoid]];
+            if distance < nearest[i] {
+                second[i] = nearest[i];
+                nearest[i] = distance;
+                positions[i] = position;
+            } else if distance < second[i] {
+                second[i] = distance;
+            }
+        }
+    }
+    (positions, nearest, second)
+}
+
+/// Cluster time series into `clusters` clusters with k-medoids, using the Partitioning Around Medoids
+/// (PAM) algorithm on their matrix of distances, e.g. as computed by `pdist`. The square matrix of
+/// `pdist` is taken rather than the condensed form of SciPy, so that its output can be passed as is.
+///
+/// The medoids are initialized greedily (the BUILD phase), by repeatedly adding the time series which
+/// decreases the cost the most. Each iteration of the SWAP phase then replaces a medoid with another
+/// time series, choosing the swap which decreases the cost the most, until no swap decreases it or
+/// after `max_iterations` swaps. Unlike k-means, the centroids are time series of the set, so no
+/// averaging under DTW is needed and any `ElasticMeasure` can be used.
+///
+/// As in the original PAM, the distance of each time series to its nearest and second nearest medoid
+/// is kept, so that the cost of a swap is computed in linear time: a time series whose nearest medoid
+/// is replaced moves to the new medoid or to its second nearest one, and the others stay with their
+/// nearest medoid unless the new medoid is nearer. Each iteration thus takes `O(k n^2)` time.
+///
+/// # Panics
+///
+/// If the matrix is not square, or if the number of clusters is 0 or larger than the number of time
+/// series.
+pub fn k_medoids(
+    distances: &ArrayView2<f64>,
+    clusters: usize,
+    max_iterations: usize,
+) -> MedoidClustering {
+    let size = distances.shape()[0];
+    assert_eq!(
+        size,
+        distances.shape()[1],
+        "the distance matrix must be square"
+    );
+    assert!(
+        clusters > 0 && clusters <= size,
+        "the number of clusters must be between 1 and the number of time series"
+    );
+
+    // the distance of each time series to its nearest medoid so far
+    let mut nearest = vec![f64::INFINITY; size];
+    let mut medoids: Vec<usize> = Vec::with_capacity(clusters);
+    while medoids.len() < clusters {
+        let best = (0..size)
+            .filter(|i| !medoids.contains(i))
+            .map(|i| {
+                let cost: f64 = (0..size).map(|j| nearest[j].min(distances[[j, i]])).sum();
+                (i, cost)
+            })
+            .fold((0, f64::INFINITY), |best, (i, cost)| match cost < best.1 {
+                true => (i, cost),
+                false => best,
+            });
+        medoids.push(best.0);
+        for (j, distance) in nearest.iter_mut().enumerate() {
+            *distance = distance.min(distances[[j, best.0]]);
+        }
+    }
+
+    let (mut positions, mut nearest, mut second) = nearest_two_medoids(distances, &medoids);
+    let mut cost: f64 = nearest.iter().sum();
+    for _ in 0..max_iterations {
+        let mut best_swap = None;
+        let mut best_cost = cost;
+        for position in 0..clusters {
+            for i in (0..size).filter(|i| !medoids.contains(i)) {
+                let candidate_cost: f64 = (0..size)
+                    .map(|j| {
+                        let kept = match positions[j] == position {
+                            true => second[j],
+                            false => nearest[j],
+                        };
+                        kept.min(distances[[j, i]])
+                    })
+                    .sum();
+                if candidate_cost < best_cost {
+                    best_swap = Some((position, i));
+                    best_cost = candidate_cost;
+                }
+            }
+        }
+        match best_swap {
+            Some((position, i)) => {
+                medoids[position] = i;
+                (positions, nearest, second) = nearest_two_medoids(distances, &medoids);
+                cost = best_cost;
+            }
+            None => break,
+        }
+    }
+
+    let (labels, cost) = nearest_medoids(distances, &medoids);
+    MedoidClustering {
+        labels,
+        medoids,
+        cost,
+    }
+}
+
+/// A merge of two clusters in a `Dendrogram`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Merge {
+    /// The identifier of the first merged cluster.
+    pub left: usize,
+    /// The identifier of the second merged cluster.
+    pub right: usize,
+    /// The linkage distance between the two merged clusters.
+    pub distance: f64,
+    /// The number of time series in the merged cluster.
+    pub size: usize,
+}
+
+/// The result of an agglomerative hierarchical clustering of `n` time series, as the sequence of
+/// `n - 1` merges. Clusters are identified as in SciPy: the identifiers below `n` are the single time
+/// series, and merge `i` creates the cluster with identifier `n + i`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dendrogram {
+    /// The `n - 1` merges in the order in which they were applied, with non-decreasing distances.
+    pub merges: Vec<Merge>,
+}
+
+impl Dendrogram {
+    /// The number of clustered time series.
+    pub fn leaves(&self) -> usize {
+        self.merges.len() + 1
+    }
+
+    /// Cut the dendrogram into `clusters` clusters, by applying all merges but the last `clusters - 1`.
+    ///
+    /// # Returns
+    ///
+    /// The index of the cluster of each time series. The clusters are numbered in the order of their
+    /// first time series.
+    ///
+    /// # Panics
+    ///
+    /// If the number of clusters is 0 or larger than the number of time series.
+    pub fn labels(&self, clusters: usize) -> Vec<usize> {
+        let size = self.leaves();
+        assert!(
+            clusters > 0 && clusters <= size,
+            "the number of clusters must be between 1 and the number of time series"
+        );
+        // the cluster containing each time series and each merged cluster
+        let mut parents: Vec<usize> = (0..size + self.merges.len()).collect();
+        for (i, merge) in self.merges[..size - clusters].iter().enumerate() {
+            parents[merge.left] = size + i;
+            parents[merge.right] = size + i;
+        }
+        let root = |mut cluster: usize| {
+            while parents[cluster] != cluster {
+                cluster = parents[cluster];
+            }
+            cluster
+        };
+        let mut roots: Vec<usize> = Vec::with_capacity(clusters);
+        (0..size)
+            .map(|i| {
+                let cluster = root(i);
+                match roots.iter().position(|&r| r == cluster) {
+                    Some(label) => label,
+                    None => {
+                        roots.push(cluster);
+                        roots.len() - 1
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Cluster time series with agglomerative hierarchical clustering on their matrix of distances, e.g. as
+/// computed by `pdist`. As for `k_medoids`, the square matrix of `pdist` is taken rather than the
+/// condensed form of SciPy.
+///
+/// Starting with a cluster per time series, the two clusters with the smallest `linkage` distance are
+/// merged repeatedly until a single cluster remains. On equal distances, the pair whose first time
+/// series come first is merged. The linkage distances of a merged cluster are updated with the
+/// Lance-Williams formulas, so that the distances between members are only read once.
+///
+/// # Panics
+///
+/// If the matrix is not square or empty.
+pub fn hierarchical(distances: &ArrayView2<f64>, linkage: Linkage) -> Dendrogram {
+    let size = distances.shape()[0];
+    assert_eq!(
+        size,
+        distances.shape()[1],
+        "the distance matrix must be square"
+    );
+    assert_ne!(size, 0, "there must be at least one time series");
+
+    // the identifier, size and linkage distances of each active cluster, indexed by its position
+    let mut identifiers: Vec<usize> = (0..size).collect();
+    let mut sizes: Vec<usize> = vec![1; size];
+    let mut linkages = distances.to_owned();
+    let mut active: Vec<bool> = vec![true; size];
+    let mut merges = Vec::with_capacity(size - 1);
+    for step in 0..size - 1 {
+        let mut closest: Option<(usize, usize, f64)> = None;
+        for a in (0..size).filter(|&a| active[a]) {
+            for b in (a + 1..size).filter(|&b| active[b]) {
+                let is_closer = match closest {
+                    Some((_, _, distance)) => linkages[[a, b]] < distance,
+                    None => true,
+                };
+                if is_closer {
+                    closest = Some((a, b, linkages[[a, b]]));
+                }
+            }
+        }
+        let (a, b, distance) = closest.expect("at least two clusters are active");
+        for c in (0..size).filter(|&c| active[c] && c != a && c != b) {
+            let updated = match linkage {
+                Linkage::Single => linkages[[a, c]].min(linkages[[b, c]]),
+                Linkage::Complete => linkages[[a, c]].max(linkages[[b, c]]),
+                Linkage::Average => {
+                    (sizes[a] as f64 * linkages[[a, c]] + sizes[b] as f64 * linkages[[b, c]])
+                        / (sizes[a] + sizes[b]) as f64
+                }
+            };
+            linkages[[a, c]] = updated;
+            linkages[[c, a]] = updated;
+        }
+        let (left, right) = (identifiers[a], identifiers[b]);
+        merges.push(Merge {
+            left: left.min(right),
+            right: left.max(right),
+            distance,
+            size: sizes[a] + sizes[b],
+        });
+        // the merged cluster takes the position of the first one
+        identifiers[a] = size + step;
+        sizes[a] += sizes[b];
+        active[b] = false;
+    }
+    Dendrogram { merges }
+}
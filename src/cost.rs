@@ -1,48 +1,80 @@
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, ArrayView2};
 use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
 
-use crate::enums::Action;
+use crate::enums::{Action, BoundaryPolicy, StorageBackend, StorageStrategy};
+use crate::float::DtwFloat;
 
-pub trait CostStorage {
-    fn get_cost(&self, row: usize, column: usize) -> f64;
-    fn set_cost(&mut self, row: usize, column: usize, cost: f64);
-    fn get_action(&self, row: usize, column: usize) -> Action;
+pub trait CostStorage<F: DtwFloat = f64> {
+    /// Get the accumulated cost of a cell. The cells of the virtual first row and first column (index
+    /// 0) have the cost given by the `BoundaryPolicy` of the storage.
+    fn get_cost(&self, row: usize, column: usize) -> F;
+    fn set_cost(&mut self, row: usize, column: usize, cost: F);
+    /// Get the action of a cell, or `None` if the cell was not computed.
+    fn get_action(&self, row: usize, column: usize) -> Option<Action>;
     fn set_action(&mut self, row: usize, column: usize, action: Action);
 }
 
-pub struct CostMatrix {
-    cost_matrix: Array2<f64>,
-    actions_matrix: Array2<Action>,
+pub struct CostMatrix<F: DtwFloat = f64> {
+    cost_matrix: Array2<F>,
+    actions_matrix: Array2<Option<Action>>,
+    boundary_policy: BoundaryPolicy,
 }
 
-impl CostMatrix {
+impl<F: DtwFloat> CostMatrix<F> {
     pub fn new(rows: usize, columns: usize) -> Self {
+        Self::with_boundary(rows, columns, BoundaryPolicy::Origin)
+    }
+
+    /// Create a cost matrix whose virtual first row and column follow `boundary_policy`.
+    pub fn with_boundary(rows: usize, columns: usize, boundary_policy: BoundaryPolicy) -> Self {
         let mut cost_matrix = Self {
-            cost_matrix: Array2::<f64>::from_elem((rows, columns), f64::INFINITY),
-            actions_matrix: Array2::<Action>::default((rows, columns)),
+            cost_matrix: Array2::<F>::from_elem((rows, columns), F::infinity()),
+            actions_matrix: Array2::<Option<Action>>::default((rows, columns)),
+            boundary_policy,
         };
-        cost_matrix.cost_matrix[[0, 0]] = 0f64;
+        cost_matrix.cost_matrix[[0, 0]] = F::zero();
         cost_matrix
     }
+
+    /// The number of rows in the cost matrix (the size of the y time series).
+    pub fn rows(&self) -> usize {
+        self.cost_matrix.shape()[0]
+    }
+
+    /// The number of columns in the cost matrix (the size of the x time series).
+    pub fn columns(&self) -> usize {
+        self.cost_matrix.shape()[1]
+    }
+
+    /// The accumulated costs, without the virtual first row and column, i.e. element `[i, j]` holds the
+    /// cost of the 1 based cell `(i + 1, j + 1)`.
+    pub fn costs(&self) -> ArrayView2<'_, F> {
+        self.cost_matrix.view()
+    }
+
+    /// The actions, indexed like the costs returned by `costs`.
+    pub fn actions(&self) -> ArrayView2<'_, Option<Action>> {
+        self.actions_matrix.view()
+    }
 }
 
-impl CostStorage for CostMatrix {
-    fn get_cost(&self, row: usize, column: usize) -> f64 {
-        if row == 0 && column == 0 {
-            return 0f64;
-        } else if row == 0 || column == 0 {
-            return f64::INFINITY;
+impl<F: DtwFloat> CostStorage<F> for CostMatrix<F> {
+    fn get_cost(&self, row: usize, column: usize) -> F {
+        if row == 0 || column == 0 {
+            return boundary_cost(self.boundary_policy, row, column);
         }
         self.cost_matrix[[row - 1, column - 1]]
     }
 
-    fn set_cost(&mut self, row: usize, column: usize, cost: f64) {
+    fn set_cost(&mut self, row: usize, column: usize, cost: F) {
         assert_ne!(row, 0);
         assert_ne!(column, 0);
         self.cost_matrix[[row - 1, column - 1]] = cost;
     }
 
-    fn get_action(&self, row: usize, column: usize) -> Action {
+    fn get_action(&self, row: usize, column: usize) -> Option<Action> {
         assert_ne!(row, 0);
         assert_ne!(column, 0);
         self.actions_matrix[[row - 1, column - 1]]
@@ -51,46 +83,51 @@ impl CostStorage for CostMatrix {
     fn set_action(&mut self, row: usize, column: usize, action: Action) {
         assert_ne!(row, 0);
         assert_ne!(column, 0);
-        self.actions_matrix[[row - 1, column - 1]] = action;
+        self.actions_matrix[[row - 1, column - 1]] = Some(action);
     }
 }
 
-pub struct CostCache {
-    cost_cache: Array1<HashMap<usize, f64>>,
+pub struct CostCache<F: DtwFloat = f64> {
+    cost_cache: Array1<HashMap<usize, F>>,
     actions_cache: Array1<HashMap<usize, Action>>,
+    boundary_policy: BoundaryPolicy,
 }
 
-impl CostCache {
+impl<F: DtwFloat> CostCache<F> {
     pub fn new(rows: usize) -> Self {
+        Self::with_boundary(rows, BoundaryPolicy::Origin)
+    }
+
+    /// Create a cost cache whose virtual first row and column follow `boundary_policy`.
+    pub fn with_boundary(rows: usize, boundary_policy: BoundaryPolicy) -> Self {
         Self {
-            cost_cache: Array1::<HashMap<usize, f64>>::default(rows),
+            cost_cache: Array1::<HashMap<usize, F>>::default(rows),
             actions_cache: Array1::<HashMap<usize, Action>>::default(rows),
+            boundary_policy,
         }
     }
 }
 
-impl CostStorage for CostCache {
-    fn get_cost(&self, row: usize, column: usize) -> f64 {
-        if row == 0 && column == 0 {
-            return 0f64;
-        } else if row == 0 || column == 0 {
-            return f64::INFINITY;
+impl<F: DtwFloat> CostStorage<F> for CostCache<F> {
+    fn get_cost(&self, row: usize, column: usize) -> F {
+        if row == 0 || column == 0 {
+            return boundary_cost(self.boundary_policy, row, column);
         }
         *self.cost_cache[row - 1]
             .get(&(column - 1))
-            .unwrap_or(&f64::INFINITY)
+            .unwrap_or(&F::infinity())
     }
 
-    fn set_cost(&mut self, row: usize, column: usize, cost: f64) {
+    fn set_cost(&mut self, row: usize, column: usize, cost: F) {
         assert_ne!(row, 0);
         assert_ne!(column, 0);
         self.cost_cache[row - 1].insert(column - 1, cost);
     }
 
-    fn get_action(&self, row: usize, column: usize) -> Action {
+    fn get_action(&self, row: usize, column: usize) -> Option<Action> {
         assert_ne!(row, 0);
         assert_ne!(column, 0);
-        self.actions_cache[row - 1][&(column - 1)]
+        self.actions_cache[row - 1].get(&(column - 1)).copied()
     }
 
     fn set_action(&mut self, row: usize, column: usize, action: Action) {
@@ -100,6 +137,111 @@ impl CostStorage for CostCache {
     }
 }
 
+/// Cost storage for windows which visit a contiguous range of columns on each row, such as the windows
+/// of FastDTW or of a Sakoe-Chiba band.
+///
+/// Each row holds the costs and actions from the first column set on that row up to the last one, so
+/// the memory is proportional to the width of the window instead of the size of the cost matrix. Cells
+/// outside the stored range of a row have an infinite cost, and gaps within the range are filled as
+/// uncomputed cells.
+pub struct CostBand<F: DtwFloat = f64> {
+    /// The 1 based column of the first stored cell of each row.
+    start_columns: Vec<usize>,
+    cost_band: Vec<Vec<F>>,
+    actions_band: Vec<Vec<Option<Action>>>,
+    boundary_policy: BoundaryPolicy,
+}
+
+impl<F: DtwFloat> CostBand<F> {
+    pub fn new(rows: usize) -> Self {
+        Self::with_boundary(rows, BoundaryPolicy::Origin)
+    }
+
+    /// Create a cost band whose virtual first row and column follow `boundary_policy`.
+    pub fn with_boundary(rows: usize, boundary_policy: BoundaryPolicy) -> Self {
+        Self {
+            start_columns: vec![0; rows],
+            cost_band: vec![Vec::new(); rows],
+            actions_band: vec![Vec::new(); rows],
+            boundary_policy,
+        }
+    }
+
+    /// Get the index of a cell within the stored range of its row, growing the range if needed.
+    ///
+    /// Since windows visit the columns of a row in increasing order, the range only grows to the right.
+    fn band_index(&mut self, row: usize, column: usize) -> usize {
+        if self.cost_band[row - 1].is_empty() {
+            self.start_columns[row - 1] = column;
+        }
+        let start_column = self.start_columns[row - 1];
+        assert!(
+            column >= start_column,
+            "the columns of a row must be visited in increasing order"
+        );
+        let index = column - start_column;
+        if index >= self.cost_band[row - 1].len() {
+            self.cost_band[row - 1].resize(index + 1, F::infinity());
+            self.actions_band[row - 1].resize(index + 1, None);
+        }
+        index
+    }
+
+    /// Get the index of a cell within the stored range of its row, or `None` if it is outside of it.
+    fn stored_index(&self, row: usize, column: usize) -> Option<usize> {
+        let start_column = self.start_columns[row - 1];
+        match column >= start_column && column - start_column < self.cost_band[row - 1].len() {
+            true => Some(column - start_column),
+            false => None,
+        }
+    }
+}
+
+impl<F: DtwFloat> CostStorage<F> for CostBand<F> {
+    fn get_cost(&self, row: usize, column: usize) -> F {
+        if row == 0 || column == 0 {
+            return boundary_cost(self.boundary_policy, row, column);
+        }
+        match self.stored_index(row, column) {
+            Some(index) => self.cost_band[row - 1][index],
+            None => F::infinity(),
+        }
+    }
+
+    fn set_cost(&mut self, row: usize, column: usize, cost: F) {
+        assert_ne!(row, 0);
+        assert_ne!(column, 0);
+        let index = self.band_index(row, column);
+        self.cost_band[row - 1][index] = cost;
+    }
+
+    fn get_action(&self, row: usize, column: usize) -> Option<Action> {
+        assert_ne!(row, 0);
+        assert_ne!(column, 0);
+        self.stored_index(row, column)
+            .and_then(|index| self.actions_band[row - 1][index])
+    }
+
+    fn set_action(&mut self, row: usize, column: usize, action: Action) {
+        assert_ne!(row, 0);
+        assert_ne!(column, 0);
+        let index = self.band_index(row, column);
+        self.actions_band[row - 1][index] = Some(action);
+    }
+}
+
+/// The accumulated cost of a cell on the virtual first row or first column of the cost matrix.
+fn boundary_cost<F: DtwFloat>(boundary_policy: BoundaryPolicy, row: usize, column: usize) -> F {
+    match (boundary_policy, row, column) {
+        (_, 0, 0) => F::zero(),
+        (BoundaryPolicy::FreeFirstRow, 0, _) => F::zero(),
+        (BoundaryPolicy::FreeFirstColumn, _, 0) => F::zero(),
+        (BoundaryPolicy::Relaxed(psi), 0, column) if column <= psi => F::zero(),
+        (BoundaryPolicy::Relaxed(psi), row, 0) if row <= psi => F::zero(),
+        _ => F::infinity(),
+    }
+}
+
 static mut MAX_COST_STORAGE_MATRIX: usize = 32 * 1024 * 1024 * 1024;
 
 pub fn config_max_cost_storage_matrix(max: usize) {
@@ -108,9 +250,162 @@ pub fn config_max_cost_storage_matrix(max: usize) {
     }
 }
 
-pub(crate) fn cost_storage(rows: usize, columns: usize) -> Box<dyn CostStorage> {
-    if unsafe { rows * columns * std::mem::size_of::<f64>() < MAX_COST_STORAGE_MATRIX } {
-        return Box::new(CostMatrix::new(rows, columns));
+/// Estimated memory requirements of a DTW run, as computed by `estimate_memory`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// An upper bound of the number of bytes needed by a dense `CostMatrix` (costs and actions) for the
+    /// given sizes.
+    pub dense_bytes: usize,
+    /// The backend which `cost_storage` would select given the current threshold.
+    pub backend: StorageBackend,
+}
+
+/// Estimate the memory needed to run DTW on two time series of the given sizes, without allocating
+/// anything.
+///
+/// # Arguments
+///
+/// * `x_size` - the number of samples in the x time series (the number of columns)
+///
+/// * `y_size` - the number of samples in the y time series (the number of rows)
+///
+/// # Returns
+///
+/// A `MemoryEstimate` holding the number of bytes which the dense `CostMatrix` would allocate and the
+/// backend which would actually be selected for these sizes. The estimate counts the size of an `f64`
+/// per cell for the costs and another one for the actions, i.e. `rows * columns * size_of::<f64>() * 2`,
+/// which is an upper bound since the actions are stored more compactly.
+pub fn estimate_memory(x_size: usize, y_size: usize) -> MemoryEstimate {
+    let cells = y_size.saturating_mul(x_size);
+    MemoryEstimate {
+        dense_bytes: cells.saturating_mul(std::mem::size_of::<f64>() * 2),
+        backend: select_backend::<f64>(y_size, x_size),
+    }
+}
+
+fn select_backend<F: DtwFloat>(rows: usize, columns: usize) -> StorageBackend {
+    let matrix_bytes = rows
+        .saturating_mul(columns)
+        .saturating_mul(std::mem::size_of::<F>());
+    if unsafe { matrix_bytes < MAX_COST_STORAGE_MATRIX } {
+        return StorageBackend::Matrix;
+    }
+    StorageBackend::Cache
+}
+
+/// The backend chosen for a `StorageStrategy`. Matching on the variants gives the concrete type of the
+/// backend, so that the loops accumulating the costs are monomorphized for each backend and the dense
+/// matrix accesses can be inlined, instead of going through virtual calls on a trait object.
+pub(crate) enum SelectedStorage<F: DtwFloat> {
+    Dense(CostMatrix<F>),
+    Cache(CostCache<F>),
+    Banded(CostBand<F>),
+}
+
+pub(crate) fn cost_storage<F: DtwFloat>(
+    rows: usize,
+    columns: usize,
+    storage_strategy: StorageStrategy,
+    boundary_policy: BoundaryPolicy,
+) -> SelectedStorage<F> {
+    let dense =
+        || SelectedStorage::Dense(CostMatrix::with_boundary(rows, columns, boundary_policy));
+    let cache = || SelectedStorage::Cache(CostCache::with_boundary(rows, boundary_policy));
+    match storage_strategy {
+        StorageStrategy::Auto => match select_backend::<F>(rows, columns) {
+            StorageBackend::Matrix => dense(),
+            StorageBackend::Cache => cache(),
+        },
+        StorageStrategy::Dense => dense(),
+        StorageStrategy::Cache => cache(),
+        StorageStrategy::Banded => {
+            SelectedStorage::Banded(CostBand::with_boundary(rows, boundary_policy))
+        }
+    }
+}
+
+fn action_to_byte(action: Option<Action>) -> u8 {
+    match action {
+        None => 0,
+        Some(Action::Inserted) => 1,
+        Some(Action::Deleted) => 2,
+        Some(Action::Matched) => 3,
+    }
+}
+
+fn byte_to_action(byte: u8) -> std::io::Result<Option<Action>> {
+    match byte {
+        0 => Ok(None),
+        1 => Ok(Some(Action::Inserted)),
+        2 => Ok(Some(Action::Deleted)),
+        3 => Ok(Some(Action::Matched)),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid action value {}", byte),
+        )),
+    }
+}
+
+/// Dump the costs and actions of a `CostMatrix` to a binary file, for offline debugging.
+///
+/// The format is a header with the number of rows and the number of columns as little endian `u64`,
+/// followed by the costs as little endian `f64` and then the actions as `u8` (0 for a cell which was
+/// not computed, 1 for insertion, 2 for deletion and 3 for matching), both in row major order.
+pub fn dump_matrices(storage: &CostMatrix, path: &Path) -> std::io::Result<()> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writer.write_all(&(storage.rows() as u64).to_le_bytes())?;
+    writer.write_all(&(storage.columns() as u64).to_le_bytes())?;
+    for cost in storage.costs().iter() {
+        writer.write_all(&cost.to_le_bytes())?;
+    }
+    for &action in storage.actions().iter() {
+        writer.write_all(&[action_to_byte(action)])?;
+    }
+    writer.flush()
+}
+
+/// Load a `CostMatrix` from a file written by `dump_matrices`.
+///
+/// # Returns
+///
+/// The cost matrix, or an error of kind `std::io::ErrorKind::InvalidData` if the sizes in the header
+/// do not match the length of the file or an action value is invalid.
+pub fn load_matrices(path: &Path) -> std::io::Result<CostMatrix> {
+    let file = std::fs::File::open(path)?;
+    let file_length = file.metadata()?.len();
+    let mut reader = std::io::BufReader::new(file);
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    let rows = u64::from_le_bytes(buffer);
+    reader.read_exact(&mut buffer)?;
+    let columns = u64::from_le_bytes(buffer);
+
+    // validate the header before allocating, a corrupt header must not lead to a huge allocation
+    let expected_length = rows
+        .checked_mul(columns)
+        .filter(|&cells| cells > 0)
+        .and_then(|cells| cells.checked_mul((std::mem::size_of::<f64>() + 1) as u64))
+        .and_then(|bytes| bytes.checked_add(2 * std::mem::size_of::<u64>() as u64));
+    if expected_length != Some(file_length) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "a {} x {} matrix does not match the file length of {} bytes",
+                rows, columns, file_length
+            ),
+        ));
+    }
+    let (rows, columns) = (rows as usize, columns as usize);
+
+    let mut storage = CostMatrix::new(rows, columns);
+    for cost in storage.cost_matrix.iter_mut() {
+        reader.read_exact(&mut buffer)?;
+        *cost = f64::from_le_bytes(buffer);
+    }
+    let mut actions = vec![0u8; rows * columns];
+    reader.read_exact(&mut actions)?;
+    for (action, &byte) in storage.actions_matrix.iter_mut().zip(&actions) {
+        *action = byte_to_action(byte)?;
     }
-    Box::new(CostCache::new(rows))
+    Ok(storage)
 }
@@ -0,0 +1,81 @@
+use ndarray::{s, ArrayView1};
+
+use crate::bounds::CascadingBounds;
+use crate::enums::*;
+
+/// The most unusual subsequence of a time series, found by `discord`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Discord {
+    /// The index of the first sample of the discord.
+    pub location: usize,
+    /// The index of the first sample of the nearest non overlapping neighbor of the discord.
+    pub neighbor: usize,
+    /// The DTW distance between the discord and its nearest neighbor.
+    pub distance: f64,
+}
+
+/// Find the discord of a time series: the subsequence of `length` samples whose DTW distance to its
+/// nearest non overlapping subsequence is the largest, under DTW constrained to a Sakoe-Chiba band of
+/// `band` samples.
+///
+/// The overlapping subsequences are trivial matches and are excluded from the neighbors. The nearest
+/// neighbor of each subsequence is searched with its `CascadingBounds`, which discard the neighbors
+/// which cannot be nearer than the nearest one so far. The search for a subsequence also stops as soon
+/// as a neighbor is nearer than the distance of the best discord so far, since the subsequence cannot
+/// be the discord then. On equal distances, the earlier subsequence and the earlier neighbor are kept.
+///
+/// # Returns
+///
+/// The discord, or `None` if no subsequence has a non overlapping neighbor, i.e. if the time series has
+/// less than `2 * length` samples.
+///
+/// # Panics
+///
+/// If `length` is 0.
+pub fn discord(
+    series: &ArrayView1<f64>,
+    length: usize,
+    band: usize,
+    distance_mode: DistanceMode,
+) -> Option<Discord> {
+    assert_ne!(length, 0, "the subsequences must not be empty");
+    let size = series.shape()[0];
+    if size < 2 * length {
+        return None;
+    }
+
+    let mut best: Option<Discord> = None;
+    for location in 0..=size - length {
+        let bounds = CascadingBounds::new(
+            &series.slice(s![location..location + length]),
+            band,
+            distance_mode,
+        );
+        let best_distance = best.map_or(f64::NEG_INFINITY, |discord| discord.distance);
+        let mut nearest: Option<(usize, f64)> = None;
+        for neighbor in (0..=size - length).filter(|&j| j.abs_diff(location) >= length) {
+            let nearest_so_far = nearest.map_or(f64::INFINITY, |(_, distance)| distance);
+            let candidate = series.slice(s![neighbor..neighbor + length]);
+            if let Some(distance) = bounds.distance(&candidate, nearest_so_far) {
+                if distance < nearest_so_far {
+                    nearest = Some((neighbor, distance));
+                }
+            }
+            // the subsequence is not the discord once a neighbor is nearer than the best discord
+            if nearest.is_some_and(|(_, distance)| distance <= best_distance) {
+                break;
+            }
+        }
+        match nearest {
+            Some((neighbor, distance)) if distance > best_distance => {
+                best = Some(Discord {
+                    location,
+                    neighbor,
+                    distance,
+                })
+            }
+            _ => {}
+        }
+    }
+    best
+}
@@ -0,0 +1,154 @@
+use ndarray::{Array1, Array2, ArrayView1};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::error::DtwError;
+use crate::measure::ElasticMeasure;
+
+/// Compute the distance of each pair of time series, in parallel with the `parallel` feature.
+fn pair_distances<M>(
+    measure: &M,
+    pairs: &[(usize, usize)],
+    a: &[ArrayView1<f64>],
+    b: &[ArrayView1<f64>],
+) -> Result<Vec<f64>, DtwError>
+where
+    M: ElasticMeasure + Sync,
+{
+    #[cfg(feature = "parallel")]
+    let pairs = pairs.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let pairs = pairs.iter();
+    pairs
+        .map(|&(i, j)| measure.distance(&a[i], &b[j]))
+        .collect()
+}
+
+/// Compute the distances between all pairs of time series in `series`.
+///
+/// Since the distance of an elastic measure is symmetric and zero for identical time series, only the
+/// pairs above the diagonal are computed. With the `parallel` feature, the pairs are distributed over the
+/// threads of the rayon thread pool.
+///
+/// # Returns
+///
+/// The symmetric `n x n` matrix of distances, where `n` is the number of time series, with element
+/// `[i, j]` holding the distance between `series[i]` and `series[j]`, or the first error of `measure`.
+pub fn pdist<M>(measure: &M, series: &[ArrayView1<f64>]) -> Result<Array2<f64>, DtwError>
+where
+    M: ElasticMeasure + Sync,
+{
+    let size = series.len();
+    let pairs: Vec<(usize, usize)> = (0..size)
+        .flat_map(|i| (i + 1..size).map(move |j| (i, j)))
+        .collect();
+    let distances = pair_distances(measure, &pairs, series, series)?;
+
+    let mut matrix = Array2::<f64>::zeros((size, size));
+    for (&(i, j), distance) in pairs.iter().zip(distances) {
+        matrix[[i, j]] = distance;
+        matrix[[j, i]] = distance;
+    }
+    Ok(matrix)
+}
+
+/// Compute the distances between each time series of `a` and each time series of `b`. With the
+/// `parallel` feature, the pairs are distributed over the threads of the rayon thread pool.
+///
+/// # Returns
+///
+/// The `a.len() x b.len()` matrix of distances, with element `[i, j]` holding the distance between
+/// `a[i]` and `b[j]`, or the first error of `measure`.
+pub fn cdist<M>(
+    measure: &M,
+    a: &[ArrayView1<f64>],
+    b: &[ArrayView1<f64>],
+) -> Result<Array2<f64>, DtwError>
+where
+    M: ElasticMeasure + Sync,
+{
+    let pairs: Vec<(usize, usize)> = (0..a.len())
+        .flat_map(|i| (0..b.len()).map(move |j| (i, j)))
+        .collect();
+    let distances = pair_distances(measure, &pairs, a, b)?;
+    Ok(Array2::from_shape_vec((a.len(), b.len()), distances).expect("one distance per pair"))
+}
+
+/// The matrix of distances between a growing set of time series, e.g. for online clustering.
+///
+/// Adding a time series only computes its distances to the time series added before, instead of
+/// recomputing the whole matrix with `pdist`. The distances are stored as a lower triangular matrix
+/// without the diagonal, i.e. the row of each time series holds its distances to the earlier ones.
+pub struct GrowableDistanceMatrix<M> {
+    measure: M,
+    series: Vec<Array1<f64>>,
+    distances: Vec<f64>,
+}
+
+impl<M> GrowableDistanceMatrix<M>
+where
+    M: ElasticMeasure + Sync,
+{
+    /// Create an empty matrix of the distances of `measure`.
+    pub fn new(measure: M) -> Self {
+        Self {
+            measure,
+            series: Vec::new(),
+            distances: Vec::new(),
+        }
+    }
+
+    /// Add a time series, computing its distances to all time series added before. With the `parallel`
+    /// feature, the distances are computed on the threads of the rayon thread pool.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new time series, or `DtwError::EmptyTimeSeries` if it has no samples, or the
+    /// first other error of the measure. The time series is not added on errors.
+    pub fn add(&mut self, ts: &ArrayView1<f64>) -> Result<usize, DtwError> {
+        if ts.is_empty() {
+            return Err(DtwError::EmptyTimeSeries);
+        }
+        let index = self.series.len();
+        let members: Vec<ArrayView1<f64>> = self.series.iter().map(|ts| ts.view()).collect();
+        // the earlier time series comes first, as in pdist
+        let pairs: Vec<(usize, usize)> = (0..index).map(|j| (j, 0)).collect();
+        let distances = pair_distances(&self.measure, &pairs, &members, &[ts.view()])?;
+        self.distances.extend(distances);
+        self.series.push(ts.to_owned());
+        Ok(index)
+    }
+
+    /// The number of time series added.
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    /// Whether no time series was added yet.
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    /// The distance between the time series of index `i` and `j`, which is zero if they are equal.
+    ///
+    /// # Panics
+    ///
+    /// If either index is not smaller than `len`.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        assert!(
+            i < self.len() && j < self.len(),
+            "the indices must be smaller than the number of time series"
+        );
+        let (row, column) = (i.max(j), i.min(j));
+        match row == column {
+            true => 0f64,
+            false => self.distances[row * (row - 1) / 2 + column],
+        }
+    }
+
+    /// The symmetric `n x n` matrix of distances, as computed by `pdist` over the time series in the
+    /// order in which they were added.
+    pub fn matrix(&self) -> Array2<f64> {
+        Array2::from_shape_fn((self.len(), self.len()), |(i, j)| self.get(i, j))
+    }
+}
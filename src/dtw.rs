@@ -1,9 +1,16 @@
-use ndarray::{s, Array1, ArrayView1};
+use ndarray::{s, Array1, Array2, ArrayView1, ArrayView2};
 
-use crate::cost;
+use crate::cost::{self, CostMatrix, CostStorage, SelectedStorage};
 use crate::enums::*;
+use crate::error::DtwError;
+use crate::float::DtwFloat;
 use crate::window::*;
 
+/// The result of a DTW run: the distance between the two time series and the warp path, given as
+/// `(row, column)` pairs of 0 based indices into the y and x time series respectively. The distance
+/// has the float type of the cost storage, which is `f64` unless requested otherwise.
+pub type DtwResult<F = f64> = (F, Array1<(usize, usize)>);
+
 /// Find the minimum of the three adjacent cells at each step of the cost matrix calculation.
 ///
 /// # Arguments
@@ -16,10 +23,10 @@ use crate::window::*;
 ///
 /// # Returns
 ///
-/// The return value is a tuple with two elements. The first element is an f64 denoting the minimum
+/// The return value is a tuple with two elements. The first element is a float denoting the minimum
 /// value of the three arguments. The second element is of type `Action` and denotes the specific
 /// element that was chosen.
-fn minimum(i: f64, d: f64, m: f64) -> (f64, Action) {
+pub(crate) fn minimum<F: DtwFloat>(i: F, d: F, m: F) -> (F, Action) {
     if i < d {
         if i < m {
             return (i, Action::Inserted);
@@ -30,10 +37,180 @@ fn minimum(i: f64, d: f64, m: f64) -> (f64, Action) {
     (m, Action::Matched)
 }
 
-pub fn dtw<T>(x: &ArrayView1<T>, y: &ArrayView1<T>) -> (f64, Array1<(usize, usize)>)
+/// Find the soft minimum of the three adjacent cells, `-gamma * ln(sum(exp(-v / gamma)))`, which
+/// smooths `minimum` into a differentiable function. The soft minimum is at most the minimum, and
+/// tends to it as `gamma` tends to 0. A `gamma` of 0 gives the minimum itself.
+pub(crate) fn soft_minimum<F: DtwFloat>(i: F, d: F, m: F, gamma: F) -> F {
+    let (hard, _) = minimum(i, d, m);
+    if gamma == F::zero() || hard == F::infinity() {
+        return hard;
+    }
+    // shift by the minimum so that the largest exponent is 0
+    let sum = [i, d, m]
+        .into_iter()
+        .map(|v| (-(v - hard) / gamma).exp())
+        .sum::<F>();
+    hard - gamma * sum.ln()
+}
+
+/// Compute the local cost between two samples, i.e. the value of a single cell before accumulation.
+pub(crate) fn local_cost<F: DtwFloat>(a: F, b: F, distance_mode: DistanceMode) -> F {
+    match distance_mode {
+        DistanceMode::Manhattan | DistanceMode::Chebyshev => (a - b).abs(),
+        DistanceMode::Euclidean | DistanceMode::SquaredEuclidean => {
+            let difference = a - b;
+            difference * difference
+        }
+        DistanceMode::Canberra => match a.abs() + b.abs() {
+            sum if sum == F::zero() => F::zero(),
+            sum => (a - b).abs() / sum,
+        },
+        DistanceMode::Minkowski(p) => (a - b).abs().powf(minkowski_power(p)),
+    }
+}
+
+/// Combine the local costs of the features of multivariate samples into the local cost of the samples:
+/// their largest value for the Chebyshev mode, and their sum otherwise.
+pub(crate) fn combined_cost<I: Iterator<Item = f64>>(costs: I, distance_mode: DistanceMode) -> f64 {
+    match distance_mode {
+        DistanceMode::Chebyshev => costs.fold(0f64, f64::max),
+        _ => costs.sum(),
+    }
+}
+
+/// Convert the power of the Minkowski mode to the float type of the cost matrix.
+fn minkowski_power<F: DtwFloat>(p: f64) -> F {
+    F::from(p).expect("the Minkowski power is representable as a float")
+}
+
+/// Convert the accumulated cost of the last cell in the cost matrix into the DTW distance.
+pub(crate) fn final_distance<F: DtwFloat>(cost: F, distance_mode: DistanceMode) -> F {
+    match distance_mode {
+        DistanceMode::Manhattan
+        | DistanceMode::SquaredEuclidean
+        | DistanceMode::Chebyshev
+        | DistanceMode::Canberra => cost,
+        DistanceMode::Euclidean => cost.sqrt(),
+        DistanceMode::Minkowski(p) => cost.powf(minkowski_power::<F>(p).recip()),
+    }
+}
+
+/// Generate the warp path by backtracking the actions from the last cell of the cost matrix.
+///
+/// # Arguments
+///
+/// * `rows` - the number of rows in the cost matrix (the size of the y time series)
+///
+/// * `columns` - the number of columns in the cost matrix (the size of the x time series)
+///
+/// * `get_action` - returns the action stored for a cell, using 1 based indices, or `None` if the cell
+///   was not computed
+///
+/// The backtracking stops once it reaches the virtual first row or first column. With the default
+/// `BoundaryPolicy::Origin`, the last cell must be reachable from the origin of the cost matrix, i.e.
+/// have a finite cost. In this case, each step of the backtracking moves to a cell with a finite cost
+/// as well, and the path always ends in the origin. With the other policies, it may end on any boundary
+/// cell with a zero cost.
+///
+/// # Returns
+///
+/// The warp path as 0 based indices of the time series samples, ordered from the first to the last
+/// aligned pair, or `DtwError::UncomputedCell` if the backtracking reached a cell without an action.
+pub(crate) fn warp_path<F>(
+    rows: usize,
+    columns: usize,
+    get_action: F,
+) -> Result<Array1<(usize, usize)>, DtwError>
+where
+    F: Fn(usize, usize) -> Option<Action>,
+{
+    /* the path is allocated as a rows + columns array for the worst case scenario
+     * afterwards, the path is truncated based on the actual number of elements
+     */
+    let mut path = Array1::<(usize, usize)>::default(rows + columns);
+    let mut path_len = 0;
+    let mut row = rows;
+    let mut column = columns;
+    while row != 0 && column != 0 {
+        /* the search window and cost matrix use the first row and first column themselves
+         * the results are thus 1 based indices of the time series samples, and not 0 based
+         * in order to convert back to 0 based indices, we subtract 1 below
+         */
+        path[path_len] = (row - 1, column - 1);
+        (row, column) = match get_action(row, column) {
+            Some(Action::Inserted) => (row - 1, column),
+            Some(Action::Deleted) => (row, column - 1),
+            Some(Action::Matched) => (row - 1, column - 1),
+            None => return Err(DtwError::UncomputedCell { row, column }),
+        };
+        path_len += 1;
+    }
+    Ok(path.slice_move(s![..path_len;-1]))
+}
+
+/// Generate the warp path by backtracking the actions stored in a cost storage, starting from the cell
+/// `(rows, columns)` (1 based indices) and ending on the virtual first row or first column, see
+/// `warp_path`.
+///
+/// # Returns
+///
+/// The warp path as 0 based indices of the time series samples, or `DtwError::UncomputedCell` if the
+/// backtracking reached a cell which was not computed.
+pub fn backtrack<S, F>(
+    cost_storage: &S,
+    rows: usize,
+    columns: usize,
+) -> Result<Array1<(usize, usize)>, DtwError>
+where
+    S: cost::CostStorage<F> + ?Sized,
+    F: DtwFloat,
+{
+    warp_path(rows, columns, |row, column| {
+        cost_storage.get_action(row, column)
+    })
+}
+
+/// Compute the DTW over the full window in closed form when one of the time series has a single
+/// sample, since all samples of the other time series are then aligned to it.
+///
+/// # Returns
+///
+/// The same result as `dtw_ex` with the full window, or `None` if both time series have more than one
+/// sample (or either is empty).
+pub(crate) fn dtw_single_sample<T, F>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Option<DtwResult<F>>
+where
+    T: std::marker::Copy + std::convert::Into<F>,
+    F: DtwFloat,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    /* a single row is only reached by deletions from the origin and a single column by insertions
+     * the costs are summed in the same order as the accumulation, so the result is identical
+     */
+    let path = match (rows, columns) {
+        (0, _) | (_, 0) => return None,
+        (1, _) => Array1::from_iter((0..columns).map(|column| (0, column))),
+        (_, 1) => Array1::from_iter((0..rows).map(|row| (row, 0))),
+        _ => return None,
+    };
+    let cost = path.iter().fold(F::zero(), |cost, &(row, column)| {
+        local_cost(x[column].into(), y[row].into(), distance_mode) + cost
+    });
+    Some((final_distance(cost, distance_mode), path))
+}
+
+pub fn dtw<T>(x: &ArrayView1<T>, y: &ArrayView1<T>) -> Result<DtwResult, DtwError>
 where
     T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
 {
+    if let Some(result) = dtw_single_sample(x, y, DistanceMode::Euclidean) {
+        return Ok(result);
+    }
+
     let rows = y.shape()[0];
     let columns = x.shape()[0];
     dtw_ex(
@@ -41,31 +218,296 @@ where
         y,
         FullWindow::new(rows, columns),
         DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
     )
 }
 
+/// Run DTW on the cells of the cost matrix visited by `window`.
+///
+/// The window yields `(row, column)` cells as 1 based indices, row by row and with increasing columns
+/// on each row. If the cells of the window do not connect the last cell of the cost matrix to its
+/// origin, the distance is infinite and the warp path is empty.
+///
+/// The cost and action values are stored in the backend chosen by `storage_strategy`. All strategies
+/// lead to the same result; `StorageStrategy::Banded` requires that the window visits the columns of
+/// each row in increasing order, which holds for all windows of this crate.
+///
+/// For robustness against outliers, a sample of `y` which differs from the sample of `x` by more than
+/// `clamp_delta` is moved toward it until their difference is `clamp_delta` before computing the local
+/// cost, so that a single spike can add at most the cost of a difference of `clamp_delta` per cell. A
+/// `clamp_delta` of `f64::INFINITY` gives the standard DTW.
+///
+/// The costs are computed and stored as `f64`; see `dtw_ex_in` for other float types.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::EmptyTimeSeries` if either time series has no
+/// samples and `DtwError::MalformedWindow` if the window visits a cell outside of the cost matrix.
 pub fn dtw_ex<T, W>(
     x: &ArrayView1<T>,
     y: &ArrayView1<T>,
     window: W,
     distance_mode: DistanceMode,
-) -> (f64, Array1<(usize, usize)>)
+    storage_strategy: StorageStrategy,
+    clamp_delta: f64,
+) -> Result<DtwResult, DtwError>
 where
     T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
     W: Iterator<Item = (usize, usize)>,
+{
+    dtw_ex_in(x, y, window, distance_mode, storage_strategy, clamp_delta)
+}
+
+/// Run `dtw_ex` with the costs computed and stored with the float type `F`, e.g.
+/// `dtw_ex_in::<f32, _, _>` halves the memory of the cost storage, at the expense of precision in the
+/// accumulated costs of long time series. A `clamp_delta` beyond the range of `F` does not clamp.
+///
+/// # Returns
+///
+/// The distance and the warp path with the float type `F`, or the errors of `dtw_ex`.
+pub fn dtw_ex_in<F, T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    distance_mode: DistanceMode,
+    storage_strategy: StorageStrategy,
+    clamp_delta: f64,
+) -> Result<DtwResult<F>, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<F>,
+    W: Iterator<Item = (usize, usize)>,
+    F: DtwFloat,
+{
+    dtw_ex_with_cutoff(
+        x,
+        y,
+        window,
+        distance_mode,
+        storage_strategy,
+        F::from(clamp_delta).unwrap_or_else(F::infinity),
+        F::infinity(),
+    )
+}
+
+/// Run `dtw_ex`, but return `DtwError::Abandoned` as soon as the accumulated cost provably exceeds
+/// `cutoff` (see `dtw_cells_with_cutoff`).
+pub(crate) fn dtw_ex_with_cutoff<T, W, F>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    distance_mode: DistanceMode,
+    storage_strategy: StorageStrategy,
+    clamp_delta: F,
+    cutoff: F,
+) -> Result<DtwResult<F>, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<F>,
+    W: Iterator<Item = (usize, usize)>,
+    F: DtwFloat,
 {
     let x_size = x.shape()[0];
     let y_size = y.shape()[0];
-    let mut cost_storage = cost::cost_storage(y_size, x_size);
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        distance_mode,
+        storage_strategy,
+        cutoff,
+        |row, column| {
+            clamped_cost(
+                x[column - 1].into(),
+                y[row - 1].into(),
+                clamp_delta,
+                distance_mode,
+            )
+        },
+    )
+}
+
+/// The local cost between two samples whose difference is clamped to `clamp_delta`, see `dtw_ex`.
+///
+/// The sample `b` is moved toward `a` rather than comparing the difference to zero, since the local
+/// cost of some distance modes (e.g. `DistanceMode::Canberra`) depends on the samples themselves.
+fn clamped_cost<F: DtwFloat>(a: F, b: F, clamp_delta: F, distance_mode: DistanceMode) -> F {
+    match (a - b).abs() > clamp_delta {
+        true => local_cost(a, a - clamp_delta * (a - b).signum(), distance_mode),
+        false => local_cost(a, b, distance_mode),
+    }
+}
+
+/// Run `dtw_ex` with a dense `CostMatrix` and return the cost matrix together with the distance and
+/// warp path, e.g. for the visual debugging of the accumulated costs, or for computing alternative
+/// warp paths with `backtrack` from other cells than the last one.
+///
+/// The cost matrix is always dense, regardless of `config_max_cost_storage_matrix`, and holds the
+/// accumulated costs and actions of the cells of the window; the other cells keep an infinite cost and
+/// no action.
+///
+/// # Returns
+///
+/// The distance and the warp path together with the cost matrix, or the errors of `dtw_ex`.
+pub fn dtw_ex_with_matrix<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    distance_mode: DistanceMode,
+    clamp_delta: f64,
+) -> Result<(DtwResult, CostMatrix), DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let mut cost_matrix = CostMatrix::new(y_size, x_size);
+    let malformed = std::cell::Cell::new(None);
+    let result = accumulate_cells(
+        &mut cost_matrix,
+        (y_size, x_size),
+        inside_window(y_size, x_size, window, &malformed),
+        distance_mode,
+        f64::INFINITY,
+        |row, column| {
+            clamped_cost(
+                x[column - 1].into(),
+                y[row - 1].into(),
+                clamp_delta,
+                distance_mode,
+            )
+        },
+    );
+    checked_result(malformed.get(), result).map(|result| (result, cost_matrix))
+}
+
+/// Run DTW like `dtw_ex`, but give up once more than `max_cells` cells of the window were visited.
+///
+/// The window is consumed lazily, so at most `max_cells + 1` cells are computed before giving up and
+/// the work is bounded even for very large windows. No approximation is attempted when the budget is
+/// exceeded; callers which prefer an approximate result can retry with a narrower window.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::BudgetExceeded` if the window has more
+/// than `max_cells` cells.
+pub fn dtw_budgeted<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    distance_mode: DistanceMode,
+    max_cells: usize,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let visited = std::cell::Cell::new(0usize);
+    let window = window.take_while(|_| {
+        visited.set(visited.get() + 1);
+        visited.get() <= max_cells
+    });
+    let result = dtw_ex(
+        x,
+        y,
+        window,
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    );
+    match visited.get() > max_cells {
+        true => Err(DtwError::BudgetExceeded { max_cells }),
+        false => result,
+    }
+}
+
+/// Run the DTW accumulation and backtracking on the cells visited by `window`, where the local cost of
+/// each cell is given by `cell_cost` (using 1 based indices). This is the core shared by the DTW
+/// variants which only differ in how the local cost of a cell is computed.
+///
+/// The computation is abandoned as soon as the accumulated cost provably exceeds `cutoff`, i.e. when
+/// all cells of a completed row have a higher accumulated cost. Since every warp path crosses every
+/// row, the accumulated cost of the last cell is then higher as well. The cutoff is a distance, and is
+/// compared to the accumulated costs after the final transformation of `distance_mode`.
+///
+/// # Returns
+///
+/// The distance and warp path, `DtwError::Abandoned` if the distance exceeds `cutoff`, or
+/// `DtwError::UncomputedCell` if the warp path reaches a cell the window did not visit.
+pub(crate) fn dtw_cells_with_cutoff<W, C, F>(
+    y_size: usize,
+    x_size: usize,
+    window: W,
+    distance_mode: DistanceMode,
+    storage_strategy: StorageStrategy,
+    cutoff: F,
+    cell_cost: C,
+) -> Result<DtwResult<F>, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+    C: Fn(usize, usize) -> F,
+    F: DtwFloat,
+{
+    let sizes = (y_size, x_size);
+    let cost_storage = cost::cost_storage(y_size, x_size, storage_strategy, BoundaryPolicy::Origin);
+    match cost_storage {
+        SelectedStorage::Dense(mut storage) => accumulate_cells(
+            &mut storage,
+            sizes,
+            window,
+            distance_mode,
+            cutoff,
+            cell_cost,
+        ),
+        SelectedStorage::Cache(mut storage) => accumulate_cells(
+            &mut storage,
+            sizes,
+            window,
+            distance_mode,
+            cutoff,
+            cell_cost,
+        ),
+        SelectedStorage::Banded(mut storage) => accumulate_cells(
+            &mut storage,
+            sizes,
+            window,
+            distance_mode,
+            cutoff,
+            cell_cost,
+        ),
+    }
+}
 
+/// The body of `dtw_cells_with_cutoff` for a concrete backend of the cost storage.
+fn accumulate_cells<S, W, C, F>(
+    cost_storage: &mut S,
+    (y_size, x_size): (usize, usize),
+    window: W,
+    distance_mode: DistanceMode,
+    cutoff: F,
+    cell_cost: C,
+) -> Result<DtwResult<F>, DtwError>
+where
+    S: CostStorage<F>,
+    W: Iterator<Item = (usize, usize)>,
+    C: Fn(usize, usize) -> F,
+    F: DtwFloat,
+{
+    // the row of the previous cell and the minimum accumulated cost on it
+    let mut current_row = 0;
+    let mut row_minimum = F::zero();
     for (row, column) in window {
-        let cost = match distance_mode {
-            DistanceMode::Manhattan => f64::abs(x[column - 1].into() - y[row - 1].into()),
-            DistanceMode::Euclidean => {
-                let difference = x[column - 1].into() - y[row - 1].into();
-                difference * difference
+        if row != current_row {
+            if final_distance(row_minimum, distance_mode) > cutoff {
+                return Err(DtwError::Abandoned);
             }
-        };
+            current_row = row;
+            row_minimum = F::infinity();
+        }
+        let cost = cell_cost(row, column);
 
         let (value, action) = minimum(
             cost_storage.get_cost(row - 1, column), // insertion - the cell above
@@ -75,40 +517,547 @@ where
 
         cost_storage.set_cost(row, column, cost + value);
         cost_storage.set_action(row, column, action);
+        row_minimum = row_minimum.min(cost + value);
+    }
+    let cost = cost_storage.get_cost(y_size, x_size);
+    let distance = final_distance(cost, distance_mode);
+    if distance > cutoff {
+        return Err(DtwError::Abandoned);
     }
-    let distance = match distance_mode {
-        DistanceMode::Manhattan => cost_storage.get_cost(y_size, x_size),
-        DistanceMode::Euclidean => cost_storage.get_cost(y_size, x_size).sqrt(),
-    };
 
     /* generate the warp path based on the cost matrix
-     * the path is allocated as a x_size + y_size array for the worst case scenario
-     * afterwards, the path is truncated based on the actual number of elements
+     * if the window does not connect the last cell to the origin, the cost is infinite and there is
+     * no warp path
      */
-    let mut path = Array1::<(usize, usize)>::default(x_size + y_size);
-    let mut path_len = 0;
-    let mut row = y_size;
-    let mut column = x_size;
-    while row != 0 && column != 0 {
-        /* the search window and cost matrix use the first row and first column themselves
-         * the results are thus 1 based indices of the time series samples, and not 0 based
-         * in order to convert back to 0 based indices, we subtract 1 below
-         */
-        path[path_len] = (row - 1, column - 1);
-        (row, column) = match cost_storage.get_action(row, column) {
-            Action::Inserted => (row - 1, column),
-            Action::Deleted => (row, column - 1),
-            Action::Matched => (row - 1, column - 1),
-            Action::Unknown => {
-                /* this should actually never happen if everything went well during the generation
-                 * of the cost matrix
-                 */
-                panic!("unknown error during the generation of the warp path");
-            }
+    let path = match cost.is_finite() {
+        // all cells on the path from a cell with a finite cost were computed
+        true => backtrack(cost_storage, y_size, x_size)?,
+        false => Array1::<(usize, usize)>::default(0),
+    };
+
+    Ok((distance, path))
+}
+
+/// Run `dtw_cells_with_cutoff` on time series supplied by the caller, validating their sizes and the
+/// window first.
+///
+/// # Returns
+///
+/// The result of `dtw_cells_with_cutoff`, or `DtwError::EmptyTimeSeries` if either size is 0,
+/// `DtwError::MalformedWindow` for the first cell of the window outside of the cost matrix and
+/// `DtwError::Abandoned` if the accumulated cost exceeds `cutoff`.
+pub(crate) fn checked_dtw_cells<W, C, F>(
+    y_size: usize,
+    x_size: usize,
+    window: W,
+    distance_mode: DistanceMode,
+    storage_strategy: StorageStrategy,
+    cutoff: F,
+    cell_cost: C,
+) -> Result<DtwResult<F>, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+    C: Fn(usize, usize) -> F,
+    F: DtwFloat,
+{
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let malformed = std::cell::Cell::new(None);
+    let result = dtw_cells_with_cutoff(
+        y_size,
+        x_size,
+        inside_window(y_size, x_size, window, &malformed),
+        distance_mode,
+        storage_strategy,
+        cutoff,
+        cell_cost,
+    );
+    checked_result(malformed.get(), result)
+}
+
+/// Stop a window at its first cell outside of the cost matrix instead of indexing the time series out
+/// of bounds, and record the cell in `malformed`, to be reported once the accumulation is done.
+pub(crate) fn inside_window<'a, W>(
+    y_size: usize,
+    x_size: usize,
+    window: W,
+    malformed: &'a std::cell::Cell<Option<(usize, usize)>>,
+) -> impl Iterator<Item = (usize, usize)> + 'a
+where
+    W: Iterator<Item = (usize, usize)> + 'a,
+{
+    window.take_while(move |&(row, column)| {
+        let inside = (1..=y_size).contains(&row) && (1..=x_size).contains(&column);
+        if !inside {
+            malformed.set(Some((row, column)));
+        }
+        inside
+    })
+}
+
+/// Convert the result of an accumulation over `inside_window` into the errors of `checked_dtw_cells`.
+pub(crate) fn checked_result<R>(
+    malformed: Option<(usize, usize)>,
+    result: Result<R, DtwError>,
+) -> Result<R, DtwError> {
+    match malformed {
+        Some((row, column)) => Err(DtwError::MalformedWindow { row, column }),
+        None => result,
+    }
+}
+
+/// Estimate the sub-sample offset of the minimum of the local cost around a cell, by fitting a
+/// parabola through the costs of the cell and of its two neighbors along one axis. The result is
+/// clamped to half a cell on either side.
+fn subsample_offset(previous: f64, current: f64, next: f64) -> f64 {
+    let curvature = previous - 2f64 * current + next;
+    if curvature <= 0f64 || !curvature.is_finite() {
+        return 0f64;
+    }
+    ((previous - next) / (2f64 * curvature)).clamp(-0.5, 0.5)
+}
+
+/// Run DTW and refine the warp path into a fractional (sub-sample) alignment.
+///
+/// Each vertex of the integer warp path is shifted towards the neighbor with the lower local cost, in
+/// proportion to the difference between the local costs of the two neighbors (parabolic
+/// interpolation), independently along the row and the column axes. Since a misalignment between the
+/// two series shows up along both axes, each vertex is moved by half of the two offsets, which places it
+/// on the line of best alignment between the two neighborhoods. Vertices on the first or last row
+/// (column) are not shifted along the row (column) axis.
+///
+/// This is a heuristic refinement and not an exact solution: the distance is the one of the integer
+/// warp path and every fractional vertex stays within half a cell of its integer counterpart.
+///
+/// # Returns
+///
+/// The DTW distance and the warp path given as fractional `(row, column)` positions, or
+/// `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_fractional<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<(f64, Array1<(f64, f64)>), DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    let (distance, path) = dtw_ex(
+        x,
+        y,
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )?;
+
+    let cost =
+        |row: usize, column: usize| local_cost(x[column].into(), y[row].into(), distance_mode);
+    let fractional_path = path.map(|&(row, column)| {
+        let row_offset = match row > 0 && row + 1 < rows {
+            true => subsample_offset(
+                cost(row - 1, column),
+                cost(row, column),
+                cost(row + 1, column),
+            ),
+            false => 0f64,
         };
-        path_len += 1;
+        let column_offset = match column > 0 && column + 1 < columns {
+            true => subsample_offset(
+                cost(row, column - 1),
+                cost(row, column),
+                cost(row, column + 1),
+            ),
+            false => 0f64,
+        };
+        (
+            row as f64 + row_offset / 2f64,
+            column as f64 + column_offset / 2f64,
+        )
+    });
+
+    Ok((distance, fractional_path))
+}
+
+/// Compute the DTW distance between `x` and every prefix of `y` in a single pass.
+///
+/// The cost matrix is filled row by row, and the accumulated cost in the last column of row `k`
+/// is the cost of aligning all of `x` to the first `k` samples of `y`. This is thus much cheaper than
+/// running DTW once per prefix.
+///
+/// # Returns
+///
+/// An array with one element per sample of `y`, where element `k` is the DTW distance between `x`
+/// and `y[0..k + 1]`, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_prefix_distances<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<Array1<f64>, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let cost_storage = cost::cost_storage::<f64>(
+        y_size,
+        x_size,
+        StorageStrategy::Auto,
+        BoundaryPolicy::Origin,
+    );
+    Ok(match cost_storage {
+        SelectedStorage::Dense(storage) => prefix_distances(storage, x, y, distance_mode),
+        SelectedStorage::Cache(storage) => prefix_distances(storage, x, y, distance_mode),
+        SelectedStorage::Banded(storage) => prefix_distances(storage, x, y, distance_mode),
+    })
+}
+
+/// The body of `dtw_prefix_distances` for a concrete backend of the cost storage.
+fn prefix_distances<S, T>(
+    mut cost_storage: S,
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Array1<f64>
+where
+    S: CostStorage,
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    let mut distances = Array1::<f64>::from_elem(y_size, f64::INFINITY);
+
+    for (row, column) in FullWindow::new(y_size, x_size) {
+        let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+        let (value, _) = minimum(
+            cost_storage.get_cost(row - 1, column),
+            cost_storage.get_cost(row, column - 1),
+            cost_storage.get_cost(row - 1, column - 1),
+        );
+        cost_storage.set_cost(row, column, cost + value);
+
+        // the row is complete once the last column is reached
+        if column == x_size {
+            distances[row - 1] = final_distance(cost + value, distance_mode);
+        }
+    }
+
+    distances
+}
+
+/// Run DTW of `x` against `y` and against `y` in reverse order, and keep the orientation of `y` with
+/// the lower distance. On equal distances, the forward orientation is kept.
+///
+/// # Returns
+///
+/// The winning orientation and its DTW result, or `DtwError::EmptyTimeSeries` if either time series
+/// has no samples. For `Orientation::Reversed`, the rows of the warp path are indices into the reversed
+/// y time series, i.e. row `i` refers to `y[y.len() - 1 - i]`.
+pub fn dtw_best_orientation<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<(Orientation, DtwResult), DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    let forward = dtw_ex(
+        x,
+        y,
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )?;
+    let reversed_y = y.slice(s![..;-1]);
+    let reversed = dtw_ex(
+        x,
+        &reversed_y,
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )?;
+
+    Ok(match reversed.0 < forward.0 {
+        true => (Orientation::Reversed, reversed),
+        false => (Orientation::Forward, forward),
+    })
+}
+
+/// Run DTW on time series sampled at irregular timestamps.
+///
+/// The local cost of a cell is the cost between the two samples plus `time_penalty` times the cost
+/// between their timestamps, both computed according to `distance_mode`. Aligning samples which are far
+/// apart in time is thus penalized, which makes the alignment prefer samples close in time. With a
+/// `time_penalty` of zero, the timestamps are ignored and the result is the one of `dtw_ex` with the full
+/// window.
+///
+/// # Arguments
+///
+/// * `tx` - the timestamps of the samples of `x`
+///
+/// * `ty` - the timestamps of the samples of `y`
+///
+/// * `time_penalty` - the weight of the timestamp cost relative to the sample cost
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::TimestampLengthMismatch` if a time series
+/// and its timestamps have different lengths. Empty time series are reported as for `dtw_ex`.
+pub fn dtw_timestamped<T>(
+    x: &ArrayView1<T>,
+    tx: &ArrayView1<f64>,
+    y: &ArrayView1<T>,
+    ty: &ArrayView1<f64>,
+    time_penalty: f64,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    for (values, timestamps) in [(x_size, tx.shape()[0]), (y_size, ty.shape()[0])] {
+        if values != timestamps {
+            return Err(DtwError::TimestampLengthMismatch { values, timestamps });
+        }
+    }
+
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        FullWindow::new(y_size, x_size),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| {
+            local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode)
+                + time_penalty * local_cost(tx[column - 1], ty[row - 1], distance_mode)
+        },
+    )
+}
+
+/// Run DTW like `dtw_ex` while recording snapshots of the accumulated costs as the cost matrix is
+/// filled, e.g. to animate or debug the computation.
+///
+/// A snapshot of the whole cost matrix is taken after every `frame_interval` computed cells, and once
+/// more after the last cell if the number of cells in the window is not a multiple of `frame_interval`.
+/// Each snapshot is indexed like `CostMatrix::costs`, with an infinite cost for the cells which are not
+/// computed yet. Since every snapshot is a copy of the cost matrix, the dense backend is always used and
+/// this is only meant for small matrices.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, and the snapshots in the order in which they were taken,
+/// or the errors of `dtw_ex`.
+///
+/// # Panics
+///
+/// If `frame_interval` is 0.
+pub fn dtw_frames<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    distance_mode: DistanceMode,
+    frame_interval: usize,
+) -> Result<(DtwResult, Vec<Array2<f64>>), DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    assert!(frame_interval > 0);
+
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let mut cost_matrix = CostMatrix::<f64>::new(y_size, x_size);
+    let mut frames = Vec::new();
+    let mut cells = 0;
+
+    let malformed = std::cell::Cell::new(None);
+    for (row, column) in inside_window(y_size, x_size, window, &malformed) {
+        let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+        let (value, action) = minimum(
+            cost_matrix.get_cost(row - 1, column),
+            cost_matrix.get_cost(row, column - 1),
+            cost_matrix.get_cost(row - 1, column - 1),
+        );
+        cost_matrix.set_cost(row, column, cost + value);
+        cost_matrix.set_action(row, column, action);
+
+        cells += 1;
+        if cells % frame_interval == 0 {
+            frames.push(cost_matrix.costs().to_owned());
+        }
+    }
+    if cells % frame_interval != 0 {
+        frames.push(cost_matrix.costs().to_owned());
+    }
+    if let Some((row, column)) = malformed.get() {
+        return Err(DtwError::MalformedWindow { row, column });
+    }
+
+    let cost = cost_matrix.get_cost(y_size, x_size);
+    let path = match cost.is_finite() {
+        true => backtrack(&cost_matrix, y_size, x_size)?,
+        false => Array1::<(usize, usize)>::default(0),
+    };
+
+    Ok(((final_distance(cost, distance_mode), path), frames))
+}
+
+/// Run DTW directly on a precomputed grid of local costs, without access to the time series.
+///
+/// Element `[i, j]` of `grid` is the local cost of aligning sample `i` of the y time series to sample
+/// `j` of the x time series, i.e. the grid has one row per sample of y and one column per sample of x,
+/// like the cost matrix. The local costs are accumulated as they are and the distance is the
+/// accumulated cost of the last cell, without any final transformation.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if the grid has no rows
+/// or no columns.
+pub fn dtw_from_distance_grid(grid: &ArrayView2<f64>) -> Result<DtwResult, DtwError> {
+    let rows = grid.shape()[0];
+    let columns = grid.shape()[1];
+    // the final distance of the manhattan mode is the accumulated cost itself
+    checked_dtw_cells(
+        rows,
+        columns,
+        FullWindow::new(rows, columns),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| grid[[row - 1, column - 1]],
+    )
+}
+
+/// Run DTW on slices of arbitrary samples (e.g. structs with several fields), where `extract` gives
+/// the scalar value of a sample.
+///
+/// The extractor is applied lazily whenever the local cost of a cell is computed, so no intermediate
+/// time series is allocated, at the cost of calling `extract` twice per cell. The full window is used.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if either slice is
+/// empty.
+pub fn dtw_by<T, F>(
+    x: &[T],
+    y: &[T],
+    extract: F,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    F: Fn(&T) -> f64,
+{
+    let x_size = x.len();
+    let y_size = y.len();
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        FullWindow::new(y_size, x_size),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| local_cost(extract(&x[column - 1]), extract(&y[row - 1]), distance_mode),
+    )
+}
+
+/// Run DTW over the cells of `window` with a user supplied local cost function instead of a
+/// `DistanceMode`, for samples which are not scalars (e.g. complex numbers or categorical labels).
+///
+/// `cost(a, b)` is called with a sample of x and a sample of y, and must not return a negative cost.
+/// The local costs are accumulated as they are and the distance is the accumulated cost of the last
+/// cell, without any final transformation.
+///
+/// # Returns
+///
+/// The distance and warp path, or the errors of `dtw_ex`.
+pub fn dtw_with_cost<T, W, C>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    cost: C,
+) -> Result<DtwResult, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+    C: Fn(&T, &T) -> f64,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    // the final distance of the manhattan mode is the accumulated cost itself
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| cost(&x[column - 1], &y[row - 1]),
+    )
+}
+
+/// Run DTW with the symmetric2 step pattern and return the distance normalized by `n + m`, where `n`
+/// and `m` are the sizes of the time series.
+///
+/// The symmetric2 step pattern weighs the local cost of a diagonal step twice, and the local cost of a
+/// horizontal or vertical step once:
+///
+/// ```text
+/// g(i, j) = min(g(i - 1, j) + d(i, j), g(i - 1, j - 1) + 2 * d(i, j), g(i, j - 1) + d(i, j))
+/// ```
+///
+/// so that every warp path has a total weight of `n + m`, and dividing by it gives an average cost per
+/// step which is comparable across sizes. The first cell has a weight of 2, as if reached diagonally
+/// from the origin. The normalized cost goes through the final transformation of `distance_mode`
+/// (the square root for the euclidean mode).
+///
+/// # Returns
+///
+/// The normalized distance and the warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if
+/// either time series has no samples.
+pub fn dtw_symmetric2_normalized<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
     }
-    let path = path.slice_move(s![..path_len;-1]);
+    let mut cost_matrix = CostMatrix::<f64>::new(y_size, x_size);
 
-    (distance, path)
+    for (row, column) in FullWindow::new(y_size, x_size) {
+        let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+        let (value, action) = minimum(
+            cost_matrix.get_cost(row - 1, column) + cost,
+            cost_matrix.get_cost(row, column - 1) + cost,
+            cost_matrix.get_cost(row - 1, column - 1) + 2f64 * cost,
+        );
+        cost_matrix.set_cost(row, column, value);
+        cost_matrix.set_action(row, column, action);
+    }
+
+    let cost = cost_matrix.get_cost(y_size, x_size);
+    let path = match cost.is_finite() {
+        true => backtrack(&cost_matrix, y_size, x_size)?,
+        false => Array1::<(usize, usize)>::default(0),
+    };
+    let normalized_cost = cost / (x_size + y_size) as f64;
+    Ok((final_distance(normalized_cost, distance_mode), path))
 }
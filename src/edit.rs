@@ -0,0 +1,360 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::cost::{self, CostStorage, SelectedStorage};
+use crate::dtw::{backtrack, checked_result, inside_window, minimum, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// Run the recurrence of an edit distance over the cells of `window`, where each of the three steps
+/// into a cell has its own cost.
+///
+/// # Arguments
+///
+/// * `boundary` - the accumulated cost of a cell of the virtual first row or first column
+///
+/// * `step_costs` - the costs of the steps from the cell above (insertion), from the cell to the left
+///   (deletion) and from the cell above and to the left (match) into a cell, using 1 based indices
+///
+/// # Returns
+///
+/// The accumulated cost of the last cell and the warp path, or the errors of `dtw_ex` for empty time
+/// series and malformed windows. The warp path is empty if the window does not connect the last cell
+/// to the boundary.
+pub(crate) fn edit_cells<W, B, C>(
+    y_size: usize,
+    x_size: usize,
+    window: W,
+    storage_strategy: StorageStrategy,
+    boundary: B,
+    step_costs: C,
+) -> Result<DtwResult, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+    B: Fn(usize, usize) -> f64,
+    C: Fn(usize, usize) -> (f64, f64, f64),
+{
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let malformed = std::cell::Cell::new(None);
+    let window = inside_window(y_size, x_size, window, &malformed);
+    let sizes = (y_size, x_size);
+    let cost_storage = cost::cost_storage(y_size, x_size, storage_strategy, BoundaryPolicy::Origin);
+    let result = match cost_storage {
+        SelectedStorage::Dense(storage) => {
+            accumulate_edit_cells(storage, sizes, window, boundary, step_costs)
+        }
+        SelectedStorage::Cache(storage) => {
+            accumulate_edit_cells(storage, sizes, window, boundary, step_costs)
+        }
+        SelectedStorage::Banded(storage) => {
+            accumulate_edit_cells(storage, sizes, window, boundary, step_costs)
+        }
+    };
+    checked_result(malformed.get(), result)
+}
+
+/// The body of `edit_cells` for a concrete backend of the cost storage.
+fn accumulate_edit_cells<S, W, B, C>(
+    mut cost_storage: S,
+    (y_size, x_size): (usize, usize),
+    window: W,
+    boundary: B,
+    step_costs: C,
+) -> Result<DtwResult, DtwError>
+where
+    S: CostStorage,
+    W: Iterator<Item = (usize, usize)>,
+    B: Fn(usize, usize) -> f64,
+    C: Fn(usize, usize) -> (f64, f64, f64),
+{
+    for (row, column) in window {
+        let get_cost = |row: usize, column: usize| match row == 0 || column == 0 {
+            true => boundary(row, column),
+            false => cost_storage.get_cost(row, column),
+        };
+        let (inserted, deleted, matched) = step_costs(row, column);
+        let (value, action) = minimum(
+            get_cost(row - 1, column) + inserted,
+            get_cost(row, column - 1) + deleted,
+            get_cost(row - 1, column - 1) + matched,
+        );
+        cost_storage.set_cost(row, column, value);
+        cost_storage.set_action(row, column, action);
+    }
+    let cost = cost_storage.get_cost(y_size, x_size);
+    let path = match cost.is_finite() {
+        true => backtrack(&cost_storage, y_size, x_size)?,
+        false => Array1::<(usize, usize)>::default(0),
+    };
+    Ok((cost, path))
+}
+
+/// The boundary of the recurrences which start in the origin, like DTW.
+pub(crate) fn origin_boundary(row: usize, column: usize) -> f64 {
+    match (row, column) {
+        (0, 0) => 0f64,
+        _ => f64::INFINITY,
+    }
+}
+
+/// The cost of a split or merge in MSM, when `value` is inserted next to `previous` and aligned to
+/// `other`: `cost` if `value` lies between the two, and otherwise `cost` plus its distance to the
+/// nearer one.
+fn msm_split_merge(value: f64, previous: f64, other: f64, cost: f64) -> f64 {
+    match (previous.min(other)..=previous.max(other)).contains(&value) {
+        true => cost,
+        false => cost + f64::min((value - previous).abs(), (value - other).abs()),
+    }
+}
+
+/// Compute the Move-Split-Merge (MSM) distance of Stefan et al. between two time series, over the
+/// cells of `window`.
+///
+/// MSM transforms x into y with three operations: a move changes the value of a sample at the cost of
+/// the absolute difference, a split repeats a sample and a merge removes a sample equal to its
+/// neighbor, at the cost of `cost` each. A match step (up and to the left) is a move, and the other
+/// steps split or merge a sample, with an additional cost when it does not lie between its neighbor
+/// and the sample it is aligned to. Unlike DTW, MSM is a metric.
+///
+/// # Returns
+///
+/// The distance and the warp path, as the cells of the cost matrix visited, or the errors of `dtw_ex`
+/// for empty time series and malformed windows.
+pub fn msm<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    cost: f64,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        origin_boundary,
+        |row, column| {
+            let (x_value, y_value) = (x[column - 1].into(), y[row - 1].into());
+            // the first sample of each time series has no neighbor, and can only be reached by a move
+            let inserted = match row {
+                1 => f64::INFINITY,
+                _ => msm_split_merge(y_value, y[row - 2].into(), x_value, cost),
+            };
+            let deleted = match column {
+                1 => f64::INFINITY,
+                _ => msm_split_merge(x_value, x[column - 2].into(), y_value, cost),
+            };
+            (inserted, deleted, (x_value - y_value).abs())
+        },
+    )
+}
+
+/// Compute the Time Warp Edit (TWE) distance of Marteau between two time series, over the cells of
+/// `window`.
+///
+/// TWE combines the edit distance with the timestamps of the samples, which are their indices. A
+/// match step (up and to the left) costs the absolute differences of the two matched samples and of
+/// the samples before them, plus `nu` times the difference of their timestamps, so that `nu` sets the
+/// stiffness of the alignment. Each of the other steps deletes a sample, which costs its difference to
+/// the previous sample of its time series, plus `nu` times the time step and the gap penalty `lambda`.
+/// The samples before the first ones are taken as 0. TWE is a metric.
+///
+/// # Returns
+///
+/// The distance and the warp path, as the cells of the cost matrix visited, or the errors of `dtw_ex`
+/// for empty time series and malformed windows.
+pub fn twe<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    nu: f64,
+    lambda: f64,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    // the sample at a 1 based index, with 0 before the first sample
+    let x_at = |column: usize| match column {
+        0 => 0f64,
+        _ => x[column - 1].into(),
+    };
+    let y_at = |row: usize| match row {
+        0 => 0f64,
+        _ => y[row - 1].into(),
+    };
+    edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        origin_boundary,
+        |row, column| {
+            let inserted = (y_at(row) - y_at(row - 1)).abs() + nu + lambda;
+            let deleted = (x_at(column) - x_at(column - 1)).abs() + nu + lambda;
+            let matched = (x_at(column) - y_at(row)).abs()
+                + (x_at(column - 1) - y_at(row - 1)).abs()
+                + nu * 2f64 * row.abs_diff(column) as f64;
+            (inserted, deleted, matched)
+        },
+    )
+}
+
+/// Compute the Edit distance with Real Penalty (ERP) of Chen and Ng between two time series, over the
+/// cells of `window`.
+///
+/// A match step (up and to the left) costs the absolute difference of the two samples, and each of the
+/// other steps aligns a sample to a gap, which costs its absolute difference to the gap value `g`. The
+/// virtual first row and column hold the costs of aligning the first samples of either time series to
+/// gaps, so that a warp path may start anywhere on them. Unlike DTW, ERP is a metric, which makes it
+/// suitable for indexing structures relying on the triangle inequality.
+///
+/// # Returns
+///
+/// The distance and the warp path, as the cells of the cost matrix visited, or the errors of `dtw_ex`
+/// for empty time series and malformed windows. The samples before the first cell of the warp path are
+/// aligned to gaps.
+pub fn erp<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    g: f64,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    // the costs of aligning the first samples to gaps, with a leading 0 for none
+    let gap_costs = |ts: &ArrayView1<T>| {
+        let mut costs = vec![0f64];
+        for &value in ts {
+            costs.push(costs[costs.len() - 1] + (value.into() - g).abs());
+        }
+        costs
+    };
+    let (x_gaps, y_gaps) = (gap_costs(x), gap_costs(y));
+    edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        |row, column| match row {
+            0 => x_gaps[column],
+            _ => y_gaps[row],
+        },
+        |row, column| {
+            let (x_value, y_value) = (x[column - 1].into(), y[row - 1].into());
+            (
+                (y_value - g).abs(),
+                (x_value - g).abs(),
+                (x_value - y_value).abs(),
+            )
+        },
+    )
+}
+
+/// The boundary of the recurrences where each sample before the warp path costs 1.
+fn count_boundary(row: usize, column: usize) -> f64 {
+    (row + column) as f64
+}
+
+/// Compute the Edit Distance on Real sequences (EDR) of Chen et al. between two time series, over the
+/// cells of `window`.
+///
+/// Two samples match if their absolute difference is at most `epsilon`, in which case a match step (up
+/// and to the left) is free, and costs 1 otherwise. Each of the other steps deletes a sample at the cost
+/// of 1, so that the distance is the number of edits turning x into y. Since the cost of a step does not
+/// depend on how far the samples are apart, EDR is robust to outliers.
+///
+/// # Returns
+///
+/// The distance and the warp path, as the cells of the cost matrix visited, or the errors of `dtw_ex`
+/// for empty time series and malformed windows.
+pub fn edr<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    epsilon: f64,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        count_boundary,
+        |row, column| {
+            let matched = match (x[column - 1].into() - y[row - 1].into()).abs() <= epsilon {
+                true => 0f64,
+                false => 1f64,
+            };
+            (1f64, 1f64, matched)
+        },
+    )
+}
+
+/// Compute the Longest Common Subsequence (LCSS) distance of Vlachos et al. between two time series,
+/// over the cells of `window`.
+///
+/// Two samples match if their absolute difference is at most `epsilon` and their indices are at most
+/// `delta` apart. The longest common subsequence is the largest number of matching pairs which can be
+/// aligned in order, and is found as the alignment skipping the fewest samples: each of the steps up or
+/// to the left skips a sample at the cost of 1, and a match step (up and to the left) is free for a
+/// matching pair and forbidden otherwise. The accumulated cost is thus `x.len() + y.len() - 2 * lcss`.
+///
+/// # Returns
+///
+/// The distance `1 - lcss / min(x.len(), y.len())`, which is 0 when the shorter time series matches
+/// entirely and 1 when no samples match, and the warp path, as the cells of the cost matrix visited,
+/// where the match steps are the matching pairs. Alternatively, the errors of `dtw_ex` for empty time
+/// series and malformed windows.
+pub fn lcss<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    epsilon: f64,
+    delta: usize,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    let (cost, path) = edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        count_boundary,
+        |row, column| {
+            let is_match = (x[column - 1].into() - y[row - 1].into()).abs() <= epsilon
+                && row.abs_diff(column) <= delta;
+            let matched = match is_match {
+                true => 0f64,
+                false => f64::INFINITY,
+            };
+            (1f64, 1f64, matched)
+        },
+    )?;
+    let common = (x_size + y_size) as f64 - cost;
+    Ok((
+        1f64 - common / (2 * usize::min(x_size, y_size)) as f64,
+        path,
+    ))
+}
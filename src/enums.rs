@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 /// For each cell of the matrix calculation an action is executed in order to align the X time
 /// series to Y. This can be either of insertion, deletion or matching.
 pub enum Action {
@@ -8,13 +8,153 @@ pub enum Action {
     Deleted,
     /// Matching - samples from X are found to be aligned with samples for Y
     Matched,
-    #[default]
-    /// Default enum value used for .default() calls.
-    Unknown,
 }
 
 #[derive(Copy, Clone)]
 pub enum DistanceMode {
+    /// The absolute difference between samples, accumulated as is.
     Manhattan,
+    /// The squared difference between samples, the distance being the square root of the accumulated
+    /// cost, i.e. the Euclidean distance between the aligned samples.
     Euclidean,
+    /// The squared difference between samples, the distance being the accumulated cost itself. This is
+    /// the square of the `Euclidean` distance with the same warp path, and unlike it, the partial
+    /// accumulated costs can be compared directly to the distance.
+    SquaredEuclidean,
+    /// The absolute difference between samples, accumulated as is. For multivariate samples, the local
+    /// cost is the largest absolute difference between the features instead of their sum.
+    Chebyshev,
+    /// The absolute difference between samples divided by the sum of their absolute values, which is
+    /// between 0 and 1. Two zero samples have a local cost of 0.
+    Canberra,
+    /// The absolute difference between samples raised to the power `p`, the distance being the `p`-th
+    /// root of the accumulated cost. `Minkowski(1.0)` is equivalent to `Manhattan` and `Minkowski(2.0)`
+    /// to `Euclidean`. `p` should be at least 1.
+    Minkowski(f64),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The orientation of the y time series which aligns best to x, as found by `dtw_best_orientation`.
+pub enum Orientation {
+    /// The y time series as given.
+    Forward,
+    /// The y time series in reverse order.
+    Reversed,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The backend used to store the cost and action values computed during DTW.
+pub enum StorageBackend {
+    /// A dense `CostMatrix` holding every cell of the cost matrix.
+    Matrix,
+    /// A sparse `CostCache` holding only the visited cells.
+    Cache,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The strategy used to choose the cost storage backend of a DTW run.
+pub enum StorageStrategy {
+    /// Choose between the dense and the cache backends depending on the size of the cost matrix, using
+    /// the threshold set by `config_max_cost_storage_matrix`.
+    #[default]
+    Auto,
+    /// Always use a dense `CostMatrix`, regardless of the size of the cost matrix.
+    Dense,
+    /// Always use a sparse `CostCache`.
+    Cache,
+    /// Use a `CostBand`, which stores a contiguous range of columns per row.
+    Banded,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The accumulated costs of the virtual first row and first column of the cost matrix, which precede
+/// the first samples of the time series.
+pub enum BoundaryPolicy {
+    /// Only the origin has a zero cost and all other boundary cells are unreachable, so every warp path
+    /// starts with the first samples of both time series.
+    #[default]
+    Origin,
+    /// The whole first row has a zero cost, so a warp path may start at any sample of the x time series,
+    /// as in subsequence DTW where y is searched within x.
+    FreeFirstRow,
+    /// The whole first column has a zero cost, so a warp path may start at any sample of the y time
+    /// series.
+    FreeFirstColumn,
+    /// The first `psi` cells after the origin on both the first row and the first column have a zero
+    /// cost, so a warp path may start at any of the first `psi + 1` samples of either time series, as
+    /// in psi DTW.
+    Relaxed(usize),
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The strategy used to align multivariate time series.
+pub enum MultivariateMode {
+    /// A single alignment over the feature vectors, where all features share the same warp path.
+    #[default]
+    Dependent,
+    /// A separate alignment per feature, where the distance is the sum of the distances of the features.
+    Independent,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The stage of a cascade of lower bounds at which a candidate was discarded.
+pub enum PruningStage {
+    /// The LB_Kim bound on the first and last samples exceeded the best distance so far.
+    LbKim,
+    /// The LB_Keogh bound of the candidate against the envelope of the query exceeded it.
+    LbKeogh,
+    /// The LB_Keogh bound of the query against the envelope of the candidate exceeded it.
+    LbKeoghReversed,
+    /// The DTW computation was abandoned once its accumulated cost exceeded it.
+    Abandoned,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// A transformation applied to both time series before the local costs are computed.
+pub enum Preprocessing {
+    /// The samples are used as given.
+    #[default]
+    None,
+    /// The samples are replaced by the estimates of their first derivatives, as computed by
+    /// `derivative_estimate`, as in the derivative DTW of Keogh and Pazzani.
+    Derivative,
+    /// The time series is z-normalized, as computed by `znormalize`.
+    ZNormalize,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The function summarizing each block of samples when downsampling a time series with `downsample`.
+pub enum Pooling {
+    /// The average of the block, i.e. the Piecewise Aggregate Approximation computed by `paa`.
+    #[default]
+    Mean,
+    /// The largest sample of the block.
+    Max,
+    /// The smallest sample of the block.
+    Min,
+    /// The median of the block, i.e. the average of its two middle samples for a block of even size.
+    Median,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// How `warp` fills the indices of y which are aligned to a single sample of x, when x is stretched.
+pub enum WarpInterpolation {
+    /// The sample of x is repeated over all the indices of y aligned to it.
+    #[default]
+    Step,
+    /// The indices of y aligned to a single sample of x are spread evenly between it and the next
+    /// sample of x, and take the linearly interpolated values.
+    Linear,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The distance between two clusters in agglomerative hierarchical clustering, computed from the
+/// distances between their members.
+pub enum Linkage {
+    /// The distance of the closest pair of members.
+    Single,
+    /// The distance of the farthest pair of members.
+    Complete,
+    /// The average distance over all pairs of members.
+    Average,
 }
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors reported by the DTW computations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DtwError {
+    /// The backtracking of the warp path reached a cell of the cost matrix which was not computed. The
+    /// cell is given as 1 based indices.
+    UncomputedCell { row: usize, column: usize },
+    /// A time series and its timestamps have different lengths.
+    TimestampLengthMismatch { values: usize, timestamps: usize },
+    /// The computation would visit more cells of the cost matrix than allowed by the budget.
+    BudgetExceeded { max_cells: usize },
+    /// One of the time series has no samples.
+    EmptyTimeSeries,
+    /// The samples of the two multivariate time series have different numbers of features.
+    DimensionMismatch {
+        x_features: usize,
+        y_features: usize,
+    },
+    /// The window visits a cell outside of the cost matrix. The cell is given as 1 based indices.
+    MalformedWindow { row: usize, column: usize },
+    /// The computation was abandoned because the distance exceeds the cutoff.
+    Abandoned,
+}
+
+impl fmt::Display for DtwError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DtwError::UncomputedCell { row, column } => write!(
+                f,
+                "the warp path reached the uncomputed cell ({}, {}) of the cost matrix",
+                row, column
+            ),
+            DtwError::TimestampLengthMismatch { values, timestamps } => write!(
+                f,
+                "the time series has {} values but {} timestamps",
+                values, timestamps
+            ),
+            DtwError::BudgetExceeded { max_cells } => write!(
+                f,
+                "the computation exceeds the budget of {} cells of the cost matrix",
+                max_cells
+            ),
+            DtwError::EmptyTimeSeries => write!(f, "a time series has no samples"),
+            DtwError::DimensionMismatch {
+                x_features,
+                y_features,
+            } => write!(
+                f,
+                "the samples of x have {} features but the samples of y have {}",
+                x_features, y_features
+            ),
+            DtwError::MalformedWindow { row, column } => write!(
+                f,
+                "the window visits the cell ({}, {}) outside of the cost matrix",
+                row, column
+            ),
+            DtwError::Abandoned => write!(f, "the distance exceeds the cutoff"),
+        }
+    }
+}
+
+impl std::error::Error for DtwError {}
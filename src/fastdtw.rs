@@ -1,76 +1,781 @@
 use ndarray::{Array1, ArrayView1};
+use std::time::{Duration, Instant};
 
-use crate::{dtw_ex, ConstrainedWindow, DistanceMode, FullWindow};
+use crate::dtw::{checked_dtw_cells, dtw_single_sample, local_cost};
+use crate::{
+    dtw_ex, dtw_ex_in, ConstrainedWindow, DistanceMode, DtwError, DtwFloat, DtwResult, FullWindow,
+    StorageStrategy,
+};
 
-fn coarse_time_series<T>(ts: &ArrayView1<T>, resolution_factor: usize) -> Array1<f64>
+fn coarse_time_series<T, F>(ts: &ArrayView1<T>, resolution_factor: usize) -> Array1<F>
+where
+    T: std::ops::Add + Default + Copy + std::convert::Into<F>,
+    F: DtwFloat,
+{
+    assert!(resolution_factor > 0);
+
+    let rounded_coarsed_size = (ts.shape()[0] as f64 / resolution_factor as f64).ceil() as usize;
+    let mut result = Array1::<F>::default(rounded_coarsed_size);
+
+    for pos in (0..rounded_coarsed_size * resolution_factor).step_by(resolution_factor) {
+        let mut sum = F::zero();
+        let end = std::cmp::min(pos + resolution_factor, ts.shape()[0]);
+        for i in pos..end {
+            sum = sum + ts[i].into();
+        }
+        let average = sum / F::from(end - pos).expect("the block size is representable as a float");
+        result[pos / resolution_factor] = average;
+    }
+    result
+}
+
+/// Compute the Piecewise Aggregate Approximation (PAA) of a time series, i.e. the averages of its
+/// consecutive blocks of `resolution_factor` samples. The last block is shorter when the size of the
+/// time series is not a multiple of `resolution_factor`. This is the coarsening used between the
+/// resolution levels of `fastdtw_ex`; see `downsample` for other pooling functions.
+///
+/// # Panics
+///
+/// If `resolution_factor` is 0.
+pub fn paa<T>(ts: &ArrayView1<T>, resolution_factor: usize) -> Array1<f64>
 where
     T: std::ops::Add + Default + Copy + std::convert::Into<f64>,
 {
+    coarse_time_series(ts, resolution_factor)
+}
+
+/// Shrink a weighted time series by `resolution_factor`, like `coarse_time_series`, while keeping track
+/// of the number of original samples summarized by each coarse sample.
+///
+/// # Returns
+///
+/// A tuple with the coarse time series, where each sample is the weighted average of its block, and the
+/// weight of each coarse sample, which is the sum of the weights in its block.
+fn coarse_weighted_time_series(
+    ts: &ArrayView1<f64>,
+    weights: &ArrayView1<f64>,
+    resolution_factor: usize,
+) -> (Array1<f64>, Array1<f64>) {
     assert!(resolution_factor > 0);
 
     let rounded_coarsed_size = (ts.shape()[0] as f64 / resolution_factor as f64).ceil() as usize;
     let mut result = Array1::<f64>::default(rounded_coarsed_size);
+    let mut result_weights = Array1::<f64>::default(rounded_coarsed_size);
 
     for pos in (0..rounded_coarsed_size * resolution_factor).step_by(resolution_factor) {
         let mut sum: f64 = 0f64;
+        let mut weight: f64 = 0f64;
         let end = std::cmp::min(pos + resolution_factor, ts.shape()[0]);
         for i in pos..end {
-            sum += ts[i].into();
+            sum += ts[i] * weights[i];
+            weight += weights[i];
         }
-        let average = sum / (end - pos) as f64;
-        result[pos / resolution_factor] = average;
+        result[pos / resolution_factor] = sum / weight;
+        result_weights[pos / resolution_factor] = weight;
     }
-    result
+    (result, result_weights)
+}
+
+/// Compute the coarse versions of the two time series at each resolution level of FastDTW, from the
+/// first coarse level down to the base case. The full resolution time series are not included. If the
+/// time series are already small enough for the base case, the result is empty.
+fn coarse_levels<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    min_ts_size: usize,
+) -> Vec<(Array1<f64>, Array1<f64>)>
+where
+    T: std::ops::Add + Default + Copy + std::convert::Into<f64>,
+{
+    let mut levels: Vec<(Array1<f64>, Array1<f64>)> = Vec::new();
+    if x.shape()[0] <= min_ts_size || y.shape()[0] <= min_ts_size {
+        return levels;
+    }
+
+    levels.push((
+        coarse_time_series(x, resolution_factor),
+        coarse_time_series(y, resolution_factor),
+    ));
+    loop {
+        let (coarse_x, coarse_y) = levels.last().unwrap();
+        if coarse_x.shape()[0] <= min_ts_size || coarse_y.shape()[0] <= min_ts_size {
+            break;
+        }
+        let coarser = (
+            coarse_time_series(&coarse_x.view(), resolution_factor),
+            coarse_time_series(&coarse_y.view(), resolution_factor),
+        );
+        levels.push(coarser);
+    }
+    levels
 }
 
-pub fn fastdtw<T>(x: &ArrayView1<T>, y: &ArrayView1<T>) -> (f64, Array1<(usize, usize)>)
+pub fn fastdtw<T>(x: &ArrayView1<T>, y: &ArrayView1<T>) -> Result<DtwResult, DtwError>
 where
     T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
 {
     fastdtw_ex(x, y, 2, 1, DistanceMode::Euclidean)
 }
 
+/// Run the FastDTW approximation of DTW.
+///
+/// The DTW is computed recursively on time series shrunk by `resolution_factor`, and the resulting warp
+/// path is projected onto the higher resolution, where DTW only visits the cells within `search_radius`
+/// of the projected path. The distance is thus never lower than the exact DTW distance, and equal to
+/// it whenever an optimal warp path lies within the window.
+///
+/// Even when the distance is exact, the warp path is not guaranteed to be the one returned by `dtw_ex`.
+/// When several warp paths have the same distance, the choice between them depends on the tie-breaking
+/// between neighboring cells, and the window may exclude the cells which the full DTW prefers.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::EmptyTimeSeries` if either time series has no
+/// samples.
 pub fn fastdtw_ex<T>(
     x: &ArrayView1<T>,
     y: &ArrayView1<T>,
     resolution_factor: usize,
     search_radius: usize,
     distance_mode: DistanceMode,
-) -> (f64, Array1<(usize, usize)>)
+) -> Result<DtwResult, DtwError>
 where
     T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
 {
+    fastdtw_ex_in(x, y, resolution_factor, search_radius, distance_mode)
+}
+
+/// Run `fastdtw_ex` with the coarse time series and the costs of all levels computed with the float
+/// type `F`, e.g. `fastdtw_ex_in::<f32, _>` halves the memory of the cost storage compared to `f64`.
+///
+/// # Returns
+///
+/// The distance and the warp path with the float type `F`, or the errors of `fastdtw_ex`.
+pub fn fastdtw_ex_in<F, T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult<F>, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<F> + Default + Copy,
+    F: DtwFloat,
+{
+    scheduled(x, y, &[resolution_factor], search_radius, distance_mode)
+}
+
+/// Run FastDTW with a different resolution factor at each recursion level.
+///
+/// Element `k` of `schedule` is the resolution factor used to shrink the time series of level `k`
+/// (where level 0 is the full resolution) into those of level `k + 1`, and to project the warp path of
+/// level `k + 1` back onto level `k`. Levels beyond the end of the schedule use its last factor, so a
+/// schedule with a single factor is the same as `fastdtw_ex`. Coarse levels can typically use larger
+/// factors than the fine levels, which need to stay close to the optimal warp path.
+pub fn fastdtw_scheduled<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    schedule: &[usize],
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    scheduled(x, y, schedule, search_radius, distance_mode)
+}
+
+/// The body of `fastdtw_scheduled` for the float type `F` of the costs.
+fn scheduled<F, T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    schedule: &[usize],
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult<F>, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<F> + Default + Copy,
+    F: DtwFloat,
+{
+    assert!(!schedule.is_empty());
     let min_ts_size: usize = search_radius + 2;
     let rows = y.shape()[0];
     let columns = x.shape()[0];
 
-    if x.shape()[0] <= min_ts_size || y.shape()[0] <= min_ts_size {
+    if let Some(result) = dtw_single_sample(x, y, distance_mode) {
+        Ok(result)
+    } else if x.shape()[0] <= min_ts_size || y.shape()[0] <= min_ts_size {
         // base case: for a very small time series run the full dtw algorithm
-        dtw_ex(x, y, FullWindow::new(rows, columns), distance_mode)
+        dtw_ex_in(
+            x,
+            y,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
     } else {
         /* recursive case:
          * project the warp path from a coarser resolution onto the current resolution
          * run dtw only along the projected path (and also 'search_radius' cells from the projected path)
          */
+        let resolution_factor = schedule[0];
+        let coarse_schedule = match schedule.len() {
+            1 => schedule,
+            _ => &schedule[1..],
+        };
+        let coarse_x = coarse_time_series::<T, F>(x, resolution_factor);
+        let coarse_y = coarse_time_series::<T, F>(y, resolution_factor);
+
+        let (_, low_res_path) = scheduled::<F, F>(
+            &coarse_x.view(),
+            &coarse_y.view(),
+            coarse_schedule,
+            search_radius,
+            distance_mode,
+        )?;
+
+        let constrained_window = ConstrainedWindow::from_low_res_path(
+            low_res_path,
+            resolution_factor,
+            search_radius,
+            y.shape()[0], /* high_res_rows */
+            x.shape()[0], /* high_res_columns */
+        );
+
+        dtw_ex_in(
+            x,
+            y,
+            constrained_window,
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+    }
+}
+
+/// Run FastDTW like `fastdtw_ex`, but give up if the windows of all resolution levels together would
+/// visit more than `max_cells` cells of the cost matrices.
+///
+/// The size of the window of each level is known before the level is computed, so the budget is
+/// checked before doing the work of a level and no level is computed only partially.
+///
+/// # Returns
+///
+/// The distance and warp path as for `fastdtw_ex`, or `DtwError::BudgetExceeded` if the budget is not
+/// sufficient for all levels.
+pub fn fastdtw_budgeted<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+    max_cells: usize,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    let mut remaining = max_cells;
+    fastdtw_budgeted_level(
+        x,
+        y,
+        resolution_factor,
+        search_radius,
+        distance_mode,
+        max_cells,
+        &mut remaining,
+    )
+}
+
+/// Take the cells of a level from the remaining budget, before computing the level.
+fn spend_budget(remaining: &mut usize, cells: usize, max_cells: usize) -> Result<(), DtwError> {
+    match cells <= *remaining {
+        true => {
+            *remaining -= cells;
+            Ok(())
+        }
+        false => Err(DtwError::BudgetExceeded { max_cells }),
+    }
+}
+
+fn fastdtw_budgeted_level<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+    max_cells: usize,
+    remaining: &mut usize,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    let min_ts_size: usize = search_radius + 2;
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    if x.shape()[0] <= min_ts_size || y.shape()[0] <= min_ts_size {
+        spend_budget(remaining, rows.saturating_mul(columns), max_cells)?;
+        dtw_ex(
+            x,
+            y,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+    } else {
         let coarse_x = coarse_time_series(x, resolution_factor);
         let coarse_y = coarse_time_series(y, resolution_factor);
 
-        let (_, low_res_path) = fastdtw_ex(
+        let (_, low_res_path) = fastdtw_budgeted_level(
             &coarse_x.view(),
             &coarse_y.view(),
             resolution_factor,
             search_radius,
             distance_mode,
+            max_cells,
+            remaining,
+        )?;
+
+        let constrained_window = ConstrainedWindow::from_low_res_path(
+            low_res_path,
+            resolution_factor,
+            search_radius,
+            rows,
+            columns,
         );
+        spend_budget(remaining, constrained_window.cell_count(), max_cells)?;
 
+        dtw_ex(
+            x,
+            y,
+            constrained_window,
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+    }
+}
+
+/// Iterative formulation of `fastdtw_ex`, producing identical results.
+///
+/// Instead of recursing once per resolution level, the coarse versions of the two time series are
+/// first computed bottom-up (from the full resolution down to the base case), after which the warp
+/// path is refined top-down by projecting it onto each finer level with a `ConstrainedWindow`. The
+/// stack usage is thus constant, independent of the number of resolution levels, which makes this
+/// variant suitable for very long time series or threads with small stacks.
+///
+/// # Returns
+///
+/// The distance and warp path as for `fastdtw_ex`, or `DtwError::EmptyTimeSeries` if either time
+/// series has no samples.
+///
+/// # Panics
+///
+/// If `resolution_factor` is less than 2, since the time series would then never shrink.
+pub fn fastdtw_iterative<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    assert!(
+        resolution_factor >= 2,
+        "the resolution factor must be at least 2"
+    );
+    let min_ts_size: usize = search_radius + 2;
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    if columns <= min_ts_size || rows <= min_ts_size {
+        // base case: for a very small time series run the full dtw algorithm
+        return dtw_ex(
+            x,
+            y,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        );
+    }
+
+    let mut pyramid = coarse_levels(x, y, resolution_factor, min_ts_size);
+
+    // base case at the coarsest level, then refine the path on each finer coarse level
+    let (base_x, base_y) = pyramid.pop().unwrap();
+    let (_, mut low_res_path) = dtw_ex(
+        &base_x.view(),
+        &base_y.view(),
+        FullWindow::new(base_y.shape()[0], base_x.shape()[0]),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )?;
+    while let Some((coarse_x, coarse_y)) = pyramid.pop() {
         let constrained_window = ConstrainedWindow::from_low_res_path(
             low_res_path,
             resolution_factor,
             search_radius,
-            y.shape()[0], /* high_res_rows */
-            x.shape()[0], /* high_res_columns */
+            coarse_y.shape()[0], /* high_res_rows */
+            coarse_x.shape()[0], /* high_res_columns */
+        );
+        (_, low_res_path) = dtw_ex(
+            &coarse_x.view(),
+            &coarse_y.view(),
+            constrained_window,
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )?;
+    }
+
+    // the last refinement step is on the full resolution time series
+    let constrained_window = ConstrainedWindow::from_low_res_path(
+        low_res_path,
+        resolution_factor,
+        search_radius,
+        rows,    /* high_res_rows */
+        columns, /* high_res_columns */
+    );
+    dtw_ex(
+        x,
+        y,
+        constrained_window,
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+}
+
+/// Compute the pyramid of coarse time series which FastDTW works on, without running DTW.
+///
+/// This is a debugging and visualization aid for inspecting the time series at each resolution level.
+///
+/// # Arguments
+///
+/// * `resolution_factor` - the factor by which the time series are shrunk at each level
+///
+/// # Returns
+///
+/// The `(x, y)` pair at each recursion level, starting with the full resolution time series and ending
+/// with the coarse time series on which the full DTW would be computed. The base case is the one of
+/// `fastdtw`, with a search radius of 1, i.e. the recursion stops once either time series has at most
+/// 3 samples.
+///
+/// # Panics
+///
+/// If `resolution_factor` is less than 2, since the time series would then never shrink.
+pub fn fastdtw_pyramid<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+) -> Vec<(Array1<f64>, Array1<f64>)>
+where
+    T: std::ops::Add + Default + Copy + std::convert::Into<f64>,
+{
+    assert!(
+        resolution_factor >= 2,
+        "the resolution factor must be at least 2"
+    );
+    let mut pyramid = vec![(x.map(|&v| v.into()), y.map(|&v| v.into()))];
+    pyramid.extend(coarse_levels(x, y, resolution_factor, 3));
+    pyramid
+}
+
+/// FastDTW variant where the coarse samples are weighted by the number of original samples they
+/// summarize.
+///
+/// When the length of a time series is not divisible by `resolution_factor`, its last coarse sample
+/// summarizes fewer samples than the other ones, but plain FastDTW weights all coarse samples equally
+/// in the DTW at the coarse levels. Here, the local cost of each coarse cell is scaled by the larger
+/// weight of its two samples, since a warp path through a block of `a` x `b` cells at the full
+/// resolution visits at least `max(a, b)` of them. The cost at a coarse level thus reflects the size of
+/// the blocks. At the full resolution all weights are 1 and the cost is computed exactly as by
+/// `fastdtw_ex`, only the projected window may differ.
+///
+/// # Returns
+///
+/// The distance and warp path as for `fastdtw_ex`, or `DtwError::EmptyTimeSeries` if either time
+/// series has no samples.
+pub fn fastdtw_weighted<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::convert::Into<f64> + Copy,
+{
+    let x = x.map(|&v| v.into());
+    let y = y.map(|&v| v.into());
+    let x_weights = Array1::<f64>::ones(x.shape()[0]);
+    let y_weights = Array1::<f64>::ones(y.shape()[0]);
+    fastdtw_weighted_level(
+        &x.view(),
+        &x_weights.view(),
+        &y.view(),
+        &y_weights.view(),
+        resolution_factor,
+        search_radius,
+        distance_mode,
+    )
+}
+
+fn fastdtw_weighted_level(
+    x: &ArrayView1<f64>,
+    x_weights: &ArrayView1<f64>,
+    y: &ArrayView1<f64>,
+    y_weights: &ArrayView1<f64>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError> {
+    let min_ts_size: usize = search_radius + 2;
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    let weighted_cost = |row: usize, column: usize| {
+        let weight = f64::max(x_weights[column - 1], y_weights[row - 1]);
+        local_cost(x[column - 1], y[row - 1], distance_mode) * weight
+    };
+
+    if columns <= min_ts_size || rows <= min_ts_size {
+        // base case: for a very small time series run the full dtw algorithm
+        checked_dtw_cells(
+            rows,
+            columns,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+            weighted_cost,
+        )
+    } else {
+        // recursive case: same as for fastdtw_ex, with the weights coarsened along the time series
+        let (coarse_x, coarse_x_weights) =
+            coarse_weighted_time_series(x, x_weights, resolution_factor);
+        let (coarse_y, coarse_y_weights) =
+            coarse_weighted_time_series(y, y_weights, resolution_factor);
+
+        let (_, low_res_path) = fastdtw_weighted_level(
+            &coarse_x.view(),
+            &coarse_x_weights.view(),
+            &coarse_y.view(),
+            &coarse_y_weights.view(),
+            resolution_factor,
+            search_radius,
+            distance_mode,
+        )?;
+
+        let constrained_window = ConstrainedWindow::from_low_res_path(
+            low_res_path,
+            resolution_factor,
+            search_radius,
+            rows,    /* high_res_rows */
+            columns, /* high_res_columns */
         );
 
-        dtw_ex(x, y, constrained_window, distance_mode)
+        checked_dtw_cells(
+            rows,
+            columns,
+            constrained_window,
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+            weighted_cost,
+        )
+    }
+}
+
+/// The result of FastDTW at one resolution factor, as reported by `dtw_tradeoff_report`.
+#[derive(Copy, Clone, Debug)]
+pub struct TradeoffEntry {
+    pub resolution_factor: usize,
+    /// The distance found by FastDTW.
+    pub distance: f64,
+    /// The relative error of the distance with regard to the exact DTW distance.
+    pub relative_error: f64,
+    /// The time spent in FastDTW.
+    pub elapsed: Duration,
+}
+
+/// The accuracy and speed of FastDTW at several resolution factors compared to the exact DTW.
+#[derive(Clone, Debug)]
+pub struct TradeoffReport {
+    /// The exact DTW distance.
+    pub exact_distance: f64,
+    /// The time spent in the exact DTW.
+    pub exact_elapsed: Duration,
+    /// One entry per resolution factor, in the order in which the factors were given.
+    pub entries: Vec<TradeoffEntry>,
+}
+
+/// Run the exact DTW once and FastDTW once per resolution factor, and report the distance, the relative
+/// error and the elapsed time of each run. FastDTW uses a search radius of 1, as in `fastdtw`.
+///
+/// The relative error is `(distance - exact_distance) / exact_distance`. If the exact distance is zero,
+/// the relative error is zero if FastDTW finds a zero distance as well, and infinite otherwise.
+///
+/// # Returns
+///
+/// The report, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_tradeoff_report<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    factors: &[usize],
+    distance_mode: DistanceMode,
+) -> Result<TradeoffReport, DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    let start = Instant::now();
+    let (exact_distance, _) = dtw_ex(
+        x,
+        y,
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )?;
+    let exact_elapsed = start.elapsed();
+
+    let entries = factors
+        .iter()
+        .map(|&resolution_factor| {
+            let start = Instant::now();
+            let (distance, _) = fastdtw_ex(x, y, resolution_factor, 1, distance_mode)?;
+            let elapsed = start.elapsed();
+
+            let relative_error = match exact_distance == 0f64 {
+                true if distance == 0f64 => 0f64,
+                true => f64::INFINITY,
+                false => (distance - exact_distance) / exact_distance,
+            };
+            Ok(TradeoffEntry {
+                resolution_factor,
+                distance,
+                relative_error,
+                elapsed,
+            })
+        })
+        .collect::<Result<_, DtwError>>()?;
+
+    Ok(TradeoffReport {
+        exact_distance,
+        exact_elapsed,
+        entries,
+    })
+}
+
+/// Check whether a warp path touches the boundary of a window, i.e. whether any cell of the path has a
+/// neighbor inside the cost matrix which is outside of the window.
+fn touches_boundary(
+    path: &Array1<(usize, usize)>,
+    window: &ConstrainedWindow,
+    rows: usize,
+    columns: usize,
+) -> bool {
+    path.iter().any(|&(row, column)| {
+        // convert the 0 based indices of the path to the 1 based indices of the window
+        let (row, column) = (row + 1, column + 1);
+        let neighbors = [
+            (row - 1, column),
+            (row + 1, column),
+            (row, column - 1),
+            (row, column + 1),
+        ];
+        neighbors
+            .iter()
+            .any(|&(r, c)| r >= 1 && r <= rows && c >= 1 && c <= columns && !window.contains(r, c))
+    })
+}
+
+/// FastDTW with a search radius which grows until the warp path does not touch the boundary of the
+/// constrained window anymore.
+///
+/// A warp path touching the boundary of the window is a sign that the optimal warp path may lie outside
+/// of it. In this case the search radius is doubled and DTW is run again on the full resolution level,
+/// reusing the low resolution warp path which is only computed once (with `initial_radius`). This is
+/// repeated until the warp path lies in the interior of the window or `max_radius` is reached.
+///
+/// Note that this only detects an optimal warp path close to the window. If the low resolution warp path
+/// misses the optimal alignment entirely (for example when averaging erases a narrow feature), the warp
+/// path found within the window may lie in its interior and the radius is not increased.
+///
+/// # Returns
+///
+/// The DTW result of the last run and the search radius used for it, or `DtwError::EmptyTimeSeries`
+/// if either time series has no samples.
+pub fn fastdtw_adaptive<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    resolution_factor: usize,
+    initial_radius: usize,
+    max_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<(DtwResult, usize), DtwError>
+where
+    T: std::ops::Add + std::ops::Sub + std::convert::Into<f64> + Default + Copy,
+{
+    let min_ts_size: usize = initial_radius + 2;
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    if columns <= min_ts_size || rows <= min_ts_size {
+        // base case: the full dtw algorithm does not depend on the search radius
+        let result = dtw_ex(
+            x,
+            y,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )?;
+        return Ok((result, initial_radius));
+    }
+
+    let coarse_x = coarse_time_series(x, resolution_factor);
+    let coarse_y = coarse_time_series(y, resolution_factor);
+    let (_, low_res_path) = fastdtw_ex(
+        &coarse_x.view(),
+        &coarse_y.view(),
+        resolution_factor,
+        initial_radius,
+        distance_mode,
+    )?;
+
+    let mut search_radius = initial_radius;
+    loop {
+        let constrained_window = ConstrainedWindow::from_low_res_path(
+            low_res_path.clone(),
+            resolution_factor,
+            search_radius,
+            rows,    /* high_res_rows */
+            columns, /* high_res_columns */
+        );
+        let result = dtw_ex(
+            x,
+            y,
+            constrained_window.clone(),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )?;
+
+        if search_radius >= max_radius
+            || !touches_boundary(&result.1, &constrained_window, rows, columns)
+        {
+            return Ok((result, search_radius));
+        }
+        search_radius = usize::min(usize::max(2 * search_radius, 1), max_radius);
     }
 }
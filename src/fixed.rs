@@ -0,0 +1,82 @@
+use ndarray::{Array2, ArrayView1};
+
+use crate::dtw::{final_distance, local_cost, minimum};
+use crate::enums::*;
+
+/// DTW for repeated runs on time series of the same fixed sizes, without allocations per run.
+///
+/// The cost matrix, the actions and the buffer of the warp path are allocated once in `new` and reused
+/// by every call to `run`. The full window is always used, so each run overwrites every cell of the
+/// cost matrix and no state carries over from one run to the next.
+pub struct FixedSizeDtw {
+    distance_mode: DistanceMode,
+    /// The accumulated costs, including the virtual first row and first column.
+    costs: Array2<f64>,
+    /// The actions, including the virtual first row and first column which are never used.
+    actions: Array2<Action>,
+    /// The warp path of the last run.
+    path: Vec<(usize, usize)>,
+}
+
+impl FixedSizeDtw {
+    pub fn new(x_size: usize, y_size: usize, distance_mode: DistanceMode) -> Self {
+        let mut costs = Array2::<f64>::from_elem((y_size + 1, x_size + 1), f64::INFINITY);
+        costs[[0, 0]] = 0f64;
+        Self {
+            distance_mode,
+            costs,
+            actions: Array2::<Action>::from_elem((y_size + 1, x_size + 1), Action::Matched),
+            path: Vec::with_capacity(x_size + y_size),
+        }
+    }
+
+    /// Run DTW on two time series of the sizes given to `new`.
+    ///
+    /// # Returns
+    ///
+    /// The distance and the warp path as for `dtw_ex`, where the warp path borrows the internal buffer
+    /// and is thus only valid until the next run.
+    pub fn run<T>(&mut self, x: &ArrayView1<T>, y: &ArrayView1<T>) -> (f64, &[(usize, usize)])
+    where
+        T: std::marker::Copy + std::convert::Into<f64>,
+    {
+        let rows = self.costs.shape()[0] - 1;
+        let columns = self.costs.shape()[1] - 1;
+        assert_eq!(
+            x.len(),
+            columns,
+            "the size of x differs from the fixed size"
+        );
+        assert_eq!(y.len(), rows, "the size of y differs from the fixed size");
+
+        for row in 1..rows + 1 {
+            for column in 1..columns + 1 {
+                let cost = local_cost(x[column - 1].into(), y[row - 1].into(), self.distance_mode);
+                let (value, action) = minimum(
+                    self.costs[[row - 1, column]],
+                    self.costs[[row, column - 1]],
+                    self.costs[[row - 1, column - 1]],
+                );
+                self.costs[[row, column]] = cost + value;
+                self.actions[[row, column]] = action;
+            }
+        }
+        let distance = final_distance(self.costs[[rows, columns]], self.distance_mode);
+
+        // the path is backtracked from the last cell and then reversed in place
+        self.path.clear();
+        let mut row = rows;
+        let mut column = columns;
+        while row != 0 && column != 0 {
+            self.path.push((row - 1, column - 1));
+            (row, column) = match self.actions[[row, column]] {
+                Action::Inserted => (row - 1, column),
+                Action::Deleted => (row, column - 1),
+                Action::Matched => (row - 1, column - 1),
+            };
+        }
+        self.path.reverse();
+
+        (distance, &self.path)
+    }
+}
@@ -0,0 +1,11 @@
+/// The floating point types usable for the costs of the DTW computations, i.e. `f32` and `f64`.
+///
+/// The costs of long time series may be stored as `f32` to halve the memory of the cost matrix, at the
+/// expense of precision in the accumulated costs.
+pub trait DtwFloat:
+    num_traits::Float + Default + std::fmt::Debug + std::iter::Sum + 'static
+{
+}
+
+impl DtwFloat for f32 {}
+impl DtwFloat for f64 {}
@@ -0,0 +1,152 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::dtw::{final_distance, local_cost, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// Accumulate the costs of the rectangle of cells from `(first_row, first_column)` to
+/// `(last_row, last_column)` (1 based, inclusive), for warp paths starting in its first cell, and
+/// return the accumulated costs of its last row. Only two rows are kept in memory.
+fn forward_costs<C>(
+    cell_cost: &C,
+    (first_row, first_column): (usize, usize),
+    (last_row, last_column): (usize, usize),
+) -> Vec<f64>
+where
+    C: Fn(usize, usize) -> f64,
+{
+    let columns = last_column - first_column + 1;
+    // element 0 is the virtual column to the left of the rectangle
+    let mut previous = vec![f64::INFINITY; columns + 1];
+    let mut current = vec![f64::INFINITY; columns + 1];
+    previous[0] = 0f64;
+    for row in first_row..=last_row {
+        current[0] = f64::INFINITY;
+        for column in 1..=columns {
+            let value = f64::min(
+                current[column - 1],
+                f64::min(previous[column], previous[column - 1]),
+            );
+            current[column] = cell_cost(row, first_column + column - 1) + value;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous.split_off(1)
+}
+
+/// Accumulate the costs of the rectangle of cells like `forward_costs`, but for warp paths ending in its
+/// last cell, and return the accumulated costs of its first row. The accumulated cost of a cell includes
+/// its own local cost.
+fn backward_costs<C>(
+    cell_cost: &C,
+    (first_row, first_column): (usize, usize),
+    (last_row, last_column): (usize, usize),
+) -> Vec<f64>
+where
+    C: Fn(usize, usize) -> f64,
+{
+    let columns = last_column - first_column + 1;
+    // element `columns` is the virtual column to the right of the rectangle
+    let mut next = vec![f64::INFINITY; columns + 1];
+    let mut current = vec![f64::INFINITY; columns + 1];
+    next[columns] = 0f64;
+    for row in (first_row..=last_row).rev() {
+        current[columns] = f64::INFINITY;
+        for column in (0..columns).rev() {
+            let value = f64::min(
+                current[column + 1],
+                f64::min(next[column], next[column + 1]),
+            );
+            current[column] = cell_cost(row, first_column + column) + value;
+        }
+        std::mem::swap(&mut next, &mut current);
+    }
+    next.truncate(columns);
+    next
+}
+
+/// Append the optimal warp path from `first` to `last` (1 based, inclusive cells) to `path`, as 0 based
+/// indices, by splitting the rectangle between them at its middle row.
+fn hirschberg_path<C>(
+    cell_cost: &C,
+    first: (usize, usize),
+    last: (usize, usize),
+    path: &mut Vec<(usize, usize)>,
+) where
+    C: Fn(usize, usize) -> f64,
+{
+    let ((first_row, first_column), (last_row, last_column)) = (first, last);
+    if first_row == last_row {
+        path.extend((first_column..=last_column).map(|column| (first_row - 1, column - 1)));
+        return;
+    }
+    if first_column == last_column {
+        path.extend((first_row..=last_row).map(|row| (row - 1, first_column - 1)));
+        return;
+    }
+
+    /* the warp path leaves the middle row from one of its cells (mid, j), either downwards to
+     * (mid + 1, j) or diagonally to (mid + 1, j + 1)
+     * the best crossing minimizes the cost up to (mid, j) plus the cost from the cell below onwards
+     */
+    let mid_row = (first_row + last_row) / 2;
+    let forward = forward_costs(cell_cost, first, (mid_row, last_column));
+    let backward = backward_costs(cell_cost, (mid_row + 1, first_column), last);
+    let mut best = (f64::INFINITY, first_column, first_column);
+    for (offset, &cost) in forward.iter().enumerate() {
+        let crossings = [
+            Some(offset),
+            Some(offset + 1).filter(|&next| next < backward.len()),
+        ];
+        for next in crossings.into_iter().flatten() {
+            if cost + backward[next] < best.0 {
+                best = (
+                    cost + backward[next],
+                    first_column + offset,
+                    first_column + next,
+                );
+            }
+        }
+    }
+    let (_, mid_column, next_column) = best;
+
+    hirschberg_path(cell_cost, first, (mid_row, mid_column), path);
+    hirschberg_path(cell_cost, (mid_row + 1, next_column), last, path);
+}
+
+/// Run DTW over the full window with memory linear in the sizes of the time series, by recovering the
+/// warp path with Hirschberg's divide and conquer scheme instead of storing the actions of all cells.
+///
+/// The distance is computed by a first pass over the cost matrix which keeps two rows of accumulated
+/// costs, and is identical to the one of `dtw_ex`. The warp path is then built by recursively splitting
+/// the cost matrix at its middle row, where the best crossing is found from the accumulated costs of a
+/// forward pass over the upper half and a backward pass over the lower half. This roughly doubles the
+/// computation compared to `dtw_ex`. The warp path is optimal, but may differ from the one of `dtw_ex`
+/// when several warp paths have the same distance.
+///
+/// # Returns
+///
+/// The distance and warp path, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_hirschberg<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let cell_cost = |row: usize, column: usize| {
+        local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode)
+    };
+
+    let cost = forward_costs(&cell_cost, (1, 1), (rows, columns))[columns - 1];
+    let mut path = Vec::with_capacity(rows + columns);
+    hirschberg_path(&cell_cost, (1, 1), (rows, columns), &mut path);
+
+    Ok((final_distance(cost, distance_mode), Array1::from(path)))
+}
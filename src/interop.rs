@@ -0,0 +1,52 @@
+//! Exchange of DTW results with the Python DTW ecosystem (tslearn, dtaidistance).
+//!
+//! The format matches the output of tslearn's `dtw_path(s1, s2)`, with `s1` being the x time series and
+//! `s2` the y time series:
+//!
+//! ```json
+//! {"path": [[0, 0], [1, 0], [2, 1]], "distance": 1.5}
+//! ```
+//!
+//! Each pair of the path is `[x index, y index]` with 0 based indices, in ascending order from the first
+//! to the last aligned samples. This is the reverse of the `(row, column)` order of the warp paths of
+//! this crate, which is `(y index, x index)`.
+//!
+//! JSON has no representation of an infinite number, so the infinite distance of time series which
+//! cannot be aligned, e.g. because the window does not connect the last cell of the cost matrix to
+//! its origin, is written as `null` along with an empty path, and `null` is read back as an infinite
+//! distance.
+
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+use crate::dtw::DtwResult;
+
+#[derive(Serialize, Deserialize)]
+struct TslearnResult {
+    path: Vec<(usize, usize)>,
+    // the distance is required, whereas serde would default a missing Option to None
+    #[serde(deserialize_with = "Option::deserialize")]
+    distance: Option<f64>,
+}
+
+/// Serialize a DTW result into the JSON shape of tslearn's `dtw_path` output.
+pub fn to_tslearn_json(result: &DtwResult) -> String {
+    let (distance, path) = result;
+    let tslearn_result = TslearnResult {
+        path: path.iter().map(|&(row, column)| (column, row)).collect(),
+        distance: Some(*distance).filter(|distance| distance.is_finite()),
+    };
+    serde_json::to_string(&tslearn_result).expect("a DTW result is always serializable")
+}
+
+/// Parse the JSON shape of tslearn's `dtw_path` output back into a DTW result.
+pub fn from_tslearn_json(json: &str) -> serde_json::Result<DtwResult> {
+    let tslearn_result: TslearnResult = serde_json::from_str(json)?;
+    let path = Array1::from_iter(
+        tslearn_result
+            .path
+            .into_iter()
+            .map(|(x_index, y_index)| (y_index, x_index)),
+    );
+    Ok((tslearn_result.distance.unwrap_or(f64::INFINITY), path))
+}
@@ -0,0 +1,55 @@
+use ndarray::ArrayView1;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::bounds::CascadingBounds;
+use crate::enums::*;
+
+/// Find all pairs of a time series of `left` and a time series of `right` whose DTW distance, under
+/// DTW constrained to a Sakoe-Chiba band of `band_radius` samples, is at most `threshold`.
+///
+/// The time series of `right` are compared to each time series of `left` with its `CascadingBounds`,
+/// using the threshold as the best distance so far, so that most non matching pairs are discarded by
+/// LB_Kim and LB_Keogh, or by abandoning their DTW, without computing the full distance. With the
+/// `parallel` feature, the time series of `left` are distributed over the threads of the rayon thread
+/// pool.
+///
+/// # Returns
+///
+/// The index in `left`, the index in `right` and the distance of each matching pair, ordered by the
+/// index in `left` and then by the index in `right`.
+///
+/// # Panics
+///
+/// If the time series do not all have the same size, or are empty.
+pub fn similarity_join(
+    left: &[ArrayView1<f64>],
+    right: &[ArrayView1<f64>],
+    threshold: f64,
+    band_radius: usize,
+    distance_mode: DistanceMode,
+) -> Vec<(usize, usize, f64)> {
+    let matches = |(i, query): (usize, &ArrayView1<f64>)| {
+        let bounds = CascadingBounds::new(query, band_radius, distance_mode);
+        right
+            .iter()
+            .enumerate()
+            .filter_map(|(j, candidate)| {
+                assert_eq!(
+                    candidate.len(),
+                    query.len(),
+                    "the time series must have the same size"
+                );
+                bounds
+                    .distance(candidate, threshold)
+                    .map(|distance| (i, j, distance))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    #[cfg(feature = "parallel")]
+    let left = left.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let left = left.iter();
+    left.enumerate().flat_map(matches).collect()
+}
@@ -0,0 +1,89 @@
+use ndarray::{Array2, ArrayView1};
+
+use crate::dtw::soft_minimum;
+use crate::error::DtwError;
+
+/// The logarithm of the sum of the exponentials of three values, computed without overflow or
+/// underflow as the soft minimum of their opposites with a `gamma` of 1.
+fn log_sum_exp(a: f64, b: f64, c: f64) -> f64 {
+    -soft_minimum(-a, -b, -c, 1f64)
+}
+
+/// Compute the logarithm of the triangular Global Alignment Kernel (GAK) of Cuturi between two time
+/// series.
+///
+/// The kernel sums the products of the local kernels of the aligned samples over all warp paths, which
+/// makes it positive definite, unlike a kernel built from the DTW distance. The local kernel of two
+/// samples at a squared distance `d` is `exp(-d / (2 sigma^2)) / (2 - exp(-d / (2 sigma^2)))`, with
+/// the bandwidth `sigma`. With a positive `triangular`, the local kernel of samples with indices `i`
+/// and `j` is further weighted by `1 - |i - j| / triangular`, so that only the alignments of samples
+/// less than `triangular` apart contribute, and a `triangular` of 0 disables the weighting. The
+/// accumulation is done in log space, since the kernel underflows for long time series.
+///
+/// # Returns
+///
+/// The logarithm of the kernel value, which is negative infinity if no warp path contributes, or
+/// `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn gak_log<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    sigma: f64,
+    triangular: usize,
+) -> Result<f64, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+
+    let mut log_kernels = Array2::from_elem((rows + 1, columns + 1), f64::NEG_INFINITY);
+    log_kernels[(0, 0)] = 0f64;
+    for row in 1..=rows {
+        for column in 1..=columns {
+            let phase_difference = row.abs_diff(column);
+            if triangular > 0 && phase_difference >= triangular {
+                continue;
+            }
+            let difference: f64 = x[column - 1].into() - y[row - 1].into();
+            let scaled = difference * difference / (2f64 * sigma * sigma);
+            let mut log_kernel = -scaled - (2f64 - (-scaled).exp()).ln();
+            if triangular > 0 {
+                log_kernel += (1f64 - phase_difference as f64 / triangular as f64).ln();
+            }
+            log_kernels[(row, column)] = log_kernel
+                + log_sum_exp(
+                    log_kernels[(row - 1, column)],
+                    log_kernels[(row, column - 1)],
+                    log_kernels[(row - 1, column - 1)],
+                );
+        }
+    }
+    Ok(log_kernels[(rows, columns)])
+}
+
+/// Compute the triangular Global Alignment Kernel normalized by the kernels of each time series with
+/// itself, `exp(gak_log(x, y) - (gak_log(x, x) + gak_log(y, y)) / 2)`, see `gak_log`.
+///
+/// The normalized kernel is 1 for identical time series and lies between 0 and 1, which makes it
+/// comparable across time series of different sizes.
+///
+/// # Returns
+///
+/// The normalized kernel value, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn gak_normalized<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    sigma: f64,
+    triangular: usize,
+) -> Result<f64, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let xy = gak_log(x, y, sigma, triangular)?;
+    let xx = gak_log(x, x, sigma, triangular)?;
+    let yy = gak_log(y, y, sigma, triangular)?;
+    Ok((xy - (xx + yy) / 2f64).exp())
+}
@@ -1,11 +1,81 @@
+pub mod alignment;
+pub mod antidiagonal;
+pub mod barycenter;
+pub mod block;
+pub mod bounds;
+pub mod builder;
+pub mod categorical;
+pub mod classify;
+pub mod clustering;
 pub mod cost;
+pub mod discord;
+pub mod distance_matrix;
 pub mod dtw;
+pub mod edit;
 pub mod enums;
+pub mod error;
 pub mod fastdtw;
+pub mod fixed;
+pub mod float;
+pub mod hirschberg;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod join;
+pub mod kernel;
+pub mod measure;
+pub mod motif;
+pub mod multivariate;
+pub mod online;
+pub mod path;
+pub mod preprocess;
+pub mod pruned;
+pub mod psi;
+pub mod search;
+pub mod slope;
+pub mod softdtw;
+pub mod subsequence;
+#[cfg(feature = "testdata")]
+pub mod testdata;
+pub mod weighted;
 pub mod window;
 
+pub use crate::alignment::*;
+pub use crate::antidiagonal::*;
+pub use crate::barycenter::*;
+pub use crate::block::*;
+pub use crate::bounds::*;
+pub use crate::builder::*;
+pub use crate::categorical::*;
+pub use crate::classify::*;
+pub use crate::clustering::*;
 pub use crate::cost::*;
+pub use crate::discord::*;
+pub use crate::distance_matrix::*;
 pub use crate::dtw::*;
+pub use crate::edit::*;
 pub use crate::enums::*;
+pub use crate::error::*;
 pub use crate::fastdtw::*;
+pub use crate::fixed::*;
+pub use crate::float::*;
+pub use crate::hirschberg::*;
+#[cfg(feature = "interop")]
+pub use crate::interop::*;
+pub use crate::join::*;
+pub use crate::kernel::*;
+pub use crate::measure::*;
+pub use crate::motif::*;
+pub use crate::multivariate::*;
+pub use crate::online::*;
+pub use crate::path::*;
+pub use crate::preprocess::*;
+pub use crate::pruned::*;
+pub use crate::psi::*;
+pub use crate::search::*;
+pub use crate::slope::*;
+pub use crate::softdtw::*;
+pub use crate::subsequence::*;
+#[cfg(feature = "testdata")]
+pub use crate::testdata::*;
+pub use crate::weighted::*;
 pub use crate::window::*;
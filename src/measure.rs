@@ -0,0 +1,207 @@
+use ndarray::ArrayView1;
+
+use crate::dtw::dtw_ex;
+use crate::edit::{edr, erp, lcss, msm, twe};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::fastdtw::fastdtw_ex;
+use crate::window::FullWindow;
+
+/// A distance between two time series which may align them elastically, so that generic code (nearest
+/// neighbor search, clustering, ...) can be written once and used with any of the measures.
+pub trait ElasticMeasure {
+    /// The distance between `x` and `y`, or the error of the underlying computation, e.g.
+    /// `DtwError::EmptyTimeSeries` if either time series has no samples.
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError>;
+}
+
+/// Builds the window of a DTW run from the number of rows and columns of the cost matrix.
+///
+/// The factory is `Send + Sync`, so that a measure can be shared between threads (e.g. by `pdist`).
+pub type WindowFactory =
+    Box<dyn Fn(usize, usize) -> Box<dyn Iterator<Item = (usize, usize)>> + Send + Sync>;
+
+/// DTW as an `ElasticMeasure`, computed with `dtw_ex` on the window built by `window_factory`.
+pub struct Dtw {
+    pub distance_mode: DistanceMode,
+    pub window_factory: WindowFactory,
+}
+
+impl Dtw {
+    /// DTW over the full window.
+    pub fn new(distance_mode: DistanceMode) -> Self {
+        Self {
+            distance_mode,
+            window_factory: Box::new(|rows, columns| Box::new(FullWindow::new(rows, columns))),
+        }
+    }
+
+    /// DTW over the window built by `window_factory` for each pair of time series.
+    pub fn with_window(distance_mode: DistanceMode, window_factory: WindowFactory) -> Self {
+        Self {
+            distance_mode,
+            window_factory,
+        }
+    }
+}
+
+impl ElasticMeasure for Dtw {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = (self.window_factory)(y.shape()[0], x.shape()[0]);
+        let (distance, _) = dtw_ex(
+            x,
+            y,
+            window,
+            self.distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )?;
+        Ok(distance)
+    }
+}
+
+/// The FastDTW approximation of DTW as an `ElasticMeasure`, computed with `fastdtw_ex`.
+pub struct FastDtw {
+    pub resolution_factor: usize,
+    pub search_radius: usize,
+    pub distance_mode: DistanceMode,
+}
+
+impl FastDtw {
+    /// FastDTW halving the time series at each level, with the given search radius.
+    pub fn new(search_radius: usize, distance_mode: DistanceMode) -> Self {
+        Self {
+            resolution_factor: 2,
+            search_radius,
+            distance_mode,
+        }
+    }
+}
+
+impl ElasticMeasure for FastDtw {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let (distance, _) = fastdtw_ex(
+            x,
+            y,
+            self.resolution_factor,
+            self.search_radius,
+            self.distance_mode,
+        )?;
+        Ok(distance)
+    }
+}
+
+/// MSM as an `ElasticMeasure` over the full window, computed with `msm`.
+pub struct Msm {
+    pub cost: f64,
+}
+
+impl ElasticMeasure for Msm {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = FullWindow::new(y.shape()[0], x.shape()[0]);
+        let (distance, _) = msm(x, y, window, self.cost)?;
+        Ok(distance)
+    }
+}
+
+/// TWE as an `ElasticMeasure` over the full window, computed with `twe`.
+pub struct Twe {
+    pub nu: f64,
+    pub lambda: f64,
+}
+
+impl ElasticMeasure for Twe {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = FullWindow::new(y.shape()[0], x.shape()[0]);
+        let (distance, _) = twe(x, y, window, self.nu, self.lambda)?;
+        Ok(distance)
+    }
+}
+
+/// ERP as an `ElasticMeasure` over the full window, computed with `erp`.
+pub struct Erp {
+    pub g: f64,
+}
+
+impl ElasticMeasure for Erp {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = FullWindow::new(y.shape()[0], x.shape()[0]);
+        let (distance, _) = erp(x, y, window, self.g)?;
+        Ok(distance)
+    }
+}
+
+/// EDR as an `ElasticMeasure` over the full window, computed with `edr`.
+pub struct Edr {
+    pub epsilon: f64,
+}
+
+impl ElasticMeasure for Edr {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = FullWindow::new(y.shape()[0], x.shape()[0]);
+        let (distance, _) = edr(x, y, window, self.epsilon)?;
+        Ok(distance)
+    }
+}
+
+/// LCSS as an `ElasticMeasure` over the full window, computed with `lcss`.
+pub struct Lcss {
+    pub epsilon: f64,
+    pub delta: usize,
+}
+
+impl ElasticMeasure for Lcss {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        let window = FullWindow::new(y.shape()[0], x.shape()[0]);
+        let (distance, _) = lcss(x, y, window, self.epsilon, self.delta)?;
+        Ok(distance)
+    }
+}
+
+/// The complexity estimate of a time series as defined by Batista et al., the length of the line
+/// through its samples when stretched out, `sqrt(sum((v[i + 1] - v[i])^2))`.
+pub fn complexity_estimate(ts: &ArrayView1<f64>) -> f64 {
+    ts.windows(2)
+        .into_iter()
+        .map(|pair| (pair[1] - pair[0]) * (pair[1] - pair[0]))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// The complexity correction factor of two time series, the ratio of the larger to the smaller
+/// complexity estimate, see `complexity_estimate`. The factor is 1 if both time series are constant,
+/// and infinite if only one of them is.
+pub fn complexity_correction(x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> f64 {
+    let (x_complexity, y_complexity) = (complexity_estimate(x), complexity_estimate(y));
+    let (smaller, larger) = (
+        x_complexity.min(y_complexity),
+        x_complexity.max(y_complexity),
+    );
+    match larger {
+        0f64 => 1f64,
+        _ => larger / smaller,
+    }
+}
+
+/// The Complexity-Invariant Distance (CID) of Batista et al. over any `ElasticMeasure`: the distance of
+/// the wrapped measure multiplied by the complexity correction factor of the two time series, see
+/// `complexity_correction`.
+///
+/// Under the wrapped measure alone, a jagged time series is often nearer to a smooth one than to other
+/// jagged ones, since smooth time series are close to everything. The correction factor increases the
+/// distances between time series of different complexities to compensate.
+pub struct Cid<M> {
+    pub measure: M,
+}
+
+impl<M: ElasticMeasure> Cid<M> {
+    pub fn new(measure: M) -> Self {
+        Self { measure }
+    }
+}
+
+impl<M: ElasticMeasure> ElasticMeasure for Cid<M> {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> Result<f64, DtwError> {
+        Ok(self.measure.distance(x, y)? * complexity_correction(x, y))
+    }
+}
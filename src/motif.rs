@@ -0,0 +1,82 @@
+use ndarray::{s, ArrayView1};
+
+use crate::bounds::CascadingBounds;
+use crate::enums::*;
+
+/// A pair of similar subsequences found by `motifs`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Motif {
+    /// The index of the first sample of the earlier subsequence.
+    pub first: usize,
+    /// The index of the first sample of the later subsequence.
+    pub second: usize,
+    /// The DTW distance between the two subsequences.
+    pub distance: f64,
+}
+
+/// Find the `count` most similar pairs of subsequences of `length` samples within a time series, under
+/// DTW constrained to a Sakoe-Chiba band of `band` samples.
+///
+/// The two subsequences of a pair do not overlap, and neither overlaps the subsequences of the pairs
+/// found before it, so that the motifs are distinct patterns rather than shifts of the same one. The
+/// pairs are found greedily, the most similar first. Each search compares every subsequence with the
+/// later ones through the `CascadingBounds` of the earlier subsequence, which discard most pairs once
+/// they cannot be more similar than the best pair found so far. On equal distances, the pair of
+/// earlier subsequences is kept.
+///
+/// # Returns
+///
+/// The motifs by increasing distance, fewer than `count` if the time series is too short to hold more
+/// non overlapping pairs.
+///
+/// # Panics
+///
+/// If `length` is 0.
+pub fn motifs(
+    series: &ArrayView1<f64>,
+    length: usize,
+    count: usize,
+    band: usize,
+    distance_mode: DistanceMode,
+) -> Vec<Motif> {
+    assert_ne!(length, 0, "the subsequences must not be empty");
+    let size = series.shape()[0];
+    let mut found: Vec<Motif> = Vec::with_capacity(count);
+    // whether a subsequence overlaps one of the motifs found so far
+    let is_taken = |found: &[Motif], start: usize| {
+        found.iter().any(|motif| {
+            start.abs_diff(motif.first) < length || start.abs_diff(motif.second) < length
+        })
+    };
+
+    while found.len() < count && size >= 2 * length {
+        let mut best: Option<Motif> = None;
+        for first in (0..=size - 2 * length).filter(|&first| !is_taken(&found, first)) {
+            let bounds = CascadingBounds::new(
+                &series.slice(s![first..first + length]),
+                band,
+                distance_mode,
+            );
+            for second in
+                (first + length..=size - length).filter(|&second| !is_taken(&found, second))
+            {
+                let best_so_far = best.map_or(f64::INFINITY, |motif| motif.distance);
+                let candidate = series.slice(s![second..second + length]);
+                if let Some(distance) = bounds.distance(&candidate, best_so_far) {
+                    if distance < best_so_far {
+                        best = Some(Motif {
+                            first,
+                            second,
+                            distance,
+                        });
+                    }
+                }
+            }
+        }
+        match best {
+            Some(motif) => found.push(motif),
+            None => break,
+        }
+    }
+    found
+}
@@ -0,0 +1,350 @@
+use ndarray::{s, Array1, Array2, ArrayView1, ArrayView2, Axis};
+
+use crate::dtw::{checked_dtw_cells, combined_cost, dtw_ex, local_cost, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::*;
+
+/// Compute the local cost between two feature vectors, as the sum of the local costs of their features,
+/// or their largest local cost for the Chebyshev mode. This is e.g. the Manhattan distance, or the
+/// squared Euclidean distance, between the vectors.
+fn vector_cost<T>(a: &ArrayView1<T>, b: &ArrayView1<T>, mode: DistanceMode) -> f64
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    combined_cost(
+        a.iter()
+            .zip(b.iter())
+            .map(|(&a, &b)| local_cost(a.into(), b.into(), mode)),
+        mode,
+    )
+}
+
+/// Run DTW on multivariate time series, where each row of `x` and `y` is a sample (a timestamp) and each
+/// column is a feature dimension.
+///
+/// The local cost of a cell is computed over the whole feature vectors: the Manhattan distance between
+/// the vectors for the manhattan mode, and the squared Euclidean distance for the euclidean mode, whose
+/// final distance is then the square root of the accumulated cost as in the univariate case. A
+/// multivariate time series with a single feature gives the same result as `dtw_ex` on the feature.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::DimensionMismatch` if `x` and `y` have a different
+/// number of features. Empty time series and malformed windows are reported as for `dtw_ex`.
+pub fn dtw_multivariate<T, W>(
+    x: &ArrayView2<T>,
+    y: &ArrayView2<T>,
+    window: W,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    check_features(x, y)?;
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| vector_cost(&x.row(column - 1), &y.row(row - 1), distance_mode),
+    )
+}
+
+/// Invert a covariance matrix into the precision matrix used by `dtw_mahalanobis`, with Gauss-Jordan
+/// elimination and partial pivoting.
+///
+/// # Returns
+///
+/// The inverse of `covariance`, or `None` if it is singular, e.g. when a feature is constant or is a
+/// linear combination of the others.
+///
+/// # Panics
+///
+/// If `covariance` is not a square matrix.
+pub fn precision_matrix(covariance: &ArrayView2<f64>) -> Option<Array2<f64>> {
+    let size = covariance.shape()[0];
+    assert_eq!(
+        size,
+        covariance.shape()[1],
+        "the covariance matrix must be square"
+    );
+    let scale = covariance.iter().fold(0f64, |scale, &v| scale.max(v.abs()));
+    let mut matrix = covariance.to_owned();
+    let mut inverse = Array2::<f64>::eye(size);
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| {
+                matrix[[a, column]]
+                    .abs()
+                    .total_cmp(&matrix[[b, column]].abs())
+            })
+            .expect("the pivot is searched in a non-empty range");
+        if matrix[[pivot, column]].abs() <= scale * size as f64 * f64::EPSILON {
+            return None;
+        }
+        for m in [&mut matrix, &mut inverse] {
+            for k in 0..size {
+                m.swap([column, k], [pivot, k]);
+            }
+        }
+        let divisor = matrix[[column, column]];
+        matrix.row_mut(column).mapv_inplace(|v| v / divisor);
+        inverse.row_mut(column).mapv_inplace(|v| v / divisor);
+        for row in (0..size).filter(|&row| row != column) {
+            let factor = matrix[[row, column]];
+            if factor != 0f64 {
+                let (pivot_row, pivot_inverse) = (
+                    matrix.row(column).to_owned(),
+                    inverse.row(column).to_owned(),
+                );
+                matrix.row_mut(row).scaled_add(-factor, &pivot_row);
+                inverse.row_mut(row).scaled_add(-factor, &pivot_inverse);
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// Run DTW on multivariate time series with the Mahalanobis distance between the feature vectors as
+/// the local cost, so that correlated features or features of large variance do not dominate the
+/// alignment.
+///
+/// The local cost of a cell is the squared Mahalanobis distance `d^T P d`, where `d` is the difference
+/// between the feature vectors and `P` is the `precision` matrix, the inverse of the covariance of the
+/// features (see `precision_matrix`). As for the euclidean mode, the distance is the square root of
+/// the accumulated cost. The identity precision matrix gives `dtw_multivariate` with the euclidean
+/// mode. The precision matrix should be symmetric positive semi-definite; negative local costs are
+/// clamped to zero.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::DimensionMismatch` if `x` and `y` have a different
+/// number of features. Empty time series and malformed windows are reported as for `dtw_ex`.
+///
+/// # Panics
+///
+/// If `precision` is not a square matrix with a row and a column per feature.
+pub fn dtw_mahalanobis<T, W>(
+    x: &ArrayView2<T>,
+    y: &ArrayView2<T>,
+    window: W,
+    precision: &ArrayView2<f64>,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    check_features(x, y)?;
+    let features = x.shape()[1];
+    assert_eq!(
+        precision.shape(),
+        [features, features],
+        "the precision matrix must have a row and a column per feature"
+    );
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| {
+            let difference = Array1::from_iter(
+                x.row(column - 1)
+                    .iter()
+                    .zip(y.row(row - 1).iter())
+                    .map(|(&a, &b)| a.into() - b.into()),
+            );
+            difference.dot(&precision.dot(&difference)).max(0f64)
+        },
+    )
+}
+
+/// The mean radius of the Earth in meters, used by `haversine_distance`.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Compute the great-circle distance in meters between two `(latitude, longitude)` positions given in
+/// degrees, with the haversine formula on a spherical Earth of radius `EARTH_RADIUS`.
+pub fn haversine_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (latitude_a, latitude_b) = (a.0.to_radians(), b.0.to_radians());
+    let half_latitude = (latitude_b - latitude_a) / 2f64;
+    let half_longitude = (b.1 - a.1).to_radians() / 2f64;
+    let h = half_latitude.sin().powi(2)
+        + latitude_a.cos() * latitude_b.cos() * half_longitude.sin().powi(2);
+    2f64 * EARTH_RADIUS * h.sqrt().min(1f64).asin()
+}
+
+/// Run DTW on GPS trajectories, where each row of `x` and `y` is a `(latitude, longitude)` position
+/// in degrees. The local cost of a cell is the `haversine_distance` between the positions, and the
+/// distance is the accumulated cost, i.e. the sum of the distances in meters between the aligned
+/// positions.
+///
+/// # Returns
+///
+/// The distance and the warp path, or `DtwError::DimensionMismatch` if `x` and `y` do not both have two
+/// columns. Empty time series and malformed windows are reported as for `dtw_ex`.
+pub fn dtw_haversine<W>(
+    x: &ArrayView2<f64>,
+    y: &ArrayView2<f64>,
+    window: W,
+) -> Result<DtwResult, DtwError>
+where
+    W: Iterator<Item = (usize, usize)>,
+{
+    if x.shape()[1] != 2 || y.shape()[1] != 2 {
+        return Err(DtwError::DimensionMismatch {
+            x_features: x.shape()[1],
+            y_features: y.shape()[1],
+        });
+    }
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| {
+            haversine_distance(
+                (x[[column - 1, 0]], x[[column - 1, 1]]),
+                (y[[row - 1, 0]], y[[row - 1, 1]]),
+            )
+        },
+    )
+}
+
+/// Check that the samples of the two time series have the same number of features.
+fn check_features<T>(x: &ArrayView2<T>, y: &ArrayView2<T>) -> Result<(), DtwError> {
+    match x.shape()[1] == y.shape()[1] {
+        true => Ok(()),
+        false => Err(DtwError::DimensionMismatch {
+            x_features: x.shape()[1],
+            y_features: y.shape()[1],
+        }),
+    }
+}
+
+/// Shrink a multivariate time series by `resolution_factor`, averaging each feature over blocks of
+/// consecutive samples like the univariate FastDTW.
+fn coarse_multivariate_time_series<T>(ts: &ArrayView2<T>, resolution_factor: usize) -> Array2<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    assert!(resolution_factor > 0);
+
+    let size = ts.shape()[0];
+    let rounded_coarsed_size = size.div_ceil(resolution_factor);
+    let mut result = Array2::<f64>::zeros((rounded_coarsed_size, ts.shape()[1]));
+    for (i, mut coarse_sample) in result.axis_iter_mut(Axis(0)).enumerate() {
+        let start = i * resolution_factor;
+        let end = usize::min(start + resolution_factor, size);
+        let block = ts.slice(s![start..end, ..]).map(|&v| v.into());
+        coarse_sample.assign(&block.mean_axis(Axis(0)).expect("the block is not empty"));
+    }
+    result
+}
+
+/// Run the FastDTW approximation on multivariate time series, with the local cost of
+/// `dtw_multivariate`. The coarse levels average each feature over blocks of `resolution_factor`
+/// samples; otherwise this works like `fastdtw_ex`.
+pub fn fastdtw_multivariate<T>(
+    x: &ArrayView2<T>,
+    y: &ArrayView2<T>,
+    resolution_factor: usize,
+    search_radius: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    check_features(x, y)?;
+    let min_ts_size: usize = search_radius + 2;
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    if columns <= min_ts_size || rows <= min_ts_size {
+        // base case: for a very small time series run the full dtw algorithm
+        dtw_multivariate(x, y, FullWindow::new(rows, columns), distance_mode)
+    } else {
+        // recursive case: project the warp path from the coarser resolution
+        let coarse_x = coarse_multivariate_time_series(x, resolution_factor);
+        let coarse_y = coarse_multivariate_time_series(y, resolution_factor);
+
+        let (_, low_res_path) = fastdtw_multivariate(
+            &coarse_x.view(),
+            &coarse_y.view(),
+            resolution_factor,
+            search_radius,
+            distance_mode,
+        )?;
+
+        let constrained_window = ConstrainedWindow::from_low_res_path(
+            low_res_path,
+            resolution_factor,
+            search_radius,
+            rows,
+            columns,
+        );
+
+        dtw_multivariate(x, y, constrained_window, distance_mode)
+    }
+}
+
+/// Compute the DTW distance between multivariate time series using the given alignment strategy, over
+/// the full window.
+///
+/// With `MultivariateMode::Dependent`, this is the distance of `dtw_multivariate`, where all features
+/// share a single warp path. With `MultivariateMode::Independent`, each feature is aligned on its own
+/// and the distance is the sum of the DTW distances of the features. The independent distance is never
+/// larger than the dependent one for the manhattan mode, since each feature may follow its best warp
+/// path.
+///
+/// # Returns
+///
+/// The distance, or an error as for `dtw_multivariate`.
+pub fn multivariate_distance<T>(
+    x: &ArrayView2<T>,
+    y: &ArrayView2<T>,
+    multivariate_mode: MultivariateMode,
+    distance_mode: DistanceMode,
+) -> Result<f64, DtwError>
+where
+    T: std::ops::Sub + std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    match multivariate_mode {
+        MultivariateMode::Dependent => {
+            let (distance, _) =
+                dtw_multivariate(x, y, FullWindow::new(rows, columns), distance_mode)?;
+            Ok(distance)
+        }
+        MultivariateMode::Independent => {
+            check_features(x, y)?;
+            x.axis_iter(Axis(1))
+                .zip(y.axis_iter(Axis(1)))
+                .map(|(x_feature, y_feature)| {
+                    let (distance, _) = dtw_ex(
+                        &x_feature,
+                        &y_feature,
+                        FullWindow::new(rows, columns),
+                        distance_mode,
+                        StorageStrategy::Auto,
+                        f64::INFINITY,
+                    )?;
+                    Ok(distance)
+                })
+                .sum()
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::dtw::{final_distance, local_cost, minimum};
+use crate::enums::*;
+
+/// DTW between two time series which grow over time, such as a live sensor signal aligned against a
+/// reference which is still being recorded.
+///
+/// Samples are appended to either time series with `push_x` and `push_y`. Instead of the whole cost
+/// matrix, only its last column and last row are retained, since appending a sample to x adds a column
+/// which only depends on the previous one, and appending a sample to y adds a row which only depends on
+/// the previous one. Each push thus costs time linear in the size of the other time series, and the
+/// distance is always the one `dtw_ex` would compute over the full window for the samples pushed so
+/// far. The warp path is not available, since it would require the whole cost matrix.
+pub struct OnlineDtw {
+    distance_mode: DistanceMode,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    /// The accumulated costs of the last column, one per row, or infinite costs while x is empty.
+    last_column: Vec<f64>,
+    /// The accumulated costs of the last row, one per column, or infinite costs while y is empty.
+    last_row: Vec<f64>,
+}
+
+impl OnlineDtw {
+    pub fn new(distance_mode: DistanceMode) -> Self {
+        Self {
+            distance_mode,
+            x: Vec::new(),
+            y: Vec::new(),
+            last_column: Vec::new(),
+            last_row: Vec::new(),
+        }
+    }
+
+    /// Append a sample to the x time series, which adds a column to the cost matrix.
+    pub fn push_x<T>(&mut self, sample: T)
+    where
+        T: std::convert::Into<f64>,
+    {
+        let sample = sample.into();
+        // the origin precedes the first column only
+        let boundary = match self.x.is_empty() {
+            true => 0f64,
+            false => f64::INFINITY,
+        };
+        let mut column = Vec::with_capacity(self.y.len());
+        for row in 0..self.y.len() {
+            let cost = local_cost(sample, self.y[row], self.distance_mode);
+            let (above, diagonal) = match row {
+                0 => (f64::INFINITY, boundary),
+                _ => (column[row - 1], self.last_column[row - 1]),
+            };
+            let (value, _) = minimum(above, self.last_column[row], diagonal);
+            column.push(cost + value);
+        }
+        self.x.push(sample);
+        self.last_row
+            .push(column.last().copied().unwrap_or(f64::INFINITY));
+        self.last_column = column;
+    }
+
+    /// Append a sample to the y time series, which adds a row to the cost matrix.
+    pub fn push_y<T>(&mut self, sample: T)
+    where
+        T: std::convert::Into<f64>,
+    {
+        let sample = sample.into();
+        // the origin precedes the first row only
+        let boundary = match self.y.is_empty() {
+            true => 0f64,
+            false => f64::INFINITY,
+        };
+        let mut row = Vec::with_capacity(self.x.len());
+        for column in 0..self.x.len() {
+            let cost = local_cost(self.x[column], sample, self.distance_mode);
+            let (left, diagonal) = match column {
+                0 => (f64::INFINITY, boundary),
+                _ => (row[column - 1], self.last_row[column - 1]),
+            };
+            let (value, _) = minimum(self.last_row[column], left, diagonal);
+            row.push(cost + value);
+        }
+        self.y.push(sample);
+        self.last_column
+            .push(row.last().copied().unwrap_or(f64::INFINITY));
+        self.last_row = row;
+    }
+
+    /// The number of samples pushed to the x time series.
+    pub fn x_len(&self) -> usize {
+        self.x.len()
+    }
+
+    /// The number of samples pushed to the y time series.
+    pub fn y_len(&self) -> usize {
+        self.y.len()
+    }
+
+    /// The DTW distance between the samples pushed so far, or `None` while either time series is empty.
+    pub fn distance(&self) -> Option<f64> {
+        let cost = self.last_row.last().filter(|_| !self.y.is_empty())?;
+        Some(final_distance(*cost, self.distance_mode))
+    }
+}
@@ -0,0 +1,246 @@
+use ndarray::{Array1, ArrayView1};
+use std::ops::Range;
+
+use crate::enums::*;
+
+/// Collapse a warp path into a warping function, giving one aligned index of y per index of x.
+///
+/// Since the warp path is monotonic, the indices of y aligned to an index of x form a contiguous run.
+/// An index of x aligned to a single index of y maps to it, and an index of x aligned to a run of
+/// indices maps to the midpoint of the run (rounded down). The resulting warping function is monotonic
+/// non-decreasing and can be used as a resampling map from x onto y.
+///
+/// # Arguments
+///
+/// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by `dtw_ex`
+///
+/// * `x_size` - the number of samples in the x time series, which all have to be covered by the path
+pub fn warping_function(path: &Array1<(usize, usize)>, x_size: usize) -> Array1<usize> {
+    // the first and last index of y aligned to each index of x
+    let mut runs = Array1::<(usize, usize)>::from_elem(x_size, (usize::MAX, 0));
+    for &(row, column) in path.iter() {
+        let (first, last) = &mut runs[column];
+        *first = usize::min(*first, row);
+        *last = usize::max(*last, row);
+    }
+
+    runs.map(|&(first, last)| {
+        assert!(
+            first <= last,
+            "the warp path does not cover all indices of x"
+        );
+        first + (last - first) / 2
+    })
+}
+
+/// Split a warp path into two aligned index arrays, `(index_x, index_y)`, where step `k` of the path
+/// aligns sample `index_x[k]` of x to sample `index_y[k]` of y. This is the layout expected by most
+/// plotting libraries, e.g. for drawing a line between each pair of aligned samples.
+///
+/// # Arguments
+///
+/// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by `dtw_ex`
+pub fn index_arrays(path: &Array1<(usize, usize)>) -> (Array1<usize>, Array1<usize>) {
+    (path.map(|&(_, column)| column), path.map(|&(row, _)| row))
+}
+
+/// The ranges of indices which a warp path aligns to each index of x and of y.
+///
+/// Since the warp path is monotonic, the indices of y aligned to an index of x form a contiguous range,
+/// and vice versa.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexMapping {
+    x_to_y: Vec<Range<usize>>,
+    y_to_x: Vec<Range<usize>>,
+}
+
+impl IndexMapping {
+    /// Collect the ranges of aligned indices from a warp path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by
+    ///   `dtw_ex`
+    ///
+    /// * `x_size` - the number of samples in the x time series, which all have to be covered by the path
+    ///
+    /// * `y_size` - the number of samples in the y time series, which all have to be covered by the path
+    ///
+    /// # Panics
+    ///
+    /// If the path does not cover all indices of x and y.
+    pub fn new(path: &Array1<(usize, usize)>, x_size: usize, y_size: usize) -> Self {
+        // the ranges start out empty, and grow to the first and last aligned indices
+        let uncovered = Range {
+            start: usize::MAX,
+            end: 0,
+        };
+        let mut x_to_y = vec![uncovered.clone(); x_size];
+        let mut y_to_x = vec![uncovered; y_size];
+        for &(row, column) in path.iter() {
+            for (range, index) in [(&mut x_to_y[column], row), (&mut y_to_x[row], column)] {
+                range.start = usize::min(range.start, index);
+                range.end = usize::max(range.end, index + 1);
+            }
+        }
+        assert!(
+            x_to_y
+                .iter()
+                .chain(y_to_x.iter())
+                .all(|range| !range.is_empty()),
+            "the warp path does not cover all indices of x and y"
+        );
+        Self { x_to_y, y_to_x }
+    }
+
+    /// The indices of y aligned to index `i` of x.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not an index of x.
+    pub fn map_x_to_y(&self, i: usize) -> Range<usize> {
+        self.x_to_y[i].clone()
+    }
+
+    /// The indices of x aligned to index `j` of y.
+    ///
+    /// # Panics
+    ///
+    /// If `j` is not an index of y.
+    pub fn map_y_to_x(&self, j: usize) -> Range<usize> {
+        self.y_to_x[j].clone()
+    }
+}
+
+/// Measure how much a warp path deviates from the diagonal of the cost matrix.
+///
+/// Each cell of the path is mapped to its relative position `(row / (y_size - 1), column / (x_size - 1))`
+/// in the unit square, and the result is the mean of `|row / (y_size - 1) - column / (x_size - 1)|`
+/// over the cells of the path. This approximates the area between the path and the diagonal, and does
+/// not depend on the lengths of the time series. A value of zero means that the path follows the
+/// diagonal, i.e. the time series are aligned without warping, while the maximum of 1 is approached by
+/// paths which run along the borders of the cost matrix.
+///
+/// # Arguments
+///
+/// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by `dtw_ex`
+///
+/// * `x_size` - the number of samples in the x time series (the number of columns)
+///
+/// * `y_size` - the number of samples in the y time series (the number of rows)
+pub fn warp_amount(path: &Array1<(usize, usize)>, x_size: usize, y_size: usize) -> f64 {
+    if path.is_empty() {
+        return 0f64;
+    }
+
+    // a time series of a single sample is at the start of the diagonal
+    let relative = |index: usize, size: usize| match size {
+        0 | 1 => 0f64,
+        _ => index as f64 / (size - 1) as f64,
+    };
+    let deviation: f64 = path
+        .iter()
+        .map(|&(row, column)| (relative(row, y_size) - relative(column, x_size)).abs())
+        .sum();
+    deviation / path.len() as f64
+}
+
+/// Carry a payload sampled like y (e.g. labels or a secondary channel) along a warp path, producing a
+/// payload aligned to x.
+///
+/// Each index of x takes the payload of the first index of y it is aligned to. Unlike the midpoint used
+/// by `warping_function`, the first index always exists in the payload, and for labels it keeps the
+/// label which starts the run of aligned samples.
+///
+/// # Arguments
+///
+/// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by `dtw_ex`
+///
+/// * `payload_y` - the payload, with one element per sample of the y time series
+///
+/// * `x_size` - the number of samples in the x time series, which all have to be covered by the path
+pub fn apply_warp<P: Copy>(
+    path: &Array1<(usize, usize)>,
+    payload_y: &ArrayView1<P>,
+    x_size: usize,
+) -> Array1<P> {
+    let mut first_rows = Array1::<usize>::from_elem(x_size, usize::MAX);
+    for &(row, column) in path.iter() {
+        first_rows[column] = usize::min(first_rows[column], row);
+    }
+
+    first_rows.map(|&row| {
+        assert!(
+            row != usize::MAX,
+            "the warp path does not cover all indices of x"
+        );
+        payload_y[row]
+    })
+}
+
+/// Resample the x time series onto the timeline of y along a warp path, producing the version of x
+/// stretched and compressed to align with y, with one sample per index of y.
+///
+/// An index of y aligned to several samples of x (where x is compressed) takes the average of these
+/// samples. A run of indices of y aligned to the same single sample of x (where x is stretched) either
+/// repeats the sample, or interpolates between it and the next sample of x, depending on
+/// `interpolation`.
+///
+/// # Arguments
+///
+/// * `x` - the time series to resample
+///
+/// * `path` - a warp path of `(row, column)` pairs, i.e. `(y index, x index)`, as returned by `dtw_ex`
+///
+/// * `y_size` - the number of samples in the y time series, which all have to be covered by the path
+///
+/// * `interpolation` - how to fill the indices of y aligned to a single sample of x
+pub fn warp<T>(
+    x: &ArrayView1<T>,
+    path: &Array1<(usize, usize)>,
+    y_size: usize,
+    interpolation: WarpInterpolation,
+) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    // the sum and the number of the samples of x aligned to each index of y, and the first of them
+    let mut aligned = Array1::<(f64, usize, usize)>::from_elem(y_size, (0f64, 0, usize::MAX));
+    for &(row, column) in path.iter() {
+        let (sum, count, first) = &mut aligned[row];
+        *sum += x[column].into();
+        *count += 1;
+        *first = usize::min(*first, column);
+    }
+    assert!(
+        aligned.iter().all(|&(_, count, _)| count > 0),
+        "the warp path does not cover all indices of y"
+    );
+
+    let mut result = aligned.map(|&(sum, count, _)| sum / count as f64);
+    if interpolation == WarpInterpolation::Linear {
+        let single = |row: usize| match aligned[row] {
+            (_, 1, column) => Some(column),
+            _ => None,
+        };
+        let mut start = 0;
+        while start < y_size {
+            // the run of indices of y aligned to the same single sample of x
+            let mut end = start + 1;
+            if let Some(column) = single(start) {
+                while end < y_size && single(end) == Some(column) {
+                    end += 1;
+                }
+                if column + 1 < x.shape()[0] {
+                    let (value, next) = (x[column].into(), x[column + 1].into());
+                    for row in start..end {
+                        let fraction = (row - start) as f64 / (end - start) as f64;
+                        result[row] = value + fraction * (next - value);
+                    }
+                }
+            }
+            start = end;
+        }
+    }
+    result
+}
@@ -0,0 +1,331 @@
+use ndarray::{s, Array1, ArrayView1};
+
+use crate::dtw::{checked_dtw_cells, combined_cost, dtw_ex, local_cost, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::fastdtw::paa;
+use crate::window::*;
+
+/// Smooth a time series with a Gaussian kernel of standard deviation `sigma` (in samples).
+///
+/// The kernel is truncated at `3 * sigma` samples on each side. At the boundaries of the time series,
+/// the kernel is truncated to the available samples and renormalized, so that a constant time series
+/// stays constant and no samples are invented by reflection. A `sigma` of zero returns the time series
+/// unchanged.
+pub fn gaussian_smooth<T>(ts: &ArrayView1<T>, sigma: f64) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    assert!(sigma >= 0f64);
+
+    let size = ts.shape()[0];
+    if sigma == 0f64 {
+        return ts.map(|&v| v.into());
+    }
+
+    let radius = (3f64 * sigma).ceil() as usize;
+    let kernel = Array1::from_iter(
+        (0..2 * radius + 1)
+            .map(|i| (-((i as f64 - radius as f64).powi(2)) / (2f64 * sigma * sigma)).exp()),
+    );
+
+    let mut result = Array1::<f64>::zeros(size);
+    for i in 0..size {
+        let start = i.saturating_sub(radius);
+        let end = usize::min(i + radius + 1, size);
+        let mut sum = 0f64;
+        let mut weight = 0f64;
+        for j in start..end {
+            let k = kernel[j + radius - i];
+            sum += k * ts[j].into();
+            weight += k;
+        }
+        result[i] = sum / weight;
+    }
+    result
+}
+
+/// Run DTW on the two time series after smoothing each of them with `gaussian_smooth`, which reduces
+/// the sensitivity of the alignment to high frequency noise.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if either time series
+/// has no samples.
+pub fn dtw_smoothed<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    sigma: f64,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let x = gaussian_smooth(x, sigma);
+    let y = gaussian_smooth(y, sigma);
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+}
+
+/// Replace each sample of a time series with its rank among all samples, so that only the relative
+/// order of the samples remains.
+///
+/// Ranks start at 1 for the smallest sample. Tied samples all get the average of the ranks they span,
+/// e.g. two samples tied for the smallest value both get rank 1.5. Any strictly increasing
+/// transformation of the samples leaves the ranks unchanged.
+pub fn rank_transform<T>(ts: &ArrayView1<T>) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let values: Vec<f64> = ts.iter().map(|&v| v.into()).collect();
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = Array1::<f64>::zeros(values.len());
+    let mut start = 0;
+    while start < order.len() {
+        // the run of tied samples starting at this position of the sorted order
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // the average of the 1 based ranks start + 1..=end
+        let rank = (start + 1 + end) as f64 / 2f64;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Run DTW on the rank transforms of the two time series, as computed by `rank_transform`. The
+/// alignment only depends on the relative order of the samples within each time series, which makes it
+/// robust to monotonic distortions of the amplitude.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if either time series
+/// has no samples.
+pub fn dtw_rank<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let x = rank_transform(x);
+    let y = rank_transform(y);
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+}
+
+/// Estimate the first derivative of a time series at each sample, as in the derivative DTW of Keogh
+/// and Pazzani.
+///
+/// The estimate at an inner sample is the average of the slope to the previous sample and of the slope
+/// between the two neighbors, `((v[i] - v[i - 1]) + (v[i + 1] - v[i - 1]) / 2) / 2`, which is less
+/// sensitive to outliers than the slope alone. The first and last samples have no neighbor on one side
+/// and take the estimates of the samples next to them. A time series of 2 samples has the slope
+/// between them as the estimate of both, and a single sample has an estimate of zero.
+pub fn derivative_estimate<T>(ts: &ArrayView1<T>) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let size = ts.shape()[0];
+    match size {
+        0 => return Array1::zeros(0),
+        1 => return Array1::zeros(1),
+        2 => return Array1::from_elem(2, ts[1].into() - ts[0].into()),
+        _ => {}
+    }
+    let mut result = Array1::<f64>::zeros(size);
+    for i in 1..size - 1 {
+        let (previous, current, next) = (ts[i - 1].into(), ts[i].into(), ts[i + 1].into());
+        result[i] = ((current - previous) + (next - previous) / 2f64) / 2f64;
+    }
+    result[0] = result[1];
+    result[size - 1] = result[size - 2];
+    result
+}
+
+/// The z-normalization of values of the given mean and standard deviation. A standard deviation of
+/// zero normalizes every value to zero.
+fn normalize(value: f64, mean: f64, std: f64) -> f64 {
+    match std > 0f64 {
+        true => (value - mean) / std,
+        false => 0f64,
+    }
+}
+
+/// Z-normalize a time series, i.e. subtract its mean and divide by its (population) standard
+/// deviation, so that the result has a mean of 0 and a standard deviation of 1. This is the
+/// normalization of `ucr_search`, and a constant time series is normalized to zeros.
+pub fn znormalize<T>(ts: &ArrayView1<T>) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let ts = ts.map(|&v| v.into());
+    let (mean, std) = match ts.mean() {
+        Some(mean) => (mean, ts.std(0f64)),
+        None => return ts,
+    };
+    ts.mapv(|v| normalize(v, mean, std))
+}
+
+/// Scale a time series linearly to the range [0, 1], its minimum becoming 0 and its maximum 1. A
+/// constant time series is scaled to zeros.
+pub fn min_max_scale<T>(ts: &ArrayView1<T>) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let ts = ts.map(|&v| v.into());
+    let minimum = ts.fold(f64::INFINITY, |minimum, &v| minimum.min(v));
+    let maximum = ts.fold(f64::NEG_INFINITY, |maximum, &v| maximum.max(v));
+    match maximum > minimum {
+        true => ts.mapv(|v| (v - minimum) / (maximum - minimum)),
+        false => ts.mapv(|_| 0f64),
+    }
+}
+
+/// Z-normalize each sample of a time series with the mean and standard deviation of the trailing
+/// window of `window` samples ending at it, which removes slow changes of the level and amplitude of
+/// long time series. The first samples use the shorter windows of the samples available so far, so
+/// the first sample is always normalized to zero. A `window` at least as large as the time series
+/// normalizes its last sample as `znormalize`.
+///
+/// # Panics
+///
+/// If `window` is 0.
+pub fn rolling_znormalize<T>(ts: &ArrayView1<T>, window: usize) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    assert!(window > 0, "the rolling window must not be empty");
+    let ts = ts.map(|&v| v.into());
+    Array1::from_iter((0..ts.shape()[0]).map(|i| {
+        let trailing = ts.slice(s![(i + 1).saturating_sub(window)..=i]);
+        let mean = trailing.mean().expect("the trailing window is not empty");
+        normalize(ts[i], mean, trailing.std(0f64))
+    }))
+}
+
+/// Downsample a time series by summarizing its consecutive blocks of `resolution_factor` samples with
+/// `pooling`. The last block is shorter when the size of the time series is not a multiple of
+/// `resolution_factor`. `Pooling::Mean` gives the same result as `paa`.
+///
+/// # Panics
+///
+/// If `resolution_factor` is 0.
+pub fn downsample<T>(ts: &ArrayView1<T>, resolution_factor: usize, pooling: Pooling) -> Array1<f64>
+where
+    T: std::ops::Add + Default + Copy + std::convert::Into<f64>,
+{
+    assert!(resolution_factor > 0, "the resolution factor must not be 0");
+    if pooling == Pooling::Mean {
+        return paa(ts, resolution_factor);
+    }
+    let size = ts.shape()[0];
+    Array1::from_iter((0..size).step_by(resolution_factor).map(|start| {
+        let end = usize::min(start + resolution_factor, size);
+        let block = ts.slice(s![start..end]).map(|&v| v.into());
+        match pooling {
+            Pooling::Max => block.fold(f64::NEG_INFINITY, |maximum, &v| maximum.max(v)),
+            Pooling::Min => block.fold(f64::INFINITY, |minimum, &v| minimum.min(v)),
+            Pooling::Mean => unreachable!("the mean is computed by paa"),
+            Pooling::Median => {
+                let mut sorted = block.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let middle = sorted.len() / 2;
+                match sorted.len() % 2 {
+                    0 => (sorted[middle - 1] + sorted[middle]) / 2f64,
+                    _ => sorted[middle],
+                }
+            }
+        }
+    }))
+}
+
+/// Apply `preprocessing` to a time series, e.g. before passing it to `fastdtw` or to any other variant
+/// of DTW.
+pub fn preprocess<T>(ts: &ArrayView1<T>, preprocessing: Preprocessing) -> Array1<f64>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    match preprocessing {
+        Preprocessing::None => ts.map(|&v| v.into()),
+        Preprocessing::Derivative => derivative_estimate(ts),
+        Preprocessing::ZNormalize => znormalize(ts),
+    }
+}
+
+/// Run DTW on the samples augmented with their first differences, which makes the alignment sensitive
+/// to both the level and the slope of the time series.
+///
+/// Each sample `v[i]` becomes the vector `(v[i], delta_weight * (v[i] - v[i - 1]))`, where the
+/// difference of the first sample is zero. The local cost between two vectors combines the local costs
+/// of their components as for `dtw_multivariate`, e.g. the Manhattan or squared Euclidean distance. A
+/// `delta_weight` of zero gives the standard DTW, and larger weights increasingly favor aligning samples
+/// with similar slopes.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`, or `DtwError::EmptyTimeSeries` if either time series
+/// has no samples.
+pub fn dtw_augmented<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    delta_weight: f64,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let differences = |ts: &ArrayView1<T>| {
+        Array1::from_iter((0..ts.shape()[0]).map(|i| match i {
+            0 => 0f64,
+            _ => delta_weight * (ts[i].into() - ts[i - 1].into()),
+        }))
+    };
+    let x_differences = differences(x);
+    let y_differences = differences(y);
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+
+    checked_dtw_cells(
+        rows,
+        columns,
+        FullWindow::new(rows, columns),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| {
+            let costs = [
+                local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode),
+                local_cost(
+                    x_differences[column - 1],
+                    y_differences[row - 1],
+                    distance_mode,
+                ),
+            ];
+            combined_cost(costs.into_iter(), distance_mode)
+        },
+    )
+}
@@ -0,0 +1,115 @@
+use ndarray::ArrayView1;
+
+use crate::cost::{CostBand, CostStorage};
+use crate::dtw::{backtrack, dtw_ex, final_distance, local_cost, minimum, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::FullWindow;
+
+/// Compute the accumulated cost of a warp path which follows the diagonal of the cost matrix as closely
+/// as possible, as an upper bound of the accumulated cost of the optimal warp path.
+///
+/// The costs are accumulated from the first cell onwards, in the same order as the DTW recurrence, so
+/// the accumulated cost of every cell computed by the recurrence is at most the partial sum of this
+/// path up to that cell, even with rounding.
+fn diagonal_upper_bound(
+    x: &ArrayView1<f64>,
+    y: &ArrayView1<f64>,
+    distance_mode: DistanceMode,
+) -> f64 {
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    let steps = usize::max(rows, columns);
+    (0..steps).fold(0f64, |cost, step| {
+        // consecutive steps advance the row and the column by at most 1
+        let (row, column) = match steps {
+            1 => (0, 0),
+            _ => (
+                step * (rows - 1) / (steps - 1),
+                step * (columns - 1) / (steps - 1),
+            ),
+        };
+        local_cost(x[column], y[row], distance_mode) + cost
+    })
+}
+
+/// Run DTW over the full window with the PrunedDTW optimization, which skips the cells whose
+/// accumulated cost exceeds an upper bound of the DTW distance, since they cannot be on the optimal
+/// warp path.
+///
+/// The upper bound is the accumulated cost of the warp path along the diagonal of the cost matrix. On
+/// each row, the computation starts at the first column whose cell on the previous row is within the
+/// upper bound, and stops once the cells to the right can only be reached from a cell of the same row
+/// which exceeds the upper bound. The pruning is exact: all cells with an accumulated cost within the
+/// upper bound are computed as with `dtw_ex`, so the distance and the warp path are identical to those
+/// of `dtw_ex` over the full window. The costs are stored in a `CostBand`, holding only the computed
+/// range of each row.
+///
+/// If a sample is not a number, neither is the upper bound, since the diagonal warp path crosses all
+/// samples. Nothing can then be pruned, and the result is the one of `dtw_ex` over the full window.
+///
+/// # Returns
+///
+/// The distance and warp path, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_pruned<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let x = x.map(|&v| v.into());
+    let y = y.map(|&v| v.into());
+    let upper_bound = diagonal_upper_bound(&x.view(), &y.view(), distance_mode);
+    if upper_bound.is_nan() {
+        return dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        );
+    }
+
+    let mut cost_band = CostBand::<f64>::new(rows);
+    // the range of columns within the upper bound on the previous row
+    let (mut start_column, mut end_column) = (1, 1);
+    for row in 1..=rows {
+        let mut next_start_column = None;
+        let mut next_end_column = 0;
+        for column in start_column..=columns {
+            let cost = local_cost(x[column - 1], y[row - 1], distance_mode);
+            let (value, action) = minimum(
+                cost_band.get_cost(row - 1, column),
+                cost_band.get_cost(row, column - 1),
+                cost_band.get_cost(row - 1, column - 1),
+            );
+            let value = cost + value;
+            /* beyond the end of the range of the previous row, a cell can only be reached from the
+             * left, so the rest of the row exceeds the upper bound as well
+             */
+            if value > upper_bound && column > end_column {
+                break;
+            }
+            cost_band.set_cost(row, column, value);
+            cost_band.set_action(row, column, action);
+            if value <= upper_bound {
+                next_start_column.get_or_insert(column);
+                next_end_column = column;
+            }
+        }
+        start_column = next_start_column.expect("the diagonal warp path is within the upper bound");
+        end_column = next_end_column;
+    }
+
+    let cost = cost_band.get_cost(rows, columns);
+    let path = backtrack(&cost_band, rows, columns)?;
+    Ok((final_distance(cost, distance_mode), path))
+}
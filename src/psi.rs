@@ -0,0 +1,73 @@
+use ndarray::ArrayView1;
+
+use crate::cost::{CostMatrix, CostStorage};
+use crate::dtw::{backtrack, final_distance, local_cost, minimum, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::FullWindow;
+
+/// Run psi DTW over the full window, where the endpoints of the warp path are relaxed: the path may
+/// start at any of the first `psi_begin + 1` samples of either time series, and end at any of the last
+/// `psi_end + 1` samples of either time series, skipping the samples before and after it at no cost.
+///
+/// The relaxed beginning is given by a cost matrix with `BoundaryPolicy::Relaxed(psi_begin)`. The
+/// distance is the minimum accumulated cost over the cells of the last row and last column within
+/// `psi_end` cells of the last cell, which is preferred on equal costs, followed by the cells of the
+/// last row and then those of the last column, from right to left and from bottom to top. Both values
+/// of 0 give the standard DTW. This makes the distance robust to time series cut at different phases,
+/// such as excerpts of cyclic signals.
+///
+/// # Returns
+///
+/// The distance and the warp path, which covers only the aligned samples, or
+/// `DtwError::EmptyTimeSeries` if either time series has no samples.
+pub fn dtw_psi<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    psi_begin: usize,
+    psi_end: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+
+    let mut cost_matrix =
+        CostMatrix::<f64>::with_boundary(rows, columns, BoundaryPolicy::Relaxed(psi_begin));
+    for (row, column) in FullWindow::new(rows, columns) {
+        let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+        let (value, action) = minimum(
+            cost_matrix.get_cost(row - 1, column),
+            cost_matrix.get_cost(row, column - 1),
+            cost_matrix.get_cost(row - 1, column - 1),
+        );
+        cost_matrix.set_cost(row, column, cost + value);
+        cost_matrix.set_action(row, column, action);
+    }
+
+    let last_row = (columns.saturating_sub(psi_end).max(1)..=columns)
+        .rev()
+        .map(|column| (rows, column));
+    let last_column = (rows.saturating_sub(psi_end).max(1)..rows)
+        .rev()
+        .map(|row| (row, columns));
+    let (row, column) = last_row
+        .chain(last_column)
+        .reduce(|best, cell| {
+            match cost_matrix.get_cost(cell.0, cell.1) < cost_matrix.get_cost(best.0, best.1) {
+                true => cell,
+                false => best,
+            }
+        })
+        .expect("the last cell is always a candidate");
+    let path = backtrack(&cost_matrix, row, column)?;
+    Ok((
+        final_distance(cost_matrix.get_cost(row, column), distance_mode),
+        path,
+    ))
+}
@@ -0,0 +1,236 @@
+use ndarray::{s, ArrayView1};
+
+use crate::bounds::Envelope;
+use crate::dtw::{dtw_ex_with_cutoff, local_cost};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::SakoeChibaWindow;
+
+/// The number of candidates discarded at each stage of a nearest neighbor search.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchStatistics {
+    /// The number of subsequences compared to the query.
+    pub candidates: usize,
+    /// The number of candidates discarded by LB_Kim.
+    pub lb_kim: usize,
+    /// The number of candidates discarded by LB_Keogh against the envelope of the query.
+    pub lb_keogh: usize,
+    /// The number of candidates discarded by LB_Keogh of the query against the envelope of the series.
+    pub lb_keogh_reversed: usize,
+    /// The number of DTW computations abandoned once they exceeded the best distance so far.
+    pub abandoned: usize,
+    /// The number of DTW computations which ran to completion.
+    pub computed: usize,
+}
+
+impl SearchStatistics {
+    fn record(&mut self, stage: PruningStage) {
+        match stage {
+            PruningStage::LbKim => self.lb_kim += 1,
+            PruningStage::LbKeogh => self.lb_keogh += 1,
+            PruningStage::LbKeoghReversed => self.lb_keogh_reversed += 1,
+            PruningStage::Abandoned => self.abandoned += 1,
+        }
+    }
+}
+
+/// The best match of a subsequence search.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// The index of the first sample of the best matching subsequence.
+    pub location: usize,
+    /// The DTW distance between the z-normalized query and the z-normalized subsequence.
+    pub distance: f64,
+    /// How many candidates each stage of the search discarded.
+    pub statistics: SearchStatistics,
+}
+
+/// The mean of a sequence and the scale which z-normalizes it, from the sums of its values and squared
+/// values. The scale is the standard deviation, or 1 for a constant sequence, which is thus normalized
+/// to zeros.
+fn mean_scale(sum: f64, sum_squares: f64, size: usize) -> (f64, f64) {
+    let mean = sum / size as f64;
+    let std = (sum_squares / size as f64 - mean * mean).max(0f64).sqrt();
+    match std > 0f64 {
+        true => (mean, std),
+        false => (mean, 1f64),
+    }
+}
+
+/// Accumulate the LB_Keogh cost of `values` against an envelope, visiting the samples in `order` and
+/// stopping as soon as the partial cost exceeds `cutoff`. `value` and `envelope` give the sample and
+/// the `(lower, upper)` envelope at an index.
+fn lb_keogh_cost<V, E>(
+    order: &[usize],
+    value: V,
+    envelope: E,
+    cutoff: f64,
+    distance_mode: DistanceMode,
+) -> f64
+where
+    V: Fn(usize) -> f64,
+    E: Fn(usize) -> (f64, f64),
+{
+    let mut cost = 0f64;
+    for &i in order {
+        let (lower, upper) = envelope(i);
+        let value = value(i);
+        cost += local_cost(value, value.clamp(lower, upper), distance_mode);
+        if cost > cutoff {
+            break;
+        }
+    }
+    cost
+}
+
+/// Find the subsequence of `series` with the smallest DTW distance to `query`, after z-normalizing the
+/// query and each subsequence, as in the UCR Suite.
+///
+/// The DTW is constrained to a Sakoe-Chiba band of `band` samples. Each subsequence goes through a
+/// cascade of lower bounds before the DTW is computed: LB_Kim on the first and last samples, LB_Keogh
+/// against the envelope of the query and LB_Keogh of the query against the envelope of the series.
+/// The LB_Keogh bounds visit the samples in the order of decreasing absolute value of the normalized
+/// query, which are the most likely to contribute a large cost, and are abandoned as soon as they
+/// exceed the best distance so far. The DTW itself is abandoned in the same way. The means and
+/// standard deviations of the subsequences are maintained incrementally while sliding over the series.
+///
+/// # Returns
+///
+/// The location and distance of the best match together with the pruning statistics, or
+/// `DtwError::EmptyTimeSeries` if the query is empty. On equal distances, the first location is kept.
+///
+/// # Panics
+///
+/// If the query is longer than the series.
+pub fn ucr_search(
+    query: &ArrayView1<f64>,
+    series: &ArrayView1<f64>,
+    band: usize,
+    distance_mode: DistanceMode,
+) -> Result<SearchResult, DtwError> {
+    let size = query.shape()[0];
+    let series_size = series.shape()[0];
+    if size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    assert!(
+        size <= series_size,
+        "the query must not be longer than the series"
+    );
+
+    let (mean, scale) = mean_scale(
+        query.sum(),
+        query.iter().map(|&value| value * value).sum(),
+        size,
+    );
+    let query = query.map(|&value| (value - mean) / scale);
+    let query_envelope = Envelope::new(&query.view(), band);
+    // the envelope of a subsequence is contained in the envelope of the series around it
+    let series_envelope = Envelope::new(series, band);
+    let mut order: Vec<usize> = (0..size).collect();
+    order.sort_by(|&a, &b| query[b].abs().total_cmp(&query[a].abs()));
+
+    let mut statistics = SearchStatistics::default();
+    let mut best = (0, f64::INFINITY);
+    let (mut sum, mut sum_squares) = (0f64, 0f64);
+    for location in 0..=series_size - size {
+        /* slide the sums of the subsequence by one sample, and recompute them once per query length
+         * to avoid the accumulation of rounding errors
+         */
+        if location % size == 0 {
+            let subsequence = series.slice(s![location..location + size]);
+            sum = subsequence.sum();
+            sum_squares = subsequence.iter().map(|&value| value * value).sum();
+        } else {
+            let (removed, added) = (series[location - 1], series[location + size - 1]);
+            sum += added - removed;
+            sum_squares += added * added - removed * removed;
+        }
+        let (mean, scale) = mean_scale(sum, sum_squares, size);
+        let normalize = |value: f64| (value - mean) / scale;
+        statistics.candidates += 1;
+
+        // the best distance so far as an accumulated cost, to which the lower bounds are compared
+        let best_cost = match distance_mode {
+            DistanceMode::Manhattan
+            | DistanceMode::SquaredEuclidean
+            | DistanceMode::Chebyshev
+            | DistanceMode::Canberra => best.1,
+            DistanceMode::Euclidean => best.1 * best.1,
+            DistanceMode::Minkowski(p) => best.1.powf(p),
+        };
+
+        let first = local_cost(normalize(series[location]), query[0], distance_mode);
+        let last = local_cost(
+            normalize(series[location + size - 1]),
+            query[size - 1],
+            distance_mode,
+        );
+        let lb_kim = match size {
+            1 => first,
+            _ => first + last,
+        };
+        if lb_kim > best_cost {
+            statistics.record(PruningStage::LbKim);
+            continue;
+        }
+
+        let lb_keogh = lb_keogh_cost(
+            &order,
+            |i| normalize(series[location + i]),
+            |i| (query_envelope.lower()[i], query_envelope.upper()[i]),
+            best_cost,
+            distance_mode,
+        );
+        if lb_keogh > best_cost {
+            statistics.record(PruningStage::LbKeogh);
+            continue;
+        }
+
+        let lb_keogh_reversed = lb_keogh_cost(
+            &order,
+            |i| query[i],
+            |i| {
+                (
+                    normalize(series_envelope.lower()[location + i]),
+                    normalize(series_envelope.upper()[location + i]),
+                )
+            },
+            best_cost,
+            distance_mode,
+        );
+        if lb_keogh_reversed > best_cost {
+            statistics.record(PruningStage::LbKeoghReversed);
+            continue;
+        }
+
+        let candidate = series
+            .slice(s![location..location + size])
+            .map(|&value| normalize(value));
+        let result = dtw_ex_with_cutoff(
+            &candidate.view(),
+            &query.view(),
+            SakoeChibaWindow::new(size, size, band),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+            best.1,
+        );
+        match result {
+            Ok((distance, _)) => {
+                statistics.computed += 1;
+                if distance < best.1 {
+                    best = (location, distance);
+                }
+            }
+            Err(DtwError::Abandoned) => statistics.record(PruningStage::Abandoned),
+            Err(error) => panic!("the DTW of a subsequence failed: {}", error),
+        }
+    }
+
+    Ok(SearchResult {
+        location: best.0,
+        distance: best.1,
+        statistics,
+    })
+}
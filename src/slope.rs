@@ -0,0 +1,150 @@
+use ndarray::{s, Array1, Array3, ArrayView1};
+
+use crate::dtw::{final_distance, inside_window, local_cost, DtwResult};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// Find the state with the minimum accumulated cost among `states` in the given cell.
+fn minimum_state<I>(costs: &Array3<f64>, row: usize, column: usize, states: I) -> (f64, usize)
+where
+    I: Iterator<Item = usize>,
+{
+    let mut result = (f64::INFINITY, 0);
+    for state in states {
+        if costs[[row, column, state]] < result.0 {
+            result = (costs[[row, column, state]], state);
+        }
+    }
+    result
+}
+
+/// Run DTW where the local slope of the warp path is constrained to lie between `1 / max_slope_ratio`
+/// and `max_slope_ratio`.
+///
+/// The constraint is enforced over a sliding window of recent steps: a warp path may contain at most
+/// `max_slope_ratio - 1` consecutive horizontal (deletion) or vertical (insertion) steps, after which a
+/// diagonal (match) step or a step in the other direction has to follow. For example, with a ratio of 2
+/// no two consecutive horizontal steps are allowed, so that each window of steps ending with a diagonal
+/// step advances at most 2 columns per row. A ratio of 1 only allows diagonal steps.
+///
+/// Since the constraint depends on the steps taken before reaching a cell, the dynamic programming
+/// keeps one accumulated cost per cell and per length of the current run of non-diagonal steps, and the
+/// backtracking follows these states. The cost matrix is thus stored densely, with
+/// `2 * max_slope_ratio - 1` values per cell, independent of the window.
+///
+/// # Returns
+///
+/// The distance and warp path as for `dtw_ex`. If no warp path within the window satisfies the
+/// constraint, the distance is infinite and the warp path is empty. Empty time series and malformed
+/// windows are reported as for `dtw_ex`.
+///
+/// # Panics
+///
+/// If `max_slope_ratio` is 0.
+pub fn dtw_slope_constrained<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    max_slope_ratio: usize,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    assert!(max_slope_ratio >= 1);
+
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    if x_size == 0 || y_size == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    /* the states of a cell are
+     * 0 - the cell was reached with a diagonal step
+     * 1..=max_run - the cell was reached with a run of 1..=max_run horizontal steps
+     * max_run + 1..=2 * max_run - the cell was reached with a run of 1..=max_run vertical steps
+     */
+    let max_run = max_slope_ratio - 1;
+    let states = 1 + 2 * max_run;
+    let horizontal = |run: usize| run;
+    let vertical = |run: usize| max_run + run;
+
+    let mut costs = Array3::<f64>::from_elem((y_size + 1, x_size + 1, states), f64::INFINITY);
+    let mut previous_states = Array3::<usize>::zeros((y_size + 1, x_size + 1, states));
+    costs[[0, 0, 0]] = 0f64;
+
+    let malformed = std::cell::Cell::new(None);
+    for (row, column) in inside_window(y_size, x_size, window, &malformed) {
+        let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+
+        // a diagonal step can follow any state
+        let (value, state) = minimum_state(&costs, row - 1, column - 1, 0..states);
+        costs[[row, column, 0]] = cost + value;
+        previous_states[[row, column, 0]] = state;
+
+        for run in 1..max_run + 1 {
+            /* a run of one horizontal step starts after a diagonal step or a vertical run
+             * longer runs extend the run which is one step shorter
+             */
+            let (value, state) = match run {
+                1 => minimum_state(
+                    &costs,
+                    row,
+                    column - 1,
+                    std::iter::once(0).chain(vertical(1)..vertical(max_run) + 1),
+                ),
+                _ => minimum_state(
+                    &costs,
+                    row,
+                    column - 1,
+                    std::iter::once(horizontal(run - 1)),
+                ),
+            };
+            costs[[row, column, horizontal(run)]] = cost + value;
+            previous_states[[row, column, horizontal(run)]] = state;
+
+            // and symmetrically for the vertical runs
+            let (value, state) = match run {
+                1 => minimum_state(
+                    &costs,
+                    row - 1,
+                    column,
+                    std::iter::once(0).chain(horizontal(1)..horizontal(max_run) + 1),
+                ),
+                _ => minimum_state(&costs, row - 1, column, std::iter::once(vertical(run - 1))),
+            };
+            costs[[row, column, vertical(run)]] = cost + value;
+            previous_states[[row, column, vertical(run)]] = state;
+        }
+    }
+
+    if let Some((row, column)) = malformed.get() {
+        return Err(DtwError::MalformedWindow { row, column });
+    }
+
+    let (cost, mut state) = minimum_state(&costs, y_size, x_size, 0..states);
+    let distance = final_distance(cost, distance_mode);
+    if !cost.is_finite() {
+        return Ok((distance, Array1::<(usize, usize)>::default(0)));
+    }
+
+    // backtrack through the states, where the state of a cell determines the step into it
+    let mut path = Array1::<(usize, usize)>::default(x_size + y_size);
+    let mut path_len = 0;
+    let mut row = y_size;
+    let mut column = x_size;
+    while row != 0 && column != 0 {
+        path[path_len] = (row - 1, column - 1);
+        let previous_state = previous_states[[row, column, state]];
+        (row, column) = match state {
+            0 => (row - 1, column - 1),
+            s if s <= max_run => (row, column - 1),
+            _ => (row - 1, column),
+        };
+        state = previous_state;
+        path_len += 1;
+    }
+    let path = path.slice_move(s![..path_len;-1]);
+
+    Ok((distance, path))
+}
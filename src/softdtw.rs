@@ -0,0 +1,155 @@
+use ndarray::{Array1, Array2, ArrayView1};
+
+use crate::dtw::{local_cost, soft_minimum};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// Compute the matrix of soft accumulated costs, with a virtual row 0 and column 0 as in the cost
+/// matrix of `dtw_ex`. The local cost is the squared difference of the samples.
+fn soft_costs(x: &ArrayView1<f64>, y: &ArrayView1<f64>, gamma: f64) -> Array2<f64> {
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    let mut costs = Array2::from_elem((rows + 1, columns + 1), f64::INFINITY);
+    costs[(0, 0)] = 0f64;
+    for row in 1..=rows {
+        for column in 1..=columns {
+            let cost = local_cost(x[column - 1], y[row - 1], DistanceMode::Euclidean);
+            costs[(row, column)] = cost
+                + soft_minimum(
+                    costs[(row - 1, column)],
+                    costs[(row, column - 1)],
+                    costs[(row - 1, column - 1)],
+                    gamma,
+                );
+        }
+    }
+    costs
+}
+
+/// The soft-DTW of two time series together with its derivatives.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoftDtwGradient {
+    /// The soft-DTW value, as returned by `softdtw_ex`.
+    pub value: f64,
+    /// The expected alignment matrix, with a row per sample of y and a column per sample of x. Each
+    /// element is the probability that the pair of samples is aligned, under the Gibbs distribution
+    /// over warp paths which soft-DTW induces, and is also the derivative of the value with respect to
+    /// the local cost of the pair.
+    pub expected_alignment: Array2<f64>,
+    /// The derivative of the value with respect to each sample of x.
+    pub gradient_x: Array1<f64>,
+    /// The derivative of the value with respect to each sample of y.
+    pub gradient_y: Array1<f64>,
+}
+
+/// Compute the soft-DTW of two time series, as defined by Cuturi and Blondel, where the minimum of the
+/// DTW recurrence is replaced by a soft minimum with smoothing parameter `gamma`.
+///
+/// The local cost is the squared difference of the samples, and the result is the accumulated cost
+/// of the last cell, without taking the square root. Unlike the DTW distance, soft-DTW is
+/// differentiable with respect to the samples, which makes it suitable as a loss function. It is at
+/// most the squared Euclidean DTW distance, and equals it when `gamma` is 0. It may be negative, even
+/// for identical time series.
+///
+/// # Returns
+///
+/// The soft-DTW value, or `DtwError::EmptyTimeSeries` if either time series has no samples.
+///
+/// # Panics
+///
+/// If `gamma` is negative or not a number.
+pub fn softdtw_ex<T>(x: &ArrayView1<T>, y: &ArrayView1<T>, gamma: f64) -> Result<f64, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    assert!(gamma >= 0f64, "gamma must be non-negative");
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let x = x.map(|&v| v.into());
+    let y = y.map(|&v| v.into());
+    Ok(soft_costs(&x.view(), &y.view(), gamma)[(rows, columns)])
+}
+
+/// Compute soft-DTW together with its expected alignment matrix and its gradient with respect to both
+/// time series, with the forward-backward algorithm of Cuturi and Blondel.
+///
+/// The forward pass is the one of `softdtw_ex`. The backward pass propagates the derivative of the
+/// value from the last cell to the first one, weighting each step by the probability of the soft
+/// minimum choosing it. The gradient follows from the expected alignment by the chain rule through the
+/// squared differences.
+///
+/// # Returns
+///
+/// The value, expected alignment and gradients, or `DtwError::EmptyTimeSeries` if either time series
+/// has no samples.
+///
+/// # Panics
+///
+/// If `gamma` is not positive, since the soft minimum is not differentiable for a `gamma` of 0.
+pub fn softdtw_gradient<T>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    gamma: f64,
+) -> Result<SoftDtwGradient, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    assert!(gamma > 0f64, "gamma must be positive");
+    let rows = y.shape()[0];
+    let columns = x.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+    let x = x.map(|&v| v.into());
+    let y = y.map(|&v| v.into());
+    let forward = soft_costs(&x.view(), &y.view(), gamma);
+    let value = forward[(rows, columns)];
+
+    /* extend the costs with a row and a column past the end, which the backward pass reads; they are
+     * -inf so that they carry no weight, except for the cell after the last one, which repeats the
+     * value so that the last cell gets a weight of 1
+     */
+    let mut costs = Array2::from_elem((rows + 2, columns + 2), f64::NEG_INFINITY);
+    costs
+        .slice_mut(ndarray::s![1..=rows, 1..=columns])
+        .assign(&forward.slice(ndarray::s![1..=rows, 1..=columns]));
+    costs[(rows + 1, columns + 1)] = value;
+    let local = |row: usize, column: usize| match row <= rows && column <= columns {
+        true => local_cost(x[column - 1], y[row - 1], DistanceMode::Euclidean),
+        false => 0f64,
+    };
+
+    let mut alignment = Array2::<f64>::zeros((rows + 2, columns + 2));
+    alignment[(rows + 1, columns + 1)] = 1f64;
+    for row in (1..=rows).rev() {
+        for column in (1..=columns).rev() {
+            let cost = costs[(row, column)];
+            let weight = |next_row: usize, next_column: usize| {
+                let next = costs[(next_row, next_column)] - local(next_row, next_column);
+                alignment[(next_row, next_column)] * ((next - cost) / gamma).exp()
+            };
+            alignment[(row, column)] =
+                weight(row + 1, column) + weight(row, column + 1) + weight(row + 1, column + 1);
+        }
+    }
+    let expected_alignment = alignment
+        .slice(ndarray::s![1..=rows, 1..=columns])
+        .to_owned();
+
+    let mut gradient_x = Array1::<f64>::zeros(columns);
+    let mut gradient_y = Array1::<f64>::zeros(rows);
+    for ((row, column), &probability) in expected_alignment.indexed_iter() {
+        let derivative = 2f64 * probability * (x[column] - y[row]);
+        gradient_x[column] += derivative;
+        gradient_y[row] -= derivative;
+    }
+    Ok(SoftDtwGradient {
+        value,
+        expected_alignment,
+        gradient_x,
+        gradient_y,
+    })
+}
@@ -0,0 +1,329 @@
+use ndarray::{Array1, Array2, ArrayView1};
+use std::ops::Range;
+
+use crate::cost::{CostMatrix, CostStorage};
+use crate::dtw::{backtrack, final_distance, local_cost, minimum};
+use crate::enums::*;
+use crate::error::DtwError;
+use crate::window::FullWindow;
+
+/// The best match of a query within a longer time series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubsequenceMatch {
+    /// The range of samples of the long time series aligned to the query.
+    pub interval: Range<usize>,
+    /// The DTW distance between the query and the samples of `interval`.
+    pub distance: f64,
+    /// The warp path, as `(query index, long time series index)` pairs of 0 based indices.
+    pub path: Array1<(usize, usize)>,
+}
+
+/// Find the contiguous region of `series` with the smallest DTW distance to `query`, with subsequence
+/// DTW.
+///
+/// The query gives the rows of the cost matrix and the long time series its columns. The cost matrix
+/// uses `BoundaryPolicy::FreeFirstRow`, so that a warp path may start at any sample of the long time
+/// series at no cost, and the match ends at the column of the last row with the minimum accumulated
+/// cost, the first one on equal costs. The whole cost matrix is computed in a single pass, which is
+/// much cheaper than running DTW for every region of the long time series.
+///
+/// Very short regions may match with an artificially low distance, e.g. when the whole query is
+/// aligned to a single sample. The length of the warp path ending in each cell is thus tracked along
+/// with its accumulated cost, and only the columns of the last row whose warp path has at least
+/// `min_path_length` cells are considered as the end of the match. A `min_path_length` of at most the
+/// size of the query does not reject any match, since every warp path visits all rows.
+///
+/// # Returns
+///
+/// The interval, distance and warp path of the best match, or `DtwError::EmptyTimeSeries` if either
+/// time series has no samples. If no warp path is long enough, the distance is infinite, the warp path
+/// is empty and so is the interval.
+pub fn subsequence_dtw<T>(
+    query: &ArrayView1<T>,
+    series: &ArrayView1<T>,
+    min_path_length: usize,
+    distance_mode: DistanceMode,
+) -> Result<SubsequenceMatch, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+{
+    let rows = query.shape()[0];
+    let columns = series.shape()[0];
+    if rows == 0 || columns == 0 {
+        return Err(DtwError::EmptyTimeSeries);
+    }
+
+    let mut cost_matrix =
+        CostMatrix::<f64>::with_boundary(rows, columns, BoundaryPolicy::FreeFirstRow);
+    // the length of the warp path ending in each cell, where the virtual row 0 starts a new warp path
+    let mut path_lengths = Array2::<usize>::zeros((rows + 1, columns + 1));
+    for (row, column) in FullWindow::new(rows, columns) {
+        let cost = local_cost(
+            series[column - 1].into(),
+            query[row - 1].into(),
+            distance_mode,
+        );
+        let (value, action) = minimum(
+            cost_matrix.get_cost(row - 1, column),
+            cost_matrix.get_cost(row, column - 1),
+            cost_matrix.get_cost(row - 1, column - 1),
+        );
+        cost_matrix.set_cost(row, column, cost + value);
+        cost_matrix.set_action(row, column, action);
+        path_lengths[[row, column]] = 1 + match action {
+            Action::Inserted => path_lengths[[row - 1, column]],
+            Action::Deleted => path_lengths[[row, column - 1]],
+            Action::Matched => path_lengths[[row - 1, column - 1]],
+        };
+    }
+
+    let end = (1..=columns)
+        .filter(|&column| path_lengths[[rows, column]] >= min_path_length)
+        .reduce(|best, column| {
+            match cost_matrix.get_cost(rows, column) < cost_matrix.get_cost(rows, best) {
+                true => column,
+                false => best,
+            }
+        });
+    let end = match end {
+        Some(end) => end,
+        None => {
+            return Ok(SubsequenceMatch {
+                interval: columns..columns,
+                distance: f64::INFINITY,
+                path: Array1::default(0),
+            })
+        }
+    };
+    let path = backtrack(&cost_matrix, rows, end)?;
+    Ok(SubsequenceMatch {
+        interval: path[0].1..end,
+        distance: final_distance(cost_matrix.get_cost(rows, end), distance_mode),
+        path,
+    })
+}
+
+/// A match of the query reported by `Spring`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpringMatch {
+    /// The range of the indices of the samples of the stream aligned to the query.
+    pub interval: Range<usize>,
+    /// The DTW distance between the query and the samples of `interval`.
+    pub distance: f64,
+}
+
+/// Monitor a stream of samples for subsequences matching a query, with the SPRING algorithm of Sakurai
+/// et al.
+///
+/// The state is a single column of the subsequence DTW cost matrix of `subsequence_dtw`, where the
+/// query gives the rows, together with the index of the stream sample at which the warp path of each
+/// cell started. Each sample pushed computes the next column from the previous one, in time and memory
+/// linear in the size of the query. A match is reported once its distance is within the threshold and
+/// no warp path still being extended could replace it with a smaller distance, i.e. each reported match
+/// is the best among the overlapping candidates, and reported matches do not overlap.
+pub struct Spring {
+    query: Array1<f64>,
+    threshold: f64,
+    distance_mode: DistanceMode,
+    /// The accumulated cost of each row of the last column, with the virtual row 0 at index 0.
+    costs: Array1<f64>,
+    /// The index of the stream sample at which the warp path of each row of the last column starts.
+    starts: Array1<usize>,
+    /// The number of samples pushed so far.
+    time: usize,
+    /// The best candidate match within the threshold which is not reported yet, with its accumulated
+    /// cost.
+    candidate: Option<(f64, SpringMatch)>,
+}
+
+impl Spring {
+    /// Create the state for monitoring a stream for matches of `query` with a DTW distance of at most
+    /// `threshold`.
+    ///
+    /// # Panics
+    ///
+    /// If the query is empty.
+    pub fn new<T>(query: &ArrayView1<T>, threshold: f64, distance_mode: DistanceMode) -> Self
+    where
+        T: std::marker::Copy + std::convert::Into<f64>,
+    {
+        let size = query.shape()[0];
+        assert_ne!(size, 0, "the query must not be empty");
+        let mut costs = Array1::from_elem(size + 1, f64::INFINITY);
+        costs[0] = 0f64;
+        Self {
+            query: query.map(|&v| v.into()),
+            threshold,
+            distance_mode,
+            costs,
+            starts: Array1::zeros(size + 1),
+            time: 0,
+            candidate: None,
+        }
+    }
+
+    /// Process the next sample of the stream.
+    ///
+    /// # Returns
+    ///
+    /// The match which is known to be final after this sample, if any. A match is reported at the
+    /// earliest once the sample after its end is pushed.
+    pub fn push<T>(&mut self, sample: T) -> Option<SpringMatch>
+    where
+        T: std::convert::Into<f64>,
+    {
+        let sample = sample.into();
+        let time = self.time;
+        self.time += 1;
+
+        // compute the next column, where the virtual row 0 starts a new warp path at this sample
+        let mut costs = Array1::from_elem(self.costs.len(), f64::INFINITY);
+        let mut starts = Array1::<usize>::zeros(self.starts.len());
+        costs[0] = 0f64;
+        starts[0] = time;
+        for row in 1..costs.len() {
+            let cost = local_cost(sample, self.query[row - 1], self.distance_mode);
+            let (value, action) = minimum(costs[row - 1], self.costs[row], self.costs[row - 1]);
+            costs[row] = cost + value;
+            starts[row] = match action {
+                Action::Inserted => starts[row - 1],
+                Action::Deleted => self.starts[row],
+                Action::Matched => match row {
+                    // the warp path starts at this sample
+                    1 => time,
+                    _ => self.starts[row - 1],
+                },
+            };
+        }
+
+        /* the candidate is final once every warp path being extended either costs at least as much or
+         * starts after the end of the candidate; the warp paths overlapping it are then discarded
+         */
+        let mut reported = None;
+        if let Some((candidate_cost, candidate)) = &self.candidate {
+            let is_final = (1..costs.len())
+                .all(|row| costs[row] >= *candidate_cost || starts[row] >= candidate.interval.end);
+            if is_final {
+                for row in 1..costs.len() {
+                    if starts[row] < candidate.interval.end {
+                        costs[row] = f64::INFINITY;
+                    }
+                }
+                reported = self.candidate.take().map(|(_, candidate)| candidate);
+            }
+        }
+
+        let last = costs.len() - 1;
+        let distance = final_distance(costs[last], self.distance_mode);
+        let improves = match &self.candidate {
+            Some((candidate_cost, _)) => costs[last] < *candidate_cost,
+            None => true,
+        };
+        if distance <= self.threshold && improves {
+            let candidate = SpringMatch {
+                interval: starts[last]..time + 1,
+                distance,
+            };
+            self.candidate = Some((costs[last], candidate));
+        }
+
+        self.costs = costs;
+        self.starts = starts;
+        reported
+    }
+
+    /// Signal the end of the stream.
+    ///
+    /// # Returns
+    ///
+    /// The best candidate match which was not reported yet, if any.
+    pub fn finish(&mut self) -> Option<SpringMatch> {
+        self.candidate.take().map(|(_, candidate)| candidate)
+    }
+}
+
+/// A match of the template reported by `StreamingMatcher`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    /// The range of the indices of the samples of the stream aligned to the template.
+    pub range: Range<usize>,
+    /// The DTW distance between the template and the samples of `range`.
+    pub distance: f64,
+}
+
+/// Detect the occurrences of a template in a stream of samples in real time, reporting each match
+/// whose DTW distance is at most a threshold, as found by `Spring`.
+///
+/// A single event may produce several matches in quick succession, e.g. when noise splits it into
+/// overlapping candidates. After a match is reported, the matches ending within `cooldown` samples of
+/// its end are thus suppressed.
+pub struct StreamingMatcher {
+    spring: Spring,
+    cooldown: usize,
+    /// The end of the last reported match.
+    last_end: Option<usize>,
+}
+
+impl StreamingMatcher {
+    /// Create a detector for matches of `template` with a DTW distance of at most `threshold`, which
+    /// suppresses the matches ending within `cooldown` samples of the end of a reported match.
+    ///
+    /// # Panics
+    ///
+    /// If the template is empty.
+    pub fn new<T>(
+        template: &ArrayView1<T>,
+        threshold: f64,
+        cooldown: usize,
+        distance_mode: DistanceMode,
+    ) -> Self
+    where
+        T: std::marker::Copy + std::convert::Into<f64>,
+    {
+        Self {
+            spring: Spring::new(template, threshold, distance_mode),
+            cooldown,
+            last_end: None,
+        }
+    }
+
+    /// Process the next sample of the stream.
+    ///
+    /// # Returns
+    ///
+    /// The match which is known to be final after this sample and is not suppressed by the cooldown, if
+    /// any. As for `Spring::push`, a match is reported at the earliest once the sample after its end is
+    /// pushed.
+    pub fn push<T>(&mut self, sample: T) -> Option<Match>
+    where
+        T: std::convert::Into<f64>,
+    {
+        let found = self.spring.push(sample)?;
+        self.report(found)
+    }
+
+    /// Signal the end of the stream.
+    ///
+    /// # Returns
+    ///
+    /// The best candidate match which was not reported yet, if any and if it is not suppressed by the
+    /// cooldown.
+    pub fn finish(&mut self) -> Option<Match> {
+        let found = self.spring.finish()?;
+        self.report(found)
+    }
+
+    fn report(&mut self, found: SpringMatch) -> Option<Match> {
+        if self
+            .last_end
+            .is_some_and(|last_end| found.interval.end <= last_end + self.cooldown)
+        {
+            return None;
+        }
+        self.last_end = Some(found.interval.end);
+        Some(Match {
+            range: found.interval,
+            distance: found.distance,
+        })
+    }
+}
@@ -0,0 +1,117 @@
+//! Interchange format for DTW problems and their expected answers.
+//!
+//! Test cases are stored as YAML lists, with arrays serialized in the ndarray serde format (`v`, `dim`
+//! and `data` fields). The crate's own fixtures in `tests/dtw.yaml` and `tests/projection.yaml` use this
+//! format, and other crates can load them or contribute their own test cases in the same format.
+
+use ndarray::{Array1, ArrayView1};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::dtw::DtwResult;
+use crate::enums::DistanceMode;
+
+/// A DTW problem together with the expected distance and warp path.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DtwTestCase {
+    pub name: String,
+    /// The x time series (the columns of the cost matrix).
+    pub time_series_a: Array1<f64>,
+    /// The y time series (the rows of the cost matrix).
+    pub time_series_b: Array1<f64>,
+    pub distance: f64,
+    /// One of `manhattan`, `euclidean`, `squared_euclidean`, `chebyshev`, `canberra` or `minkowski:<p>`
+    /// with the exponent `p`, as parsed by `parsed_distance_mode`.
+    pub distance_mode: String,
+    pub warp_path: Array1<(usize, usize)>,
+}
+
+impl DtwTestCase {
+    /// Capture the inputs and the result of a DTW run as a test case, e.g. to report a wrong result or
+    /// to add it as a regression test. The result is stored as the expected answer.
+    pub fn from_result<T>(
+        name: &str,
+        x: &ArrayView1<T>,
+        y: &ArrayView1<T>,
+        distance_mode: DistanceMode,
+        result: &DtwResult,
+    ) -> Self
+    where
+        T: std::marker::Copy + std::convert::Into<f64>,
+    {
+        let (distance, warp_path) = result;
+        Self {
+            name: name.to_string(),
+            time_series_a: x.map(|&v| v.into()),
+            time_series_b: y.map(|&v| v.into()),
+            distance: *distance,
+            distance_mode: match distance_mode {
+                DistanceMode::Manhattan => "manhattan".to_string(),
+                DistanceMode::Euclidean => "euclidean".to_string(),
+                DistanceMode::SquaredEuclidean => "squared_euclidean".to_string(),
+                DistanceMode::Chebyshev => "chebyshev".to_string(),
+                DistanceMode::Canberra => "canberra".to_string(),
+                DistanceMode::Minkowski(p) => format!("minkowski:{}", p),
+            },
+            warp_path: warp_path.clone(),
+        }
+    }
+
+    /// Parse the `distance_mode` field, returning `None` for an unknown distance mode.
+    pub fn parsed_distance_mode(&self) -> Option<DistanceMode> {
+        match self.distance_mode.as_str() {
+            "manhattan" => Some(DistanceMode::Manhattan),
+            "euclidean" => Some(DistanceMode::Euclidean),
+            "squared_euclidean" => Some(DistanceMode::SquaredEuclidean),
+            "chebyshev" => Some(DistanceMode::Chebyshev),
+            "canberra" => Some(DistanceMode::Canberra),
+            mode => match mode.strip_prefix("minkowski:") {
+                Some(p) => p.parse().ok().map(DistanceMode::Minkowski),
+                None => None,
+            },
+        }
+    }
+}
+
+/// A low resolution warp path together with the expected cells of the projected `ConstrainedWindow`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectionTestCase {
+    pub name: String,
+    pub low_res_path: Array1<(usize, usize)>,
+    pub resolution_factor: usize,
+    /// The search radius, which is the row radius if `column_search_radius` is given.
+    pub search_radius: usize,
+    /// An optional separate search radius along the columns, for anisotropic windows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_search_radius: Option<usize>,
+    pub high_res_rows: usize,
+    pub high_res_columns: usize,
+    /// The `(row, column)` cells visited by the window, as 1 based indices.
+    pub projected_window: Array1<(usize, usize)>,
+}
+
+fn load_cases<C, P>(path: P) -> std::io::Result<Vec<C>>
+where
+    C: for<'de> Deserialize<'de>,
+    P: AsRef<Path>,
+{
+    let f = std::fs::File::open(path)?;
+    serde_yaml::from_reader(f).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Load a list of `DtwTestCase` from a YAML file.
+pub fn load_dtw_cases<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<DtwTestCase>> {
+    load_cases(path)
+}
+
+/// Load a list of `ProjectionTestCase` from a YAML file.
+pub fn load_projection_cases<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<ProjectionTestCase>> {
+    load_cases(path)
+}
+
+/// Save a list of `DtwTestCase` to a YAML file, in the format read by `load_dtw_cases`.
+pub fn save_dtw_cases<P: AsRef<Path>>(path: P, cases: &[DtwTestCase]) -> std::io::Result<()> {
+    let f = std::fs::File::create(path)?;
+    serde_yaml::to_writer(f, cases)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
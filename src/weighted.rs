@@ -0,0 +1,96 @@
+use ndarray::ArrayView1;
+
+use crate::dtw::{checked_dtw_cells, final_distance, local_cost, DtwResult};
+use crate::edit::{edit_cells, origin_boundary};
+use crate::enums::*;
+use crate::error::DtwError;
+
+/// The weight of the local cost between two samples which are `phase_difference` samples apart, in
+/// time series of `size` samples, as defined by Jeong et al. for weighted DTW.
+///
+/// The weight follows the logistic curve `1 / (1 + exp(-steepness * (phase_difference - size / 2)))`,
+/// which is centered on half the size of the time series. A `steepness` of 0 gives a constant weight of
+/// 1/2, i.e. half the standard DTW, and larger values approach a step from 0 to 1 at the center, so
+/// that alignments far from the diagonal are increasingly penalized compared to those close to it.
+pub fn wdtw_weight(phase_difference: usize, size: usize, steepness: f64) -> f64 {
+    let center = size as f64 / 2f64;
+    1f64 / (1f64 + (-steepness * (phase_difference as f64 - center)).exp())
+}
+
+/// Run weighted DTW (WDTW), where the local cost of each cell is multiplied by `wdtw_weight` of the
+/// distance of the cell to the diagonal, `|row - column|`.
+///
+/// Unlike a window, which forbids the cells far from the diagonal, the weights make them gradually more
+/// expensive, with `steepness` controlling how quickly the penalty grows. The size used to center the
+/// weight curve is the size of the longer time series. For the Euclidean mode, the weighted squared
+/// differences are accumulated and the distance is the square root of their sum.
+///
+/// # Returns
+///
+/// The distance and warp path, or an error as for `dtw_ex`.
+pub fn dtw_weighted<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    steepness: f64,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    let size = usize::max(x_size, y_size);
+    checked_dtw_cells(
+        y_size,
+        x_size,
+        window,
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+        |row, column| {
+            let weight = wdtw_weight(row.abs_diff(column), size, steepness);
+            weight * local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode)
+        },
+    )
+}
+
+/// Run Amerced DTW (ADTW) of Herrmann and Webb, where each step of the warp path off the diagonal, i.e.
+/// each insertion or deletion, adds the constant `penalty` to the accumulated cost.
+///
+/// A `penalty` of 0 gives the standard DTW, and an infinite one forbids warping altogether, so that
+/// the distance is the one of the samples aligned in lockstep if the time series have the same size.
+/// Unlike a window, the penalty applies to each step rather than to the distance from the diagonal, so
+/// long but consistent shifts remain cheap. For the Euclidean mode, the penalties are added to the
+/// squared differences and the distance is the square root of the sum.
+///
+/// # Returns
+///
+/// The distance and warp path, or an error as for `dtw_ex`.
+pub fn dtw_amerced<T, W>(
+    x: &ArrayView1<T>,
+    y: &ArrayView1<T>,
+    window: W,
+    penalty: f64,
+    distance_mode: DistanceMode,
+) -> Result<DtwResult, DtwError>
+where
+    T: std::marker::Copy + std::convert::Into<f64>,
+    W: Iterator<Item = (usize, usize)>,
+{
+    let x_size = x.shape()[0];
+    let y_size = y.shape()[0];
+    let (cost, path) = edit_cells(
+        y_size,
+        x_size,
+        window,
+        StorageStrategy::Auto,
+        origin_boundary,
+        |row, column| {
+            let cost = local_cost(x[column - 1].into(), y[row - 1].into(), distance_mode);
+            (cost + penalty, cost + penalty, cost)
+        },
+    )?;
+    Ok((final_distance(cost, distance_mode), path))
+}
@@ -53,6 +53,7 @@ impl Iterator for FullWindow {
     }
 }
 
+#[derive(Clone)]
 pub struct ConstrainedWindow {
     /// For each row, the minimum and maximum column values in the cost matrix are stored as a tuple. The number of
     /// elements in the constraints array thus denotes the number of rows in the cost matrix.
@@ -68,6 +69,27 @@ impl ConstrainedWindow {
         search_radius: usize,
         high_res_rows: usize,
         high_res_columns: usize,
+    ) -> Self {
+        Self::from_low_res_path_anisotropic(
+            low_res_path,
+            resolution_factor,
+            (search_radius, search_radius),
+            high_res_rows,
+            high_res_columns,
+        )
+    }
+
+    /// Same as `from_low_res_path`, with separate search radii along the rows and the columns.
+    ///
+    /// The projected path is expanded by `row_radius` cells up and down and by `column_radius` cells to
+    /// the left and right, so that the window can be wider in one direction when one of the time series
+    /// is expected to warp more than the other. With equal radii, this is `from_low_res_path`.
+    pub fn from_low_res_path_anisotropic(
+        low_res_path: Array1<(usize, usize)>,
+        resolution_factor: usize,
+        (row_radius, column_radius): (usize, usize),
+        high_res_rows: usize,
+        high_res_columns: usize,
     ) -> Self {
         let mut window = ConstrainedWindow {
             constraints: Array1::<(usize, usize)>::from_elem(high_res_rows + 1, (usize::MAX, 0)),
@@ -141,28 +163,39 @@ impl ConstrainedWindow {
             prev_low_res_row = low_res_row;
             prev_low_res_column = low_res_column;
         }
-        /* the last step is to expand the high resolution warp path with the search radius
+        /* the last step is to expand the high resolution warp path with the search radii
+         * the row radius gives the expansion in the top and bottom directions, the column radius the
+         * expansion in the left and right directions
          * for each minimum value we expand in the left, top and top left directions
          * for each maximum value, we expand in the right, bottom and bottom right directions
          *
          * this is done in two steps:
          * 1. first iterate each row and expand on right, bottom and bottom right directions
          * 2. then iterate in reverse order and expand to the left, top and top left directions
+         *
+         * rows which are not covered by the projected path are skipped, since they have no minimum or
+         * maximum value to expand
          */
         for row in 1..window.constraints.shape()[0] {
-            let (_, row_max) = window.constraints[row];
-            for i in 0..search_radius + 1 {
-                let expanded_row_max: usize = usize::min(row_max + search_radius, high_res_columns);
+            let (row_min, row_max) = window.constraints[row];
+            if row_min > row_max {
+                continue;
+            }
+            for i in 0..row_radius + 1 {
+                let expanded_row_max: usize = usize::min(row_max + column_radius, high_res_columns);
                 if row > i && row - i >= 1 {
                     window.visit(row - i, expanded_row_max);
                 }
             }
         }
         for row in (1..window.constraints.shape()[0]).rev() {
-            let (row_min, _) = window.constraints[row];
-            for i in 0..search_radius + 1 {
-                let expanded_row_min = match row_min > search_radius {
-                    true => row_min - search_radius,
+            let (row_min, row_max) = window.constraints[row];
+            if row_min > row_max {
+                continue;
+            }
+            for i in 0..row_radius + 1 {
+                let expanded_row_min = match row_min > column_radius {
+                    true => row_min - column_radius,
                     false => 1,
                 };
                 if row + i <= high_res_rows {
@@ -174,6 +207,39 @@ impl ConstrainedWindow {
         window
     }
 
+    /// Get the minimum and maximum column (inclusive) visited on a row, using 1 based indices. Returns
+    /// `None` for rows outside of the cost matrix and for rows without any visited cell.
+    pub fn row_range(&self, row: usize) -> Option<(usize, usize)> {
+        if row == 0 || row >= self.constraints.shape()[0] {
+            return None;
+        }
+        let (min, max) = self.constraints[row];
+        match min <= max {
+            true => Some((min, max)),
+            false => None,
+        }
+    }
+
+    /// Iterate over the rows with at least one visited cell, yielding the row and its minimum and
+    /// maximum column (inclusive), using 1 based indices.
+    pub fn row_ranges(&self) -> impl Iterator<Item = (usize, (usize, usize))> + '_ {
+        (1..self.constraints.shape()[0])
+            .filter_map(|row| self.row_range(row).map(|range| (row, range)))
+    }
+
+    /// The number of cells visited by the window.
+    pub fn cell_count(&self) -> usize {
+        self.row_ranges().map(|(_, (min, max))| max - min + 1).sum()
+    }
+
+    /// Check whether the window contains the cell `(row, column)`, using 1 based indices.
+    pub(crate) fn contains(&self, row: usize, column: usize) -> bool {
+        match self.row_range(row) {
+            Some((min, max)) => min <= column && column <= max,
+            None => false,
+        }
+    }
+
     fn visit(&mut self, row: usize, column: usize) {
         if self.constraints[row].0 > column {
             self.constraints[row].0 = column;
@@ -197,14 +263,166 @@ impl Iterator for ConstrainedWindow {
             // can advance to the next column on the current row
             result = Some((self.row, self.column));
             self.column += 1;
-        } else if self.row < self.constraints.shape()[0] - 1 {
-            // can advance to the next row
-            self.row += 1;
-            self.column = self.constraints[self.row].0;
-            result = Some((self.row, self.column));
-            self.column += 1;
+        } else {
+            // can advance to the next row, skipping the rows without any visited cell
+            while self.row < self.constraints.shape()[0] - 1 {
+                self.row += 1;
+                if let Some((min, _)) = self.row_range(self.row) {
+                    result = Some((self.row, min));
+                    self.column = min + 1;
+                    break;
+                }
+            }
         }
 
         result
     }
 }
+
+/// The SakoeChibaWindow iterator visits the cells of the cost matrix within a band of fixed width around the
+/// diagonal, which is the most common global constraint for dynamic time warping.
+///
+/// For time series of different sizes, the diagonal is the straight line from the first to the last cell of the cost
+/// matrix. On each row, the band contains the columns crossed by the diagonal, extended by `band_width` columns on
+/// each side. The band thus always contains a warp path, and a `band_width` of zero only allows the cells along the
+/// diagonal.
+pub struct SakoeChibaWindow {
+    rows: usize,
+    columns: usize,
+    band_width: usize,
+    row: usize,
+    column: usize,
+}
+
+impl SakoeChibaWindow {
+    /// Create a band of `band_width` columns on each side of the diagonal, where the size of x denotes the number of
+    /// columns and the size of y denotes the number of rows.
+    pub fn new(x_size: usize, y_size: usize, band_width: usize) -> Self {
+        let mut window = Self {
+            rows: y_size,
+            columns: x_size,
+            band_width,
+            row: 1,
+            column: 1,
+        };
+        if let Some((min, _)) = window.row_range(1) {
+            window.column = min;
+        }
+        window
+    }
+
+    /// Get the minimum and maximum column (inclusive) visited on a row, using 1 based indices. Returns `None` for rows
+    /// outside of the cost matrix.
+    pub fn row_range(&self, row: usize) -> Option<(usize, usize)> {
+        if row == 0 || row > self.rows || self.columns == 0 {
+            return None;
+        }
+        // the columns crossed by the diagonal on this row
+        let diagonal_min = (row - 1) * self.columns / self.rows + 1;
+        let diagonal_max = (row * self.columns).div_ceil(self.rows);
+        Some((
+            diagonal_min.saturating_sub(self.band_width).max(1),
+            usize::min(diagonal_max + self.band_width, self.columns),
+        ))
+    }
+}
+
+impl Iterator for SakoeChibaWindow {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, max) = self.row_range(self.row)?;
+        if self.column > max {
+            // advance to the first column of the band on the next row
+            self.row += 1;
+            let (min, _) = self.row_range(self.row)?;
+            self.column = min;
+        }
+        let result = (self.row, self.column);
+        self.column += 1;
+        Some(result)
+    }
+}
+
+/// The ItakuraWindow iterator visits the cells of the cost matrix within the Itakura parallelogram, which limits the
+/// slope of the warp path relative to the diagonal to lie between `1 / max_slope` and `max_slope`.
+///
+/// The parallelogram is computed in coordinates relative to the size of the cost matrix, so that the first and last
+/// cells are its corners for time series of any sizes. On each row, the columns crossed by the diagonal are always
+/// visited as well, which keeps a warp path within the window when the discretization of the parallelogram leaves a
+/// row without cells, or when the ratio of the sizes exceeds `max_slope`.
+pub struct ItakuraWindow {
+    rows: usize,
+    columns: usize,
+    max_slope: f64,
+    row: usize,
+    column: usize,
+}
+
+impl ItakuraWindow {
+    /// Create an Itakura parallelogram with the given maximum slope, which must be at least 1, where the size of x
+    /// denotes the number of columns and the size of y denotes the number of rows.
+    pub fn new(x_size: usize, y_size: usize, max_slope: f64) -> Self {
+        assert!(max_slope >= 1f64);
+        let mut window = Self {
+            rows: y_size,
+            columns: x_size,
+            max_slope,
+            row: 1,
+            column: 1,
+        };
+        if let Some((min, _)) = window.row_range(1) {
+            window.column = min;
+        }
+        window
+    }
+
+    /// Get the minimum and maximum column (inclusive) visited on a row, using 1 based indices. Returns `None` for rows
+    /// outside of the cost matrix.
+    pub fn row_range(&self, row: usize) -> Option<(usize, usize)> {
+        if row == 0 || row > self.rows || self.columns == 0 {
+            return None;
+        }
+        // the columns crossed by the diagonal on this row
+        let mut min = (row - 1) * self.columns / self.rows + 1;
+        let mut max = (row * self.columns).div_ceil(self.rows);
+        if self.rows == 1 || self.columns == 1 {
+            return Some((min, max));
+        }
+
+        /* with u and v the relative positions of the column and the row in [0, 1], the parallelogram is bounded by
+         * the lines v = max_slope * u and v = u / max_slope through the first cell, and by the lines
+         * 1 - v = max_slope * (1 - u) and 1 - v = (1 - u) / max_slope through the last cell
+         */
+        let s = self.max_slope;
+        let v = (row - 1) as f64 / (self.rows - 1) as f64;
+        let lower = f64::max(v / s, 1f64 - s * (1f64 - v));
+        let upper = f64::min(s * v, 1f64 - (1f64 - v) / s);
+        if lower <= upper {
+            // a small tolerance keeps the cells which lie exactly on the boundary
+            let scale = (self.columns - 1) as f64;
+            let parallelogram_min = (lower * scale - 1e-9).ceil().max(0f64) as usize + 1;
+            let parallelogram_max = ((upper * scale + 1e-9).floor() as usize + 1).min(self.columns);
+            min = usize::min(min, parallelogram_min);
+            max = usize::max(max, parallelogram_max);
+        }
+        Some((min, max))
+    }
+}
+
+impl Iterator for ItakuraWindow {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, max) = self.row_range(self.row)?;
+        if self.column > max {
+            // advance to the first column of the parallelogram on the next row
+            self.row += 1;
+            let (min, _) = self.row_range(self.row)?;
+            self.column = min;
+        }
+        let result = (self.row, self.column);
+        self.column += 1;
+        Some(result)
+    }
+}
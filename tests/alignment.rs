@@ -0,0 +1,41 @@
+use dtw::{Alignment, DtwBuilder};
+use ndarray::Array1;
+
+#[test]
+fn test_alignment() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let result = dtw::dtw(&x.view(), &y.view()).unwrap();
+
+    let alignment = Alignment::from(result.clone());
+    assert_eq!(alignment.distance(), result.0);
+    assert_eq!(alignment.path(), &result.1);
+    assert_eq!(alignment.len(), result.1.len());
+    assert!(!alignment.is_empty());
+    assert_eq!(
+        alignment.normalized_distance(),
+        result.0 / result.1.len() as f64
+    );
+
+    // the index maps are the columns and rows of the warp path
+    let index_map_x = alignment.index_map_x();
+    let index_map_y = alignment.index_map_y();
+    assert_eq!(index_map_x.len(), alignment.len());
+    for (step, &(row, column)) in alignment.path().iter().enumerate() {
+        assert_eq!(index_map_x[step], column);
+        assert_eq!(index_map_y[step], row);
+    }
+    assert_eq!(index_map_x[0], 0);
+    assert_eq!(index_map_x[alignment.len() - 1], x.len() - 1);
+    assert_eq!(index_map_y[alignment.len() - 1], y.len() - 1);
+
+    assert_eq!(
+        DtwBuilder::new().run(&x.view(), &y.view()).unwrap(),
+        alignment
+    );
+    assert_eq!(alignment.into_parts(), result);
+
+    let unaligned = Alignment::new(f64::INFINITY, Array1::default(0));
+    assert!(unaligned.is_empty());
+    assert_eq!(unaligned.normalized_distance(), f64::INFINITY);
+}
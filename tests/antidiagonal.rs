@@ -0,0 +1,62 @@
+#![cfg(feature = "testdata")]
+
+use dtw::{
+    dtw_antidiagonal, dtw_ex, load_dtw_cases, DistanceMode, DtwError, FullWindow, StorageStrategy,
+};
+use ndarray::Array1;
+use std::path::PathBuf;
+
+#[test]
+fn test_dtw_antidiagonal() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    let mut cases: Vec<(Array1<f64>, Array1<f64>, DistanceMode)> = test_cases
+        .into_iter()
+        .map(|tc| {
+            let distance_mode = tc
+                .parsed_distance_mode()
+                .expect("unknown distance mode specified");
+            (tc.time_series_a, tc.time_series_b, distance_mode)
+        })
+        .collect();
+    for distance_mode in [
+        DistanceMode::Manhattan,
+        DistanceMode::Euclidean,
+        DistanceMode::SquaredEuclidean,
+        DistanceMode::Chebyshev,
+        DistanceMode::Canberra,
+        DistanceMode::Minkowski(3f64),
+    ] {
+        // wide, tall and square cost matrices, including a single row and a single column
+        for (columns, rows) in [(53, 31), (17, 40), (64, 64), (9, 1), (1, 6)] {
+            let x = Array1::from_iter((0..columns).map(|i| f64::sin(i as f64 / 5f64)));
+            let y = Array1::from_iter((0..rows).map(|i| f64::sin(i as f64 / 3f64 + 0.4)));
+            cases.push((x, y, distance_mode));
+        }
+    }
+
+    for (x, y, distance_mode) in cases {
+        let (rows, columns) = (y.shape()[0], x.shape()[0]);
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Dense,
+            f64::INFINITY,
+        );
+        assert_eq!(
+            dtw_antidiagonal(&x.view(), &y.view(), distance_mode),
+            expected
+        );
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    let x = Array1::from(vec![1.0, 2.0]);
+    assert_eq!(
+        dtw_antidiagonal(&x.view(), &empty.view(), DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
@@ -0,0 +1,225 @@
+use dtw::{
+    dba, dtw_ex, softdtw_barycenter, softdtw_ex, DistanceMode, DtwError, FullWindow,
+    StorageStrategy,
+};
+use ndarray::{Array1, ArrayView1};
+
+/// A bump of height 1 centered on `center`, over `size` samples.
+fn bump(size: usize, center: f64, width: f64) -> Array1<f64> {
+    Array1::from_iter((0..size).map(|i| (-((i as f64 - center) / width).powi(2)).exp()))
+}
+
+fn sum_of_squares(series: &[ArrayView1<f64>], center: &Array1<f64>) -> f64 {
+    series
+        .iter()
+        .map(|ts| {
+            let (distance, _) = dtw_ex(
+                &center.view(),
+                ts,
+                FullWindow::new(ts.len(), center.len()),
+                DistanceMode::Euclidean,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            distance * distance
+        })
+        .sum()
+}
+
+#[test]
+fn test_dba() {
+    // the average of identical time series is the time series itself
+    let ts = bump(30, 12f64, 3f64);
+    let series = vec![ts.view(), ts.view(), ts.view()];
+    let center = dba(&series, None, &ts.view(), 10, 0f64, DistanceMode::Euclidean).unwrap();
+    assert!(center
+        .iter()
+        .zip(ts.iter())
+        .all(|(a, b)| (a - b).abs() < 1e-15));
+
+    // bumps shifted in time and of different sizes
+    let owned = [
+        bump(40, 10f64, 3f64),
+        bump(40, 18f64, 3f64),
+        bump(35, 14f64, 2.5),
+        bump(45, 25f64, 4f64),
+    ];
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let initial = &owned[0];
+    let mut previous = sum_of_squares(&series, initial);
+    for max_iterations in 1..6 {
+        let center = dba(
+            &series,
+            None,
+            &initial.view(),
+            max_iterations,
+            0f64,
+            DistanceMode::Euclidean,
+        )
+        .unwrap();
+        assert_eq!(center.len(), initial.len());
+        let current = sum_of_squares(&series, &center);
+        assert!(current <= previous + 1e-12);
+        previous = current;
+    }
+    assert!(previous < sum_of_squares(&series, initial));
+
+    // the average keeps the shape of a single bump of height 1
+    let center = dba(
+        &series,
+        None,
+        &initial.view(),
+        20,
+        0f64,
+        DistanceMode::Euclidean,
+    )
+    .unwrap();
+    let peak = center.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!((peak - 1f64).abs() < 0.1);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        dba(
+            &series,
+            None,
+            &empty.view(),
+            10,
+            0f64,
+            DistanceMode::Euclidean
+        ),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_dba_tolerance() {
+    let owned = [
+        bump(40, 10f64, 3f64),
+        bump(40, 18f64, 3f64),
+        bump(35, 14f64, 2.5),
+        bump(45, 25f64, 4f64),
+    ];
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let initial = owned[0].view();
+    let iterate = |max_iterations, tolerance| {
+        dba(
+            &series,
+            None,
+            &initial,
+            max_iterations,
+            tolerance,
+            DistanceMode::Euclidean,
+        )
+        .unwrap()
+    };
+
+    // a huge tolerance stops after the first iteration, long before the maximum
+    assert_eq!(iterate(50, 1e6), iterate(1, 0f64));
+    // the converged average is the same as when running until it no longer changes
+    let converged = iterate(100, 0f64);
+    let early = iterate(100, 1e-9);
+    assert!(converged
+        .iter()
+        .zip(early.iter())
+        .all(|(a, b)| (a - b).abs() < 1e-9));
+    // while a tolerance above the change of the second iteration stops before the full convergence
+    let first = iterate(1, 0f64);
+    let second = iterate(2, 0f64);
+    let change = first
+        .iter()
+        .zip(second.iter())
+        .fold(0f64, |change, (a, b)| change.max((a - b).abs()));
+    assert!(change > 0f64);
+    assert_eq!(iterate(100, change), second);
+}
+
+#[test]
+fn test_dba_weights() {
+    let owned = [
+        bump(40, 10f64, 3f64),
+        bump(40, 18f64, 3f64),
+        bump(35, 14f64, 2.5),
+        bump(45, 25f64, 4f64),
+    ];
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let initial = owned[0].view();
+    let average = |series: &[ArrayView1<f64>], weights: Option<&[f64]>| {
+        dba(series, weights, &initial, 20, 0f64, DistanceMode::Euclidean).unwrap()
+    };
+
+    // equal weights give plain DBA
+    let unweighted = average(&series, None);
+    assert_eq!(average(&series, Some(&[2f64; 4])), unweighted);
+    // a time series weighted to zero does not affect the barycenter
+    assert_eq!(
+        average(&series, Some(&[1f64, 1f64, 1f64, 0f64])),
+        average(&series[..3], None)
+    );
+    assert_ne!(average(&series[..3], None), unweighted);
+}
+
+#[test]
+#[should_panic]
+fn test_dba_weights_mismatch() {
+    let ts = bump(30, 12f64, 3f64);
+    let _ = dba(
+        &[ts.view(), ts.view()],
+        Some(&[1f64]),
+        &ts.view(),
+        10,
+        0f64,
+        DistanceMode::Euclidean,
+    );
+}
+
+#[test]
+fn test_softdtw_barycenter() {
+    let owned = [
+        bump(40, 10f64, 3f64),
+        bump(40, 18f64, 3f64),
+        bump(35, 14f64, 2.5),
+        bump(45, 25f64, 4f64),
+    ];
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let initial = Array1::<f64>::zeros(40);
+    let gamma = 0.1;
+    let loss = |center: &Array1<f64>| {
+        series
+            .iter()
+            .map(|ts| softdtw_ex(&center.view(), ts, gamma).unwrap())
+            .sum::<f64>()
+    };
+
+    assert_eq!(
+        softdtw_barycenter(&series, &initial.view(), gamma, 0, 0.1).unwrap(),
+        initial
+    );
+    let mut previous = loss(&initial);
+    for iterations in [5, 20, 100] {
+        let center = softdtw_barycenter(&series, &initial.view(), gamma, iterations, 0.1).unwrap();
+        assert_eq!(center.len(), initial.len());
+        let current = loss(&center);
+        assert!(current < previous);
+        previous = current;
+    }
+
+    // the average of a single time series aligns to it almost perfectly
+    let ts = bump(30, 12f64, 3f64);
+    let center = softdtw_barycenter(
+        &[ts.view()],
+        &initial.slice(ndarray::s![..30]),
+        0.01,
+        500,
+        0.2,
+    )
+    .unwrap();
+    let hard = softdtw_ex(&center.view(), &ts.view(), 0f64).unwrap();
+    assert!(hard < 0.01);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        softdtw_barycenter(&series, &empty.view(), gamma, 10, 0.1),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
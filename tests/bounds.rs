@@ -0,0 +1,158 @@
+use dtw::{
+    dtw_ex, lb_keogh, lb_kim_fl, CascadingBounds, DistanceMode, Envelope, FullWindow, PruningStage,
+    SakoeChibaWindow, StorageStrategy,
+};
+use ndarray::Array1;
+
+#[test]
+fn test_envelope() {
+    let ts = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0, 1.0, 5.0, 7.0, 3.0]);
+    for band in [0, 1, 2, 4, 20] {
+        let envelope = Envelope::new(&ts.view(), band);
+        for i in 0..ts.len() {
+            let window = ts.slice(ndarray::s![
+                i.saturating_sub(band)..usize::min(i + band + 1, ts.len())
+            ]);
+            let maximum = window.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let minimum = window.iter().cloned().fold(f64::INFINITY, f64::min);
+            assert_eq!(envelope.upper()[i], maximum);
+            assert_eq!(envelope.lower()[i], minimum);
+        }
+    }
+}
+
+#[test]
+fn test_lb_keogh() {
+    let query = Array1::from_iter((0..50).map(|i| f64::sin(i as f64 / 4f64)));
+    let candidates = [
+        Array1::from_iter((0..50).map(|i| f64::sin((i as f64 - 2f64) / 4f64))),
+        Array1::from_iter((0..50).map(|i| f64::cos(i as f64 / 3f64) * 1.5)),
+        Array1::from_iter((0..50).map(|i| (i % 9) as f64 / 4f64 - 1f64)),
+        query.clone(),
+    ];
+
+    for distance_mode in [
+        DistanceMode::Manhattan,
+        DistanceMode::Euclidean,
+        DistanceMode::SquaredEuclidean,
+        DistanceMode::Chebyshev,
+        DistanceMode::Canberra,
+        DistanceMode::Minkowski(3f64),
+    ] {
+        for band in [0, 1, 3, 10] {
+            let envelope = Envelope::new(&query.view(), band);
+            for candidate in &candidates {
+                let bound = envelope.lb_keogh(&candidate.view(), distance_mode);
+                assert_eq!(
+                    bound,
+                    lb_keogh(&query.view(), &candidate.view(), band, distance_mode)
+                );
+                let (distance, _) = dtw_ex(
+                    &candidate.view(),
+                    &query.view(),
+                    SakoeChibaWindow::new(50, 50, band),
+                    distance_mode,
+                    StorageStrategy::Auto,
+                    f64::INFINITY,
+                )
+                .unwrap();
+                assert!(bound <= distance + 1e-9);
+            }
+        }
+        // identical time series have a bound of zero
+        assert_eq!(
+            lb_keogh(&query.view(), &query.view(), 2, distance_mode),
+            0f64
+        );
+    }
+}
+
+#[test]
+fn test_lb_kim_fl() {
+    let x = Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 4f64)));
+    for y_size in [1, 7, 30, 45] {
+        let y = Array1::from_iter((0..y_size).map(|i| f64::cos(i as f64 / 3f64)));
+        for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+            let bound = lb_kim_fl(&x.view(), &y.view(), distance_mode);
+            let (distance, _) = dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(y_size, 30),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(bound > 0f64);
+            assert!(bound <= distance + 1e-9);
+        }
+    }
+    let single = Array1::from(vec![2.0]);
+    assert_eq!(
+        lb_kim_fl(
+            &single.view(),
+            &Array1::from(vec![5.0]).view(),
+            DistanceMode::Manhattan
+        ),
+        3f64
+    );
+}
+
+#[test]
+fn test_cascading_bounds() {
+    let query = Array1::from_iter((0..40).map(|i| f64::sin(i as f64 / 4f64)));
+    let candidates: Vec<Array1<f64>> = (0..12)
+        .map(|k| {
+            Array1::from_iter((0..40).map(|i| {
+                f64::sin((i as f64 - k as f64) / (4f64 + k as f64 / 3f64)) + k as f64 / 10f64
+            }))
+        })
+        .collect();
+    let band = 3;
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let cascade = CascadingBounds::new(&query.view(), band, distance_mode);
+        let distances: Vec<f64> = candidates
+            .iter()
+            .map(|candidate| {
+                dtw_ex(
+                    &candidate.view(),
+                    &query.view(),
+                    SakoeChibaWindow::new(40, 40, band),
+                    distance_mode,
+                    StorageStrategy::Auto,
+                    f64::INFINITY,
+                )
+                .unwrap()
+                .0
+            })
+            .collect();
+
+        // without a best distance so far, nothing is discarded
+        for (candidate, &distance) in candidates.iter().zip(&distances) {
+            assert_eq!(
+                cascade.evaluate(&candidate.view(), f64::INFINITY),
+                Ok(distance)
+            );
+        }
+
+        // a candidate is only discarded if its distance exceeds the best distance so far
+        let mut stages = Vec::new();
+        for best_so_far in distances.iter() {
+            for (candidate, &distance) in candidates.iter().zip(&distances) {
+                match cascade.evaluate(&candidate.view(), *best_so_far) {
+                    Ok(result) => {
+                        assert_eq!(result, distance);
+                        assert!(distance <= *best_so_far);
+                    }
+                    Err(stage) => {
+                        assert!(distance > *best_so_far);
+                        stages.push(stage);
+                    }
+                }
+            }
+        }
+        assert!(stages.contains(&PruningStage::LbKeogh));
+        assert_eq!(cascade.distance(&candidates[11].view(), distances[0]), None);
+    }
+}
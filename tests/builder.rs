@@ -0,0 +1,134 @@
+use dtw::{
+    derivative_estimate, dtw_ex, Alignment, DistanceMode, DtwBuilder, DtwError, FullWindow,
+    Preprocessing, SakoeChibaWindow, StorageStrategy,
+};
+use ndarray::Array1;
+
+#[test]
+fn test_builder() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0, 1.0, 5.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0, 5.0]);
+    let (rows, columns) = (y.shape()[0], x.shape()[0]);
+
+    // the defaults are those of dtw
+    assert_eq!(
+        DtwBuilder::new().run(&x.view(), &y.view()),
+        dtw::dtw(&x.view(), &y.view()).map(Alignment::from)
+    );
+
+    let builder = DtwBuilder::new()
+        .distance_mode(DistanceMode::Manhattan)
+        .window(Box::new(|rows, columns| {
+            Box::new(SakoeChibaWindow::new(columns, rows, 1))
+        }))
+        .storage(StorageStrategy::Banded)
+        .clamp_delta(4f64);
+    let expected = dtw_ex(
+        &x.view(),
+        &y.view(),
+        SakoeChibaWindow::new(columns, rows, 1),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        4f64,
+    )
+    .unwrap();
+    assert_eq!(
+        builder.run(&x.view(), &y.view()).unwrap().into_parts(),
+        expected
+    );
+
+    // the distance is the raw distance, the normalized one is the average cost per aligned pair
+    let alignment = builder.run(&x.view(), &y.view()).unwrap();
+    assert_eq!(alignment.distance(), expected.0);
+    assert_eq!(
+        alignment.normalized_distance(),
+        expected.0 / expected.1.len() as f64
+    );
+
+    // errors are reported as for dtw_ex
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        DtwBuilder::new().run(&x.view(), &empty.view()),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    let oversized = DtwBuilder::new().window(Box::new(|rows, columns| {
+        Box::new(FullWindow::new(rows + 1, columns))
+    }));
+    assert_eq!(
+        oversized.run(&x.view(), &y.view()),
+        Err(DtwError::MalformedWindow {
+            row: rows + 1,
+            column: 1
+        })
+    );
+}
+
+#[test]
+fn test_builder_cutoff() {
+    let x = Array1::from_iter((0..40).map(|i| f64::sin(i as f64 / 4f64)));
+    let similar = Array1::from_iter((0..40).map(|i| f64::sin((i as f64 - 1f64) / 4f64)));
+    let dissimilar = Array1::from_iter((0..40).map(|i| 3f64 + f64::cos(i as f64 / 2f64)));
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let builder = DtwBuilder::new().distance_mode(distance_mode);
+        let (similar_distance, _) = builder
+            .run(&x.view(), &similar.view())
+            .unwrap()
+            .into_parts();
+        let dissimilar_distance = builder.run(&x.view(), &dissimilar.view()).unwrap();
+        assert!(similar_distance < dissimilar_distance.distance());
+
+        // the cutoff only discards the alignments with a larger distance
+        let cutoff = (similar_distance + dissimilar_distance.distance()) / 2f64;
+        let builder = builder.cutoff(cutoff);
+        assert_eq!(
+            builder.run(&x.view(), &similar.view()).unwrap().distance(),
+            similar_distance
+        );
+        assert_eq!(
+            builder.run(&x.view(), &dissimilar.view()),
+            Err(DtwError::Abandoned)
+        );
+
+        // a cutoff equal to the distance keeps the alignment
+        let builder = DtwBuilder::new()
+            .distance_mode(distance_mode)
+            .cutoff(dissimilar_distance.distance());
+        assert_eq!(
+            builder.run(&x.view(), &dissimilar.view()),
+            Ok(dissimilar_distance)
+        );
+    }
+}
+
+#[test]
+fn test_builder_preprocessing() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0, 1.0, 5.0]);
+    // the same shape at another level, which only the derivatives align perfectly
+    let y = x.map(|v| v + 10f64);
+    let builder = DtwBuilder::new().preprocessing(Preprocessing::Derivative);
+    let alignment = builder.run(&x.view(), &y.view()).unwrap();
+    assert_eq!(alignment.distance(), 0f64);
+
+    let expected = dtw_ex(
+        &derivative_estimate(&x.view()).view(),
+        &derivative_estimate(&y.map(|v| v * 2f64).view()).view(),
+        SakoeChibaWindow::new(x.len(), y.len(), 2),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let builder = builder
+        .distance_mode(DistanceMode::Manhattan)
+        .window(Box::new(|rows, columns| {
+            Box::new(SakoeChibaWindow::new(columns, rows, 2))
+        }));
+    assert_eq!(
+        builder
+            .run(&x.view(), &y.map(|v| v * 2f64).view())
+            .unwrap()
+            .into_parts(),
+        expected
+    );
+}
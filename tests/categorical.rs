@@ -0,0 +1,57 @@
+use dtw::{dtw_categorical, mismatch, DtwError, FullWindow, SubstitutionMatrix};
+use ndarray::Array2;
+
+#[test]
+fn test_dtw_categorical() {
+    // the repeated events are absorbed by the warping, and only "pay" is misaligned
+    let x = ["login", "browse", "browse", "cart", "pay", "logout"];
+    let y = ["login", "browse", "cart", "cart", "checkout", "logout"];
+    let (distance, path) = dtw_categorical(&x, &y, FullWindow::new(6, 6), mismatch).unwrap();
+    assert_eq!(distance, 1f64);
+    assert_eq!(path[0], (0, 0));
+    assert_eq!(path[path.len() - 1], (5, 5));
+    assert!(path.iter().any(|&(row, column)| (row, column) == (4, 4)));
+
+    let labels: Vec<u8> = vec![1, 1, 2, 3, 3, 3];
+    let (distance, _) =
+        dtw_categorical(&labels, &[1u8, 2, 3], FullWindow::new(3, 6), mismatch).unwrap();
+    assert_eq!(distance, 0f64);
+
+    let empty: [u8; 0] = [];
+    assert_eq!(
+        dtw_categorical(&labels, &empty, FullWindow::new(0, 6), mismatch),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_substitution_matrix() {
+    // transitions between purines (A, G) or pyrimidines (C, T) are cheaper than transversions
+    let matrix = SubstitutionMatrix::new(
+        vec!['A', 'C', 'G', 'T'],
+        Array2::from(vec![
+            [0f64, 2f64, 1f64, 2f64],
+            [2f64, 0f64, 2f64, 1f64],
+            [1f64, 2f64, 0f64, 2f64],
+            [2f64, 1f64, 2f64, 0f64],
+        ]),
+    );
+    assert_eq!(matrix.cost(&'A', &'G'), 1f64);
+    assert_eq!(matrix.cost(&'T', &'A'), 2f64);
+
+    let x: Vec<char> = "GATTACA".chars().collect();
+    let y: Vec<char> = "GACTACG".chars().collect();
+    let (distance, _) =
+        dtw_categorical(&x, &y, FullWindow::new(7, 7), |a, b| matrix.cost(a, b)).unwrap();
+    let (mismatches, _) = dtw_categorical(&x, &y, FullWindow::new(7, 7), mismatch).unwrap();
+    assert_eq!(mismatches, 2f64);
+    // a transition and a transition
+    assert_eq!(distance, 2f64);
+}
+
+#[test]
+#[should_panic(expected = "the symbol is not part of the substitution matrix")]
+fn test_substitution_matrix_unknown_symbol() {
+    let matrix = SubstitutionMatrix::new(vec![0u8, 1u8], Array2::from_elem((2, 2), 1f64));
+    matrix.cost(&0, &2);
+}
@@ -0,0 +1,104 @@
+use dtw::{
+    dtw_ex, match_prototypes, znormalize, DistanceMode, DtwError, KnnDtw, SakoeChibaWindow,
+    StorageStrategy,
+};
+use ndarray::{Array1, ArrayView1};
+
+/// A time series of 40 samples of the given class, varied by `seed`.
+fn sample(class: usize, seed: usize) -> Array1<f64> {
+    let shift = (seed % 5) as f64 / 3f64;
+    let noise = |t: usize| 0.1 * (((t * 7 + seed * 13) % 11) as f64 / 11f64 - 0.5);
+    Array1::from_iter((0..40).map(|t| {
+        let value = match class {
+            0 => f64::sin(t as f64 / 4f64 + shift),
+            1 => f64::signum(f64::sin(t as f64 / 4f64 + shift)),
+            _ => (t as f64 + shift) / 20f64 - 1f64,
+        };
+        value + noise(t)
+    }))
+}
+
+#[test]
+fn test_knn_dtw() {
+    let owned: Vec<Array1<f64>> = (0..15).map(|i| sample(i % 3, i)).collect();
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let labels: Vec<usize> = (0..15).map(|i| i % 3).collect();
+
+    for (neighbors, band, distance_mode) in [
+        (1, 4, DistanceMode::Euclidean),
+        (3, 2, DistanceMode::Manhattan),
+        (5, 40, DistanceMode::Euclidean),
+    ] {
+        let mut classifier = KnnDtw::new(neighbors, band, distance_mode);
+        classifier.fit(&series, &labels);
+        for seed in 15..24 {
+            let class = seed % 3;
+            let query = sample(class, seed);
+            assert_eq!(classifier.predict(&query.view()).unwrap(), class);
+
+            // the neighbors are those of an exhaustive search
+            let mut expected: Vec<(usize, f64)> = series
+                .iter()
+                .enumerate()
+                .map(|(i, ts)| {
+                    let (distance, _) = dtw_ex(
+                        ts,
+                        &query.view(),
+                        SakoeChibaWindow::new(40, 40, band),
+                        distance_mode,
+                        StorageStrategy::Auto,
+                        f64::INFINITY,
+                    )
+                    .unwrap();
+                    (i, distance)
+                })
+                .collect();
+            expected.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            expected.truncate(neighbors);
+            assert_eq!(classifier.nearest(&query.view()).unwrap(), expected);
+        }
+    }
+
+    // a tie is won by the label of the nearest neighbor
+    let mut classifier = KnnDtw::new(2, 2, DistanceMode::Euclidean);
+    classifier.fit(&series[..2], &["sine", "square"]);
+    assert_eq!(classifier.predict(&sample(1, 20).view()).unwrap(), "square");
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        classifier.predict(&empty.view()),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_match_prototypes() {
+    let owned: Vec<Array1<f64>> = (0..3).map(|class| sample(class, class)).collect();
+    let prototypes: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    // a scaled and offset sample of the second class, which the z-normalization undoes
+    let query = sample(1, 7).mapv(|v| 3f64 * v + 5f64);
+
+    let (best, distances) =
+        match_prototypes(&query.view(), &prototypes, DistanceMode::Euclidean, 4).unwrap();
+    assert_eq!(best, 1);
+    assert_eq!(distances.len(), 3);
+    for (prototype, &distance) in prototypes.iter().zip(distances.iter()) {
+        let (expected, _) = dtw_ex(
+            &znormalize(prototype).view(),
+            &znormalize(&query.view()).view(),
+            SakoeChibaWindow::new(40, 40, 4),
+            DistanceMode::Euclidean,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert!((distance - expected).abs() < 1e-12);
+        assert!(distances[best] <= distance);
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        match_prototypes(&empty.view(), &prototypes, DistanceMode::Euclidean, 4),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
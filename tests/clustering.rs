@@ -0,0 +1,157 @@
+use dtw::{
+    hierarchical, k_medoids, pdist, DistanceMode, Dtw, DtwError, FastDtw, KMeans, Linkage, Merge,
+};
+use ndarray::{Array1, Array2, ArrayView1};
+
+/// Time series of three shapes, with varying phases and sizes.
+fn shapes() -> Vec<Array1<f64>> {
+    let mut series = Vec::new();
+    for i in 0..4 {
+        let shift = i as f64 / 2f64;
+        let size = 30 + i * 3;
+        series.push(Array1::from_iter(
+            (0..size).map(|t| f64::sin(t as f64 / 3f64 + shift)),
+        ));
+        series.push(Array1::from_iter(
+            (0..size).map(|t| 4f64 + 0.2 * ((t + i) % 5) as f64),
+        ));
+        series.push(Array1::from_iter(
+            (0..size).map(|t| -3f64 + (t as f64 - shift) / 10f64),
+        ));
+    }
+    series
+}
+
+#[test]
+fn test_kmeans() {
+    let owned = shapes();
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+
+    for clustering in [
+        KMeans::new(3)
+            .fit(&Dtw::new(DistanceMode::Euclidean), &series)
+            .unwrap(),
+        KMeans::new(3)
+            .max_iterations(5)
+            .dba_iterations(3)
+            .fit(&FastDtw::new(2, DistanceMode::Euclidean), &series)
+            .unwrap(),
+    ] {
+        assert_eq!(clustering.labels.len(), series.len());
+        assert_eq!(clustering.centroids.len(), 3);
+        assert!(clustering.iterations >= 1);
+        // the time series of each shape share a cluster, and the shapes are in different clusters
+        for (i, &label) in clustering.labels.iter().enumerate() {
+            assert_eq!(label, clustering.labels[i % 3]);
+        }
+        assert_ne!(clustering.labels[0], clustering.labels[1]);
+        assert_ne!(clustering.labels[1], clustering.labels[2]);
+        assert_ne!(clustering.labels[0], clustering.labels[2]);
+    }
+
+    // with a cluster per time series, each time series is its own centroid
+    let clustering = KMeans::new(series.len())
+        .fit(&Dtw::new(DistanceMode::Manhattan), &series)
+        .unwrap();
+    assert_eq!(clustering.inertia, 0f64);
+
+    let mut with_empty = series.clone();
+    let empty = Array1::<f64>::zeros(0);
+    with_empty.push(empty.view());
+    assert_eq!(
+        KMeans::new(2).fit(&Dtw::new(DistanceMode::Euclidean), &with_empty),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_kmeans_too_many_clusters() {
+    let owned = shapes();
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let _ = KMeans::new(series.len() + 1).fit(&Dtw::new(DistanceMode::Euclidean), &series);
+}
+
+/// The distances between points on a line, in three groups.
+fn line_distances() -> Array2<f64> {
+    let points = [0f64, 1f64, 2f64, 10f64, 11f64, 20f64];
+    Array2::from_shape_fn((points.len(), points.len()), |(i, j)| {
+        (points[i] - points[j]).abs()
+    })
+}
+
+#[test]
+fn test_k_medoids() {
+    let distances = line_distances();
+    let clustering = k_medoids(&distances.view(), 3, 10);
+    assert_eq!(clustering.labels, vec![0, 0, 0, 1, 1, 2]);
+    assert_eq!(clustering.medoids[0], 1);
+    assert!(clustering.medoids[1] == 3 || clustering.medoids[1] == 4);
+    assert_eq!(clustering.medoids[2], 5);
+    assert_eq!(clustering.cost, 3f64);
+
+    // a single cluster has the first time series with the smallest sum of distances as its medoid
+    let clustering = k_medoids(&distances.view(), 1, 10);
+    assert_eq!(clustering.medoids, vec![2]);
+    assert_eq!(clustering.labels, vec![0; 6]);
+
+    // with the distances of pdist
+    let owned = shapes();
+    let series: Vec<ArrayView1<f64>> = owned.iter().map(|ts| ts.view()).collect();
+    let distances = pdist(&Dtw::new(DistanceMode::Euclidean), &series).unwrap();
+    let clustering = k_medoids(&distances.view(), 3, 10);
+    for (i, &label) in clustering.labels.iter().enumerate() {
+        assert_eq!(label, clustering.labels[i % 3]);
+    }
+    assert_ne!(clustering.labels[0], clustering.labels[1]);
+    assert_ne!(clustering.labels[1], clustering.labels[2]);
+    assert_ne!(clustering.labels[0], clustering.labels[2]);
+}
+
+#[test]
+fn test_hierarchical() {
+    let distances = line_distances();
+    let merge = |left, right, distance, size| Merge {
+        left,
+        right,
+        distance,
+        size,
+    };
+
+    let dendrogram = hierarchical(&distances.view(), Linkage::Single);
+    assert_eq!(
+        dendrogram.merges,
+        vec![
+            merge(0, 1, 1f64, 2),
+            merge(2, 6, 1f64, 3),
+            merge(3, 4, 1f64, 2),
+            merge(7, 8, 8f64, 5),
+            merge(5, 9, 9f64, 6),
+        ]
+    );
+    let dendrogram = hierarchical(&distances.view(), Linkage::Complete);
+    assert_eq!(
+        dendrogram.merges,
+        vec![
+            merge(0, 1, 1f64, 2),
+            merge(3, 4, 1f64, 2),
+            merge(2, 6, 2f64, 3),
+            merge(5, 7, 10f64, 3),
+            merge(8, 9, 20f64, 6),
+        ]
+    );
+    let dendrogram = hierarchical(&distances.view(), Linkage::Average);
+    assert_eq!(dendrogram.merges[3], merge(7, 8, 9.5, 5));
+    assert_eq!(
+        dendrogram.merges[4].distance,
+        (20f64 + 19f64 + 18f64 + 10f64 + 9f64) / 5f64
+    );
+
+    for linkage in [Linkage::Single, Linkage::Complete, Linkage::Average] {
+        let dendrogram = hierarchical(&distances.view(), linkage);
+        assert_eq!(dendrogram.leaves(), 6);
+        assert_eq!(dendrogram.labels(1), vec![0; 6]);
+        assert_eq!(dendrogram.labels(3), vec![0, 0, 0, 1, 1, 2]);
+        assert_eq!(dendrogram.labels(6), vec![0, 1, 2, 3, 4, 5]);
+    }
+}
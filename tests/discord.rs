@@ -0,0 +1,61 @@
+use dtw::{discord, dtw_ex, DistanceMode, SakoeChibaWindow, StorageStrategy};
+use ndarray::{s, Array1};
+
+#[test]
+fn test_discord() {
+    let length = 10;
+    // a periodic time series with an anomaly
+    let mut values: Vec<f64> = (0..120)
+        .map(|i| f64::sin(i as f64 * std::f64::consts::PI / 5f64) + 0.05 * ((i * 7) % 3) as f64)
+        .collect();
+    for value in &mut values[63..68] {
+        *value = 2.5;
+    }
+    let series = Array1::from(values);
+
+    for (band, distance_mode) in [(2, DistanceMode::Euclidean), (1, DistanceMode::Manhattan)] {
+        let found = discord(&series.view(), length, band, distance_mode).unwrap();
+
+        // the exhaustive search, without pruning
+        let distance = |a: usize, b: usize| {
+            dtw_ex(
+                &series.slice(s![b..b + length]),
+                &series.slice(s![a..a + length]),
+                SakoeChibaWindow::new(length, length, band),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap()
+            .0
+        };
+        let last = series.len() - length;
+        let (location, neighbor, nearest) = (0..=last)
+            .map(|a| {
+                let (neighbor, nearest) = (0..=last)
+                    .filter(|&b| a.abs_diff(b) >= length)
+                    .map(|b| (b, distance(a, b)))
+                    .fold((0, f64::INFINITY), |best, (b, d)| match d < best.1 {
+                        true => (b, d),
+                        false => best,
+                    });
+                (a, neighbor, nearest)
+            })
+            .fold((0, 0, f64::NEG_INFINITY), |best, candidate| {
+                match candidate.2 > best.2 {
+                    true => candidate,
+                    false => best,
+                }
+            });
+        assert_eq!(found.location, location);
+        assert_eq!(found.neighbor, neighbor);
+        assert_eq!(found.distance, nearest);
+        assert!(found.location + length > 63 && found.location < 68);
+    }
+
+    assert_eq!(
+        discord(&series.slice(s![..19]), length, 2, DistanceMode::Euclidean),
+        None
+    );
+    assert!(discord(&series.slice(s![..20]), length, 2, DistanceMode::Euclidean).is_some());
+}
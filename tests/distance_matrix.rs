@@ -0,0 +1,85 @@
+use dtw::{
+    cdist, pdist, ConstrainedWindow, DistanceMode, Dtw, DtwError, ElasticMeasure,
+    GrowableDistanceMatrix,
+};
+use ndarray::Array1;
+
+#[test]
+fn test_distance_matrix() {
+    let series: Vec<Array1<f64>> = (0..7)
+        .map(|k| Array1::from_iter((0..20 + k).map(|i| f64::sin(i as f64 / (2f64 + k as f64)))))
+        .collect();
+    let views: Vec<_> = series.iter().map(|ts| ts.view()).collect();
+    let measures = [
+        Dtw::new(DistanceMode::Euclidean),
+        Dtw::with_window(
+            DistanceMode::Manhattan,
+            Box::new(|rows, columns| {
+                let diagonal = (0..rows.max(columns))
+                    .map(|i| {
+                        (
+                            i * rows / rows.max(columns),
+                            i * columns / rows.max(columns),
+                        )
+                    })
+                    .collect();
+                Box::new(ConstrainedWindow::from_low_res_path(
+                    diagonal, 1, 3, rows, columns,
+                ))
+            }),
+        ),
+    ];
+
+    for measure in &measures {
+        let matrix = pdist(measure, &views).unwrap();
+        assert_eq!(matrix.shape(), &[7, 7]);
+        for i in 0..7 {
+            assert_eq!(matrix[[i, i]], 0f64);
+            for j in 0..7 {
+                assert_eq!(matrix[[i, j]], matrix[[j, i]]);
+                if i < j {
+                    assert_eq!(
+                        matrix[[i, j]],
+                        measure.distance(&views[i], &views[j]).unwrap()
+                    );
+                }
+            }
+        }
+
+        let matrix = cdist(measure, &views[..3], &views[3..]).unwrap();
+        assert_eq!(matrix.shape(), &[3, 4]);
+        for i in 0..3 {
+            for j in 0..4 {
+                assert_eq!(
+                    matrix[[i, j]],
+                    measure.distance(&views[i], &views[3 + j]).unwrap()
+                );
+            }
+        }
+    }
+
+    assert_eq!(pdist(&measures[0], &[]).unwrap().shape(), &[0, 0]);
+}
+
+#[test]
+fn test_growable_distance_matrix() {
+    let series: Vec<Array1<f64>> = (0..6)
+        .map(|k| Array1::from_iter((0..15 + 2 * k).map(|i| f64::cos(i as f64 / (1f64 + k as f64)))))
+        .collect();
+    let views: Vec<_> = series.iter().map(|ts| ts.view()).collect();
+    let measure = Dtw::new(DistanceMode::Euclidean);
+
+    let mut growable = GrowableDistanceMatrix::new(Dtw::new(DistanceMode::Euclidean));
+    assert!(growable.is_empty());
+    for (k, ts) in views.iter().enumerate() {
+        assert_eq!(growable.add(ts), Ok(k));
+        // the grown matrix is the one computed from scratch over the series added so far
+        assert_eq!(growable.matrix(), pdist(&measure, &views[..k + 1]).unwrap());
+    }
+    assert_eq!(growable.len(), 6);
+    assert_eq!(growable.get(4, 2), growable.get(2, 4));
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(growable.add(&empty.view()), Err(DtwError::EmptyTimeSeries));
+    assert_eq!(growable.len(), 6);
+}
@@ -0,0 +1,236 @@
+use dtw::{edr, erp, lcss, msm, twe, DtwError, FullWindow, SakoeChibaWindow};
+use ndarray::Array1;
+
+/// Pseudo random time series with deterministic values.
+fn random_series(count: usize, seed: u64) -> Vec<Array1<f64>> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as f64 / (1u64 << 31) as f64
+    };
+    (0..count)
+        .map(|_| {
+            let size = 5 + (next() * 10f64) as usize;
+            Array1::from_iter((0..size).map(|_| next() * 10f64 - 5f64))
+        })
+        .collect()
+}
+
+#[test]
+fn test_msm() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let full = FullWindow::new(y.len(), x.len());
+    let (distance, path) = msm(&x.view(), &y.view(), full, 0.5).unwrap();
+    assert_eq!(distance, 6.5);
+    assert_eq!(path[0], (0, 0));
+    assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+    let full = FullWindow::new(y.len(), x.len());
+    assert_eq!(msm(&x.view(), &y.view(), full, 2f64).unwrap().0, 8f64);
+
+    // a move followed by a merge
+    let (a, b) = (Array1::from(vec![0.0, 5.0]), Array1::from(vec![5.0]));
+    assert_eq!(
+        msm(&a.view(), &b.view(), FullWindow::new(1, 2), 1f64)
+            .unwrap()
+            .0,
+        6f64
+    );
+
+    // MSM is a metric
+    let series = random_series(6, 7);
+    let distance = |a: &Array1<f64>, b: &Array1<f64>| {
+        msm(
+            &a.view(),
+            &b.view(),
+            FullWindow::new(b.len(), a.len()),
+            1f64,
+        )
+        .unwrap()
+        .0
+    };
+    for a in &series {
+        assert_eq!(distance(a, a), 0f64);
+        for b in &series {
+            assert!((distance(a, b) - distance(b, a)).abs() < 1e-9);
+            for c in &series {
+                assert!(distance(a, c) <= distance(a, b) + distance(b, c) + 1e-9);
+            }
+        }
+    }
+
+    // a band can only increase the distance
+    let window = SakoeChibaWindow::new(x.len(), y.len(), 1);
+    assert!(msm(&x.view(), &y.view(), window, 0.5).unwrap().0 >= 6.5);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        msm(&empty.view(), &y.view(), FullWindow::new(y.len(), 0), 1f64),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_twe() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let full = || FullWindow::new(y.len(), x.len());
+    let (distance, path) = twe(&x.view(), &y.view(), full(), 0.5, 1f64).unwrap();
+    assert_eq!(distance, 13.5);
+    assert_eq!(path[0], (0, 0));
+    assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+    assert!((twe(&x.view(), &y.view(), full(), 0.001, 0f64).unwrap().0 - 11.003).abs() < 1e-12);
+
+    // without penalties, aligning a time series with a repeated sample only costs the repetition
+    let (a, b) = (
+        Array1::from(vec![1.0, 2.0, 3.0]),
+        Array1::from(vec![1.0, 2.0, 2.0, 3.0]),
+    );
+    let (distance, path) = twe(&a.view(), &b.view(), FullWindow::new(4, 3), 0f64, 0f64).unwrap();
+    assert_eq!(distance, 0f64);
+    assert_eq!(path.to_vec(), vec![(0, 0), (1, 1), (2, 1), (3, 2)]);
+
+    // TWE is a metric
+    let series = random_series(6, 11);
+    let distance = |a: &Array1<f64>, b: &Array1<f64>| {
+        twe(
+            &a.view(),
+            &b.view(),
+            FullWindow::new(b.len(), a.len()),
+            0.1,
+            0.5,
+        )
+        .unwrap()
+        .0
+    };
+    for a in &series {
+        assert_eq!(distance(a, a), 0f64);
+        for b in &series {
+            assert!((distance(a, b) - distance(b, a)).abs() < 1e-9);
+            for c in &series {
+                assert!(distance(a, c) <= distance(a, b) + distance(b, c) + 1e-9);
+            }
+        }
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        twe(
+            &empty.view(),
+            &y.view(),
+            FullWindow::new(y.len(), 0),
+            0.5,
+            1f64
+        ),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_erp() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let full = || FullWindow::new(y.len(), x.len());
+    let (distance, path) = erp(&x.view(), &y.view(), full(), 0f64).unwrap();
+    assert_eq!(distance, 9f64);
+    assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+    assert_eq!(erp(&x.view(), &y.view(), full(), 2.5).unwrap().0, 6.5);
+
+    // a leading sample aligned to a gap is not on the warp path
+    let (a, b) = (
+        Array1::from(vec![5.0, 1.0, 2.0]),
+        Array1::from(vec![1.0, 2.0]),
+    );
+    let (distance, path) = erp(&a.view(), &b.view(), FullWindow::new(2, 3), 0f64).unwrap();
+    assert_eq!(distance, 5f64);
+    assert_eq!(path.to_vec(), vec![(0, 1), (1, 2)]);
+
+    // ERP is a metric
+    let series = random_series(6, 13);
+    let distance = |a: &Array1<f64>, b: &Array1<f64>| {
+        erp(&a.view(), &b.view(), FullWindow::new(b.len(), a.len()), 0.5)
+            .unwrap()
+            .0
+    };
+    for a in &series {
+        assert_eq!(distance(a, a), 0f64);
+        for b in &series {
+            assert!((distance(a, b) - distance(b, a)).abs() < 1e-9);
+            for c in &series {
+                assert!(distance(a, c) <= distance(a, b) + distance(b, c) + 1e-9);
+            }
+        }
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        erp(&empty.view(), &y.view(), FullWindow::new(y.len(), 0), 0f64),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_edr() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let full = || FullWindow::new(y.len(), x.len());
+    let (distance, path) = edr(&x.view(), &y.view(), full(), 1f64).unwrap();
+    assert_eq!(distance, 2f64);
+    assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+    assert_eq!(edr(&x.view(), &y.view(), full(), 0.5).unwrap().0, 4f64);
+
+    // an outlier costs a single edit, however large
+    let outlier = Array1::from(vec![1.0, 3.0, 4.0, 1000.0, 8.0, 2.0]);
+    let window = FullWindow::new(x.len(), x.len());
+    assert_eq!(
+        edr(&x.view(), &outlier.view(), window, 0.1).unwrap().0,
+        1f64
+    );
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        edr(&empty.view(), &y.view(), FullWindow::new(y.len(), 0), 1f64),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_lcss() {
+    let x: Array1<f64> = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y: Array1<f64> = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let full = || FullWindow::new(y.len(), x.len());
+    // the longest common subsequences have 4, 3 and 2 pairs of the 5 samples of y
+    let (distance, path) = lcss(&x.view(), &y.view(), full(), 1f64, 10).unwrap();
+    assert!((distance - 0.2).abs() < 1e-12);
+    let matches = path
+        .iter()
+        .filter(|&&(row, column)| (x[column] - y[row]).abs() <= 1f64)
+        .count();
+    assert!(matches >= 4);
+    assert!((lcss(&x.view(), &y.view(), full(), 1f64, 0).unwrap().0 - 0.4).abs() < 1e-12);
+    assert!((lcss(&x.view(), &y.view(), full(), 0.5, 10).unwrap().0 - 0.6).abs() < 1e-12);
+
+    // identical and entirely different time series
+    let window = FullWindow::new(x.len(), x.len());
+    assert_eq!(lcss(&x.view(), &x.view(), window, 0f64, 0).unwrap().0, 0f64);
+    let far = x.map(|v| v + 100f64);
+    let window = FullWindow::new(x.len(), x.len());
+    assert_eq!(
+        lcss(&x.view(), &far.view(), window, 1f64, 10).unwrap().0,
+        1f64
+    );
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        lcss(
+            &empty.view(),
+            &y.view(),
+            FullWindow::new(y.len(), 0),
+            1f64,
+            1
+        ),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
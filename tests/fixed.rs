@@ -0,0 +1,63 @@
+use dtw::{dtw_ex, DistanceMode, FixedSizeDtw, FullWindow, StorageStrategy};
+use ndarray::Array1;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Global allocator which counts the allocations made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|allocations| allocations.get())
+}
+
+#[test]
+fn test_fixed_size_dtw() {
+    let pairs: Vec<(Array1<f64>, Array1<f64>)> = (0..5)
+        .map(|k| {
+            let x = Array1::from_iter((0..40).map(|i| f64::sin((i + k) as f64 / 5f64)));
+            let y = Array1::from_iter((0..30).map(|i| f64::cos((i * k) as f64 / 7f64)));
+            (x, y)
+        })
+        .collect();
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let mut fixed = FixedSizeDtw::new(40, 30, distance_mode);
+        for (x, y) in pairs.iter() {
+            let (expected_distance, expected_path) = dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(30, 40),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+
+            // no allocations happen once the fixed size dtw is created
+            let before = allocations();
+            let (distance, path) = fixed.run(&x.view(), &y.view());
+            assert_eq!(allocations(), before);
+
+            assert_eq!(distance, expected_distance);
+            assert_eq!(path, expected_path.to_vec());
+        }
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "testdata")]
+
+use dtw::{
+    dtw_ex, dtw_hirschberg, load_dtw_cases, DistanceMode, DtwError, FullWindow, StorageStrategy,
+};
+use ndarray::Array1;
+use std::path::PathBuf;
+
+/// The sum of the local costs along a warp path, before the final transformation of the distance.
+fn path_cost(
+    x: &Array1<f64>,
+    y: &Array1<f64>,
+    path: &Array1<(usize, usize)>,
+    mode: DistanceMode,
+) -> f64 {
+    path.iter()
+        .map(|&(row, column)| {
+            let difference = x[column] - y[row];
+            match mode {
+                DistanceMode::Manhattan => difference.abs(),
+                DistanceMode::Euclidean => difference * difference,
+                _ => unreachable!(),
+            }
+        })
+        .sum()
+}
+
+#[test]
+fn test_dtw_hirschberg() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    let mut cases: Vec<(Array1<f64>, Array1<f64>, DistanceMode)> = test_cases
+        .into_iter()
+        .map(|tc| {
+            let distance_mode = tc
+                .parsed_distance_mode()
+                .expect("unknown distance mode specified");
+            (tc.time_series_a, tc.time_series_b, distance_mode)
+        })
+        .collect();
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let x = Array1::from_iter((0..67).map(|i| f64::sin(i as f64 / 5f64)));
+        let y = Array1::from_iter((0..41).map(|i| f64::sin(i as f64 / 3f64 + 0.4)));
+        cases.push((x, y, distance_mode));
+    }
+
+    for (x, y, distance_mode) in cases {
+        let (rows, columns) = (y.shape()[0], x.shape()[0]);
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        let (distance, path) = dtw_hirschberg(&x.view(), &y.view(), distance_mode).unwrap();
+        assert_eq!(distance, expected.0);
+
+        // the warp path is a valid warp path with the optimal cost
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[path.len() - 1], (rows - 1, columns - 1));
+        for step in path.windows(2) {
+            let ((row, column), (next_row, next_column)) = (step[0], step[1]);
+            assert!(next_row - row <= 1 && next_column - column <= 1);
+            assert!((next_row, next_column) != (row, column));
+        }
+        let expected_cost = path_cost(&x, &y, &expected.1, distance_mode);
+        let cost = path_cost(&x, &y, &path, distance_mode);
+        assert!((cost - expected_cost).abs() <= 1e-9 * expected_cost.max(1f64));
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        dtw_hirschberg(&empty.view(), &empty.view(), DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
@@ -0,0 +1,34 @@
+#![cfg(all(feature = "interop", feature = "testdata"))]
+
+use dtw::{from_tslearn_json, load_dtw_cases, to_tslearn_json};
+use ndarray::Array1;
+use std::path::PathBuf;
+
+#[test]
+fn test_tslearn_json() {
+    // x has 3 samples and y has 2, so the path ends with x index 2 and y index 1
+    let result = (1.5, Array1::from_vec(vec![(0, 0), (0, 1), (1, 2)]));
+    let json = to_tslearn_json(&result);
+    assert_eq!(json, r#"{"path":[[0,0],[1,0],[2,1]],"distance":1.5}"#);
+    assert_eq!(from_tslearn_json(&json).unwrap(), result);
+
+    // all fixtures survive the round trip
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let result = (tc.distance, tc.warp_path);
+        assert_eq!(
+            from_tslearn_json(&to_tslearn_json(&result)).unwrap(),
+            result
+        );
+    }
+
+    // an infinite distance is written as null and read back
+    let unaligned = (f64::INFINITY, Array1::default(0));
+    let json = to_tslearn_json(&unaligned);
+    assert_eq!(json, r#"{"path":[],"distance":null}"#);
+    assert_eq!(from_tslearn_json(&json).unwrap(), unaligned);
+
+    assert!(from_tslearn_json(r#"{"path":[[0,0]]}"#).is_err());
+}
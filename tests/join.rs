@@ -0,0 +1,53 @@
+use dtw::{dtw_ex, similarity_join, DistanceMode, SakoeChibaWindow, StorageStrategy};
+use ndarray::{Array1, ArrayView1};
+
+/// A time series of 30 samples of distinct shape for each seed.
+fn shape(seed: usize) -> Array1<f64> {
+    Array1::from_iter((0..30).map(|t| {
+        let t = t as f64;
+        match seed % 3 {
+            0 => f64::sin(t / (2f64 + seed as f64)),
+            1 => f64::cos(t / 3f64) * (seed as f64 / 2f64),
+            _ => (t - 10f64 * seed as f64 / 3f64) / 10f64,
+        }
+    }))
+}
+
+#[test]
+fn test_similarity_join() {
+    let left_owned: Vec<Array1<f64>> = (0..4).map(shape).collect();
+    let mut right_owned: Vec<Array1<f64>> = (4..9).map(shape).collect();
+    // a shifted and slightly perturbed copy of left[1] planted at right[3]
+    right_owned[3] =
+        Array1::from_iter((0..30).map(|t| left_owned[1][t.max(1) - 1] + 0.01 * f64::sin(t as f64)));
+    let left: Vec<ArrayView1<f64>> = left_owned.iter().map(|ts| ts.view()).collect();
+    let right: Vec<ArrayView1<f64>> = right_owned.iter().map(|ts| ts.view()).collect();
+
+    let (band, threshold) = (3, 0.5);
+    for distance_mode in [DistanceMode::Euclidean, DistanceMode::Manhattan] {
+        let pairs = similarity_join(&left, &right, threshold, band, distance_mode);
+        assert_eq!(pairs.len(), 1);
+        let (i, j, distance) = pairs[0];
+        assert_eq!((i, j), (1, 3));
+
+        let (expected, _) = dtw_ex(
+            &right[j],
+            &left[i],
+            SakoeChibaWindow::new(30, 30, band),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert!((distance - expected).abs() < 1e-12);
+        assert!(distance <= threshold);
+    }
+
+    // every pair matches under an infinite threshold
+    let pairs = similarity_join(&left, &right, f64::INFINITY, band, DistanceMode::Euclidean);
+    assert_eq!(pairs.len(), left.len() * right.len());
+    assert!(pairs
+        .windows(2)
+        .all(|pair| (pair[0].0, pair[0].1) < (pair[1].0, pair[1].1)));
+    assert!(similarity_join(&left, &[], 1f64, band, DistanceMode::Euclidean).is_empty());
+}
@@ -0,0 +1,82 @@
+use dtw::{gak_log, gak_normalized, DtwError};
+use ndarray::{Array1, Array2};
+
+/// The kernel computed directly, without the log space.
+fn gak_direct(x: &Array1<f64>, y: &Array1<f64>, sigma: f64, triangular: usize) -> f64 {
+    let mut kernels = Array2::<f64>::zeros((y.len() + 1, x.len() + 1));
+    kernels[(0, 0)] = 1f64;
+    for row in 1..=y.len() {
+        for column in 1..=x.len() {
+            let phase_difference = row.abs_diff(column) as f64;
+            let weight = match triangular {
+                0 => 1f64,
+                _ => f64::max(0f64, 1f64 - phase_difference / triangular as f64),
+            };
+            let scaled = (x[column - 1] - y[row - 1]).powi(2) / (2f64 * sigma * sigma);
+            let local = (-scaled).exp() / (2f64 - (-scaled).exp());
+            kernels[(row, column)] = weight
+                * local
+                * (kernels[(row - 1, column)]
+                    + kernels[(row, column - 1)]
+                    + kernels[(row - 1, column - 1)]);
+        }
+    }
+    kernels[(y.len(), x.len())]
+}
+
+#[test]
+fn test_gak() {
+    let series: Vec<Array1<f64>> = (0..6)
+        .map(|i| {
+            Array1::from_iter(
+                (0..8 + i)
+                    .map(|t| f64::sin(t as f64 / (2f64 + i as f64 / 3f64)) + i as f64 / 10f64),
+            )
+        })
+        .collect();
+
+    for (sigma, triangular) in [(1f64, 0), (0.5, 3), (2f64, 1)] {
+        for x in &series {
+            for y in &series {
+                let value = gak_log(&x.view(), &y.view(), sigma, triangular).unwrap();
+                let expected = gak_direct(x, y, sigma, triangular);
+                match expected {
+                    0f64 => assert_eq!(value, f64::NEG_INFINITY),
+                    _ => assert!((value - expected.ln()).abs() < 1e-9),
+                }
+            }
+        }
+    }
+
+    // the normalized Gram matrix is positive semi-definite, as its Cholesky decomposition shows
+    let size = series.len();
+    let gram = Array2::from_shape_fn((size, size), |(i, j)| {
+        gak_normalized(&series[i].view(), &series[j].view(), 1f64, 0).unwrap()
+    });
+    let mut lower = Array2::<f64>::zeros((size, size));
+    for i in 0..size {
+        assert!((gram[(i, i)] - 1f64).abs() < 1e-12);
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[(i, k)] * lower[(j, k)]).sum();
+            if i == j {
+                assert!(gram[(i, i)] - sum >= -1e-12);
+                lower[(i, j)] = (gram[(i, i)] - sum).max(0f64).sqrt();
+            } else {
+                lower[(i, j)] = (gram[(i, j)] - sum) / lower[(j, j)];
+            }
+        }
+    }
+
+    // the log space avoids the underflow of long time series, whose kernel is below the smallest float
+    let long = Array1::from_iter((0..2000).map(|t| f64::sin(t as f64 / 10f64)));
+    let shifted = long.map(|v| v + 3f64);
+    let value = gak_log(&long.view(), &shifted.view(), 0.5, 0).unwrap();
+    assert!(value.is_finite());
+    assert!(value < -745f64);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        gak_log(&empty.view(), &long.view(), 1f64, 0),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
@@ -0,0 +1,124 @@
+use dtw::{
+    complexity_correction, complexity_estimate, Cid, ConstrainedWindow, DistanceMode, Dtw,
+    DtwError, ElasticMeasure, FastDtw, Msm,
+};
+use ndarray::{Array1, ArrayView1};
+
+/// Generic nearest neighbor search, written once for any elastic measure.
+fn nearest_neighbor<M: ElasticMeasure>(
+    measure: &M,
+    query: &ArrayView1<f64>,
+    candidates: &[Array1<f64>],
+) -> (usize, f64) {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| (index, measure.distance(query, &candidate.view()).unwrap()))
+        .fold((usize::MAX, f64::INFINITY), |best, current| {
+            match current.1 < best.1 {
+                true => current,
+                false => best,
+            }
+        })
+}
+
+#[test]
+fn test_nearest_neighbor() {
+    let candidates = vec![
+        Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 4f64))),
+        Array1::from_iter((0..30).map(|i| (i % 10) as f64 / 5f64)),
+        Array1::from_iter((0..30).map(|i| f64::cos(i as f64 / 2f64))),
+    ];
+    // a time shifted version of the first candidate
+    let query = Array1::from_iter((0..30).map(|i| f64::sin((i as f64 - 2f64) / 4f64)));
+
+    let measure = Dtw::new(DistanceMode::Euclidean);
+    let (index, distance) = nearest_neighbor(&measure, &query.view(), &candidates);
+    assert_eq!(index, 0);
+    assert!(distance < 1f64);
+
+    // a band around the diagonal can only lead to larger distances
+    let banded = Dtw::with_window(
+        DistanceMode::Euclidean,
+        Box::new(|rows, columns| {
+            let diagonal = Array1::from_iter((0..rows.max(columns)).map(|i| {
+                (
+                    i * rows / rows.max(columns),
+                    i * columns / rows.max(columns),
+                )
+            }));
+            Box::new(ConstrainedWindow::from_low_res_path(
+                diagonal, 1, 1, rows, columns,
+            ))
+        }),
+    );
+    let (banded_index, banded_distance) = nearest_neighbor(&banded, &query.view(), &candidates);
+    assert_eq!(banded_index, 0);
+    assert!(banded_distance >= distance);
+}
+
+#[test]
+fn test_cid() {
+    let line = Array1::from(vec![0.0, 3.0, 7.0]);
+    // 3^2 + 4^2 = 5^2
+    assert_eq!(complexity_estimate(&line.view()), 5f64);
+    assert_eq!(complexity_estimate(&Array1::from(vec![2.0]).view()), 0f64);
+
+    let smooth = Array1::from_iter((0..40).map(|i| f64::sin(i as f64 / 6f64)));
+    let jagged =
+        Array1::from_iter((0..40).map(|i| f64::sin(i as f64 / 6f64) + 0.8 * (i % 2) as f64 - 0.4));
+    let other_jagged = Array1::from_iter(
+        (0..40).map(|i| f64::cos(i as f64 / 6f64) + 0.8 * ((i + 1) % 2) as f64 - 0.4),
+    );
+    let factor = complexity_correction(&smooth.view(), &jagged.view());
+    assert!(factor > 1f64);
+    assert_eq!(
+        factor,
+        complexity_correction(&jagged.view(), &smooth.view())
+    );
+    assert_eq!(complexity_correction(&jagged.view(), &jagged.view()), 1f64);
+
+    let dtw = Dtw::new(DistanceMode::Euclidean);
+    let cid = Cid::new(Dtw::new(DistanceMode::Euclidean));
+    assert_eq!(
+        cid.distance(&smooth.view(), &jagged.view()).unwrap(),
+        dtw.distance(&smooth.view(), &jagged.view()).unwrap() * factor
+    );
+    // the smooth time series is the nearest neighbor of the jagged one, unless corrected
+    let candidates = [smooth.clone(), other_jagged];
+    assert_eq!(nearest_neighbor(&dtw, &jagged.view(), &candidates).0, 0);
+    assert_eq!(nearest_neighbor(&cid, &jagged.view(), &candidates).0, 1);
+
+    // constant time series
+    let constant = Array1::from_elem(5, 1f64);
+    assert_eq!(
+        complexity_correction(&constant.view(), &constant.view()),
+        1f64
+    );
+    assert_eq!(
+        complexity_correction(&constant.view(), &line.view()),
+        f64::INFINITY
+    );
+}
+
+#[test]
+fn test_empty_time_series() {
+    let empty = Array1::<f64>::zeros(0);
+    let ts = Array1::from(vec![1.0, 2.0, 3.0]);
+    let measures: [Box<dyn ElasticMeasure>; 4] = [
+        Box::new(Dtw::new(DistanceMode::Euclidean)),
+        Box::new(FastDtw::new(1, DistanceMode::Euclidean)),
+        Box::new(Msm { cost: 1f64 }),
+        Box::new(Cid::new(Dtw::new(DistanceMode::Euclidean))),
+    ];
+    for measure in &measures {
+        assert_eq!(
+            measure.distance(&empty.view(), &ts.view()),
+            Err(DtwError::EmptyTimeSeries)
+        );
+        assert_eq!(
+            measure.distance(&ts.view(), &empty.view()),
+            Err(DtwError::EmptyTimeSeries)
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use dtw::{dtw_ex, motifs, DistanceMode, Motif, SakoeChibaWindow, StorageStrategy};
+use ndarray::{s, Array1};
+
+/// Deterministic pseudo random noise in [-amplitude, amplitude].
+fn noise(size: usize, amplitude: f64) -> Vec<f64> {
+    let mut state = 42u64;
+    (0..size)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            amplitude * (((state >> 33) as f64 / (1u64 << 31) as f64) * 2f64 - 1f64)
+        })
+        .collect()
+}
+
+#[test]
+fn test_motifs() {
+    let length = 12;
+    let mut values = noise(150, 1f64);
+    // two occurrences each of a peak and of a ramp
+    for (start, pattern) in [(10, 0), (90, 0), (40, 1), (120, 1)] {
+        for i in 0..length {
+            values[start + i] = match pattern {
+                0 => 3f64 * f64::sin(i as f64 * std::f64::consts::PI / 11f64),
+                _ => -2f64 + i as f64 / 3f64,
+            } + 0.01 * values[start + i];
+        }
+    }
+    let series = Array1::from(values);
+
+    for (band, distance_mode) in [(2, DistanceMode::Euclidean), (0, DistanceMode::Manhattan)] {
+        let found = motifs(&series.view(), length, 3, band, distance_mode);
+        assert_eq!(found.len(), 3);
+
+        // the greedy search over all pairs, without pruning
+        let mut expected: Vec<Motif> = Vec::new();
+        let overlaps = |found: &[Motif], start: usize| {
+            found.iter().any(|motif| {
+                start.abs_diff(motif.first) < length || start.abs_diff(motif.second) < length
+            })
+        };
+        for _ in 0..3 {
+            let mut best: Option<Motif> = None;
+            for first in 0..=series.len() - 2 * length {
+                for second in first + length..=series.len() - length {
+                    if overlaps(&expected, first) || overlaps(&expected, second) {
+                        continue;
+                    }
+                    let (distance, _) = dtw_ex(
+                        &series.slice(s![second..second + length]),
+                        &series.slice(s![first..first + length]),
+                        SakoeChibaWindow::new(length, length, band),
+                        distance_mode,
+                        StorageStrategy::Auto,
+                        f64::INFINITY,
+                    )
+                    .unwrap();
+                    if best.is_none_or(|motif| distance < motif.distance) {
+                        best = Some(Motif {
+                            first,
+                            second,
+                            distance,
+                        });
+                    }
+                }
+            }
+            expected.push(best.unwrap());
+        }
+        assert_eq!(found, expected);
+
+        // the planted patterns are the two most similar pairs
+        let mut planted = vec![
+            (found[0].first, found[0].second),
+            (found[1].first, found[1].second),
+        ];
+        planted.sort();
+        assert_eq!(planted, vec![(10, 90), (40, 120)]);
+    }
+
+    // a time series too short for a pair
+    assert!(motifs(
+        &series.slice(s![..23]),
+        length,
+        2,
+        2,
+        DistanceMode::Euclidean
+    )
+    .is_empty());
+    assert_eq!(
+        motifs(
+            &series.slice(s![..24]),
+            length,
+            2,
+            2,
+            DistanceMode::Euclidean
+        )
+        .len(),
+        1
+    );
+}
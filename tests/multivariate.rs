@@ -0,0 +1,318 @@
+#![cfg(feature = "testdata")]
+
+use dtw::{
+    dtw_ex, dtw_haversine, dtw_mahalanobis, dtw_multivariate, fastdtw_ex, fastdtw_multivariate,
+    haversine_distance, load_dtw_cases, multivariate_distance, precision_matrix, DistanceMode,
+    DtwError, FullWindow, MultivariateMode, StorageStrategy, EARTH_RADIUS,
+};
+use ndarray::{Array1, Array2, Axis};
+use std::path::PathBuf;
+
+#[test]
+fn test_single_feature() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    // a single feature gives the univariate results
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+        let x2 = x.insert_axis(Axis(1));
+        let y2 = y.insert_axis(Axis(1));
+
+        assert_eq!(
+            dtw_multivariate(&x2, &y2, FullWindow::new(y.len(), x.len()), distance_mode).unwrap(),
+            dtw_ex(
+                &x,
+                &y,
+                FullWindow::new(y.len(), x.len()),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            fastdtw_multivariate(&x2, &y2, 2, 1, distance_mode).unwrap(),
+            fastdtw_ex(&x, &y, 2, 1, distance_mode).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_multivariate() {
+    // two features, where y is x delayed by 3 samples
+    let frames = |delay: f64| {
+        Array2::from_shape_fn((30, 2), |(i, feature)| {
+            let t = (i as f64 - delay) / 4f64;
+            match feature {
+                0 => t.sin(),
+                _ => 0.5 * t.cos(),
+            }
+        })
+    };
+    let x = frames(0f64);
+    let y = frames(3f64);
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let (distance, path) =
+            dtw_multivariate(&x.view(), &y.view(), FullWindow::new(30, 30), distance_mode).unwrap();
+
+        // the delayed part of y is aligned to the same frames of x
+        for &(row, column) in path.iter().filter(|&&(row, _)| (6..27).contains(&row)) {
+            assert_eq!(column + 3, row);
+        }
+
+        // the distance combines the features, so it is larger than the one of each feature alone
+        for feature in 0..2 {
+            let x_feature: Array1<f64> = x.column(feature).to_owned();
+            let y_feature: Array1<f64> = y.column(feature).to_owned();
+            let (feature_distance, _) = dtw_ex(
+                &x_feature.view(),
+                &y_feature.view(),
+                FullWindow::new(30, 30),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(distance >= feature_distance);
+        }
+
+        let (fast_distance, _) =
+            fastdtw_multivariate(&x.view(), &y.view(), 2, 1, distance_mode).unwrap();
+        assert!(fast_distance >= distance);
+        assert!(fast_distance.is_finite());
+    }
+}
+
+#[test]
+fn test_mismatched_features() {
+    let x = Array2::<f64>::zeros((4, 2));
+    let y = Array2::<f64>::zeros((4, 3));
+    let expected = Err(DtwError::DimensionMismatch {
+        x_features: 2,
+        y_features: 3,
+    });
+    assert_eq!(
+        dtw_multivariate(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(4, 4),
+            DistanceMode::Manhattan,
+        ),
+        expected
+    );
+    assert_eq!(
+        fastdtw_multivariate(&x.view(), &y.view(), 2, 1, DistanceMode::Manhattan),
+        expected
+    );
+    assert_eq!(
+        multivariate_distance(
+            &x.view(),
+            &y.view(),
+            MultivariateMode::Independent,
+            DistanceMode::Manhattan,
+        ),
+        Err(DtwError::DimensionMismatch {
+            x_features: 2,
+            y_features: 3,
+        })
+    );
+}
+
+#[test]
+fn test_multivariate_modes() {
+    // the two features are delayed in opposite directions, so no single warp path fits both
+    let x = Array2::from_shape_fn((20, 2), |(i, _)| (i as f64 / 3f64).sin());
+    let y = Array2::from_shape_fn((20, 2), |(i, feature)| {
+        let delay = match feature {
+            0 => 2f64,
+            _ => -2f64,
+        };
+        ((i as f64 - delay) / 3f64).sin()
+    });
+
+    let dependent = multivariate_distance(
+        &x.view(),
+        &y.view(),
+        MultivariateMode::Dependent,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    let independent = multivariate_distance(
+        &x.view(),
+        &y.view(),
+        MultivariateMode::Independent,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert!(independent < dependent / 2f64);
+
+    // the dependent mode is dtw_multivariate and the independent mode sums the per feature distances
+    let (expected, _) = dtw_multivariate(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(20, 20),
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(dependent, expected);
+    let expected: f64 = (0..2)
+        .map(|feature| {
+            dtw_ex(
+                &x.column(feature),
+                &y.column(feature),
+                FullWindow::new(20, 20),
+                DistanceMode::Manhattan,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap()
+            .0
+        })
+        .sum();
+    assert_eq!(independent, expected);
+}
+
+#[test]
+fn test_multivariate_chebyshev() {
+    let x = Array2::from(vec![[0f64, 0f64], [1f64, 3f64]]);
+    let y = Array2::from(vec![[0f64, 0f64], [2f64, 1f64]]);
+    let window = || FullWindow::new(2, 2);
+    // the local cost of the last samples is the largest of 1 and 2, instead of their sum
+    let (distance, path) =
+        dtw_multivariate(&x.view(), &y.view(), window(), DistanceMode::Chebyshev).unwrap();
+    assert_eq!(distance, 2f64);
+    assert_eq!(path.to_vec(), vec![(0, 0), (1, 1)]);
+    let (distance, _) =
+        dtw_multivariate(&x.view(), &y.view(), window(), DistanceMode::Manhattan).unwrap();
+    assert_eq!(distance, 3f64);
+}
+
+#[test]
+fn test_dtw_mahalanobis() {
+    let x = Array2::from_shape_fn((15, 2), |(i, feature)| match feature {
+        0 => (i as f64 / 3f64).sin(),
+        _ => 20f64 * (i as f64 / 4f64).cos(),
+    });
+    let y = Array2::from_shape_fn((12, 2), |(i, feature)| match feature {
+        0 => (i as f64 / 2f64 + 0.3).sin(),
+        _ => 20f64 * (i as f64 / 3f64).cos(),
+    });
+    let window = || FullWindow::new(12, 15);
+
+    // the identity precision matrix gives the Euclidean distance between the feature vectors
+    let identity = Array2::<f64>::eye(2);
+    assert_eq!(
+        dtw_mahalanobis(&x.view(), &y.view(), window(), &identity.view()),
+        dtw_multivariate(&x.view(), &y.view(), window(), DistanceMode::Euclidean)
+    );
+
+    // a diagonal covariance matrix rescales the features by their standard deviations
+    let covariance = Array2::from(vec![[0.25, 0f64], [0f64, 400f64]]);
+    let precision = precision_matrix(&covariance.view()).unwrap();
+    let scale = |ts: &Array2<f64>| {
+        Array2::from_shape_fn(ts.raw_dim(), |(i, feature)| match feature {
+            0 => ts[[i, feature]] / 0.5,
+            _ => ts[[i, feature]] / 20f64,
+        })
+    };
+    let (distance, path) =
+        dtw_mahalanobis(&x.view(), &y.view(), window(), &precision.view()).unwrap();
+    let (expected, expected_path) = dtw_multivariate(
+        &scale(&x).view(),
+        &scale(&y).view(),
+        window(),
+        DistanceMode::Euclidean,
+    )
+    .unwrap();
+    assert!((distance - expected).abs() < 1e-9);
+    assert_eq!(path, expected_path);
+
+    assert_eq!(
+        dtw_mahalanobis(
+            &x.view(),
+            &Array2::<f64>::zeros((5, 3)).view(),
+            window(),
+            &identity.view()
+        ),
+        Err(DtwError::DimensionMismatch {
+            x_features: 2,
+            y_features: 3,
+        })
+    );
+}
+
+#[test]
+fn test_precision_matrix() {
+    let covariance = Array2::from(vec![
+        [4f64, 2f64, 0.5],
+        [2f64, 3f64, 1f64],
+        [0.5, 1f64, 2f64],
+    ]);
+    let precision = precision_matrix(&covariance.view()).unwrap();
+    let product = covariance.dot(&precision);
+    for ((row, column), &value) in product.indexed_iter() {
+        let expected = match row == column {
+            true => 1f64,
+            false => 0f64,
+        };
+        assert!((value - expected).abs() < 1e-12);
+    }
+
+    // perfectly correlated features
+    let singular = Array2::from(vec![[1f64, 2f64], [2f64, 4f64]]);
+    assert_eq!(precision_matrix(&singular.view()), None);
+}
+
+#[test]
+fn test_dtw_haversine() {
+    // a degree of latitude, and a degree of longitude on the equator
+    let degree = EARTH_RADIUS * std::f64::consts::PI / 180f64;
+    assert!((haversine_distance((10f64, 5f64), (11f64, 5f64)) - degree).abs() < 1e-6);
+    assert!((haversine_distance((0f64, 179.5), (0f64, -179.5)) - degree).abs() < 1e-6);
+    // antipodal positions
+    let half_circumference = EARTH_RADIUS * std::f64::consts::PI;
+    assert!((haversine_distance((90f64, 0f64), (-90f64, 0f64)) - half_circumference).abs() < 1e-6);
+    assert_eq!(haversine_distance((48.2, 16.4), (48.2, 16.4)), 0f64);
+
+    // the same route sampled at different rates
+    let route = |size: usize| {
+        Array2::from_shape_fn((size, 2), |(i, coordinate)| {
+            let t = i as f64 / (size - 1) as f64;
+            match coordinate {
+                0 => 52.5 + 0.1 * t,
+                _ => 13.4 + 0.2 * t * t,
+            }
+        })
+    };
+    let x = route(30);
+    let y = route(12);
+    let (distance, path) = dtw_haversine(&x.view(), &y.view(), FullWindow::new(12, 30)).unwrap();
+    let expected: f64 = path
+        .iter()
+        .map(|&(row, column)| {
+            haversine_distance((x[[column, 0]], x[[column, 1]]), (y[[row, 0]], y[[row, 1]]))
+        })
+        .sum();
+    assert!((distance - expected).abs() < 1e-6);
+    let (distance, _) = dtw_haversine(&x.view(), &x.view(), FullWindow::new(30, 30)).unwrap();
+    assert_eq!(distance, 0f64);
+
+    assert_eq!(
+        dtw_haversine(
+            &x.view(),
+            &Array2::<f64>::zeros((5, 3)).view(),
+            FullWindow::new(5, 30)
+        ),
+        Err(DtwError::DimensionMismatch {
+            x_features: 2,
+            y_features: 3,
+        })
+    );
+}
@@ -0,0 +1,45 @@
+use dtw::{dtw_ex, DistanceMode, FullWindow, OnlineDtw, StorageStrategy};
+use ndarray::Array1;
+
+#[test]
+fn test_online_dtw() {
+    let x: Vec<f64> = (0..25).map(|i| f64::sin(i as f64 / 3f64)).collect();
+    let y: Vec<f64> = (0..20).map(|i| f64::cos(i as f64 / 2f64)).collect();
+    // the order in which the samples are pushed, true for x
+    let pushes = (0..45).map(|i| (i * 7) % 9 < 5);
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let mut online = OnlineDtw::new(distance_mode);
+        assert_eq!(online.distance(), None);
+        let (mut x_len, mut y_len) = (0, 0);
+        for push_x in pushes.clone() {
+            match (push_x && x_len < x.len()) || y_len == y.len() {
+                true => {
+                    online.push_x(x[x_len]);
+                    x_len += 1;
+                }
+                false => {
+                    online.push_y(y[y_len]);
+                    y_len += 1;
+                }
+            }
+            assert_eq!((online.x_len(), online.y_len()), (x_len, y_len));
+            if x_len == 0 || y_len == 0 {
+                assert_eq!(online.distance(), None);
+                continue;
+            }
+            let x = Array1::from(x[..x_len].to_vec());
+            let y = Array1::from(y[..y_len].to_vec());
+            let (distance, _) = dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(y_len, x_len),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert_eq!(online.distance(), Some(distance));
+        }
+    }
+}
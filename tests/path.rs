@@ -0,0 +1,166 @@
+#![cfg(feature = "testdata")]
+
+use dtw::{
+    apply_warp, index_arrays, load_dtw_cases, warp, warp_amount, warping_function, IndexMapping,
+    WarpInterpolation,
+};
+use ndarray::Array1;
+use std::path::PathBuf;
+
+#[test]
+fn test_warping_function() {
+    // x index 0 is aligned to y 0..=2, x index 1 to y 3, x indices 2 and 3 to y 4
+    let path = Array1::from_vec(vec![(0, 0), (1, 0), (2, 0), (3, 1), (4, 2), (4, 3)]);
+    assert_eq!(
+        warping_function(&path, 4),
+        Array1::from_vec(vec![1, 3, 4, 4])
+    );
+
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let x_size = tc.time_series_a.shape()[0];
+        let y_size = tc.time_series_b.shape()[0];
+        let warping = warping_function(&tc.warp_path, x_size);
+        assert_eq!(warping.len(), x_size);
+        for i in 1..x_size {
+            assert!(warping[i - 1] <= warping[i]);
+        }
+        assert!(warping.iter().all(|&j| j < y_size));
+    }
+}
+
+#[test]
+fn test_warp_amount() {
+    // a diagonal path does not warp, also for time series of different lengths
+    let diagonal = Array1::from_iter((0..10).map(|i| (i, i)));
+    assert_eq!(warp_amount(&diagonal, 10, 10), 0f64);
+    let stretched = Array1::from_iter((0..10).map(|i| (2 * i, i)));
+    assert!(warp_amount(&stretched, 10, 19) < 1e-12);
+
+    // a path which first runs down the first column and then along the last row warps heavily
+    let border = Array1::from_iter((0..10).map(|i| (i, 0)).chain((1..10).map(|i| (9, i))));
+    let amount = warp_amount(&border, 10, 10);
+    assert!(amount > 0.4);
+    assert!(amount <= 1f64);
+
+    // a path with a small detour from the diagonal is in between
+    let detour = Array1::from_vec(vec![(0, 0), (1, 0), (2, 1), (3, 2), (3, 3)]);
+    let small_amount = warp_amount(&detour, 4, 4);
+    assert!(small_amount > 0f64);
+    assert!(small_amount < amount);
+}
+
+#[test]
+fn test_apply_warp() {
+    // x index 0 is aligned to y 0..=2, x index 1 to y 3, x indices 2 and 3 to y 4
+    let path = Array1::from_vec(vec![(0, 0), (1, 0), (2, 0), (3, 1), (4, 2), (4, 3)]);
+    let labels = Array1::from_vec(vec![7u8, 8u8, 8u8, 9u8, 5u8]);
+    assert_eq!(
+        apply_warp(&path, &labels.view(), 4),
+        Array1::from_vec(vec![7u8, 9u8, 5u8, 5u8])
+    );
+
+    // any payload type can be carried along
+    let names = Array1::from_vec(vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(
+        apply_warp(&path, &names.view(), 4),
+        Array1::from_vec(vec!["a", "d", "e", "e"])
+    );
+}
+
+#[test]
+fn test_warp() {
+    let x = Array1::from_vec(vec![0f64, 4f64, 6f64, 8f64]);
+    // y index 0 is aligned to x 0, y indices 1..=3 to x 1, y index 4 to x 2 and 3
+    let path = Array1::from_vec(vec![(0, 0), (1, 1), (2, 1), (3, 1), (4, 2), (4, 3)]);
+    assert_eq!(
+        warp(&x.view(), &path, 5, WarpInterpolation::Step).to_vec(),
+        vec![0f64, 4f64, 4f64, 4f64, 7f64]
+    );
+    // the stretched sample 4 is interpolated towards the next sample 6
+    let linear = warp(&x.view(), &path, 5, WarpInterpolation::Linear);
+    let expected = [0f64, 4f64, 4f64 + 2f64 / 3f64, 4f64 + 4f64 / 3f64, 7f64];
+    for (value, expected) in linear.iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-12);
+    }
+
+    // the last sample of x has no next sample and is repeated
+    let path = Array1::from_vec(vec![(0, 0), (1, 1), (2, 1)]);
+    let x = Array1::from_vec(vec![1u8, 2u8]);
+    assert_eq!(
+        warp(&x.view(), &path, 3, WarpInterpolation::Linear).to_vec(),
+        vec![1f64, 2f64, 2f64]
+    );
+}
+
+#[test]
+fn test_warp_aligns_to_y() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y_size = tc.time_series_b.len();
+        let warped = warp(&x, &tc.warp_path, y_size, WarpInterpolation::Step);
+        assert_eq!(warped.len(), y_size);
+        // every warped sample is an average of samples of x, within their range
+        let (minimum, maximum) = x.fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(minimum, maximum), &v| (minimum.min(v), maximum.max(v)),
+        );
+        assert!(warped
+            .iter()
+            .all(|&v| v >= minimum - 1e-9 && v <= maximum + 1e-9));
+    }
+}
+
+#[test]
+#[should_panic(expected = "the warp path does not cover all indices of y")]
+fn test_warp_incomplete_path() {
+    let x = Array1::from_vec(vec![1f64, 2f64]);
+    warp(
+        &x.view(),
+        &Array1::from_vec(vec![(0, 0), (2, 1)]),
+        3,
+        WarpInterpolation::Step,
+    );
+}
+
+#[test]
+fn test_index_mapping() {
+    // x index 0 is aligned to y 0..=2, x index 1 to y 3, x indices 2 and 3 to y 4
+    let path = Array1::from_vec(vec![(0, 0), (1, 0), (2, 0), (3, 1), (4, 2), (4, 3)]);
+    let (index_x, index_y) = index_arrays(&path);
+    assert_eq!(index_x.to_vec(), vec![0, 0, 0, 1, 2, 3]);
+    assert_eq!(index_y.to_vec(), vec![0, 1, 2, 3, 4, 4]);
+
+    let mapping = IndexMapping::new(&path, 4, 5);
+    assert_eq!(mapping.map_x_to_y(0), 0..3);
+    assert_eq!(mapping.map_x_to_y(1), 3..4);
+    assert_eq!(mapping.map_x_to_y(3), 4..5);
+    assert_eq!(mapping.map_y_to_x(2), 0..1);
+    assert_eq!(mapping.map_y_to_x(4), 2..4);
+
+    // the ranges cover the path, which is the union of the aligned pairs
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let (x_size, y_size) = (tc.time_series_a.len(), tc.time_series_b.len());
+        let mapping = IndexMapping::new(&tc.warp_path, x_size, y_size);
+        let pairs: usize = (0..x_size).map(|i| mapping.map_x_to_y(i).len()).sum();
+        assert_eq!(pairs, tc.warp_path.len());
+        for &(row, column) in tc.warp_path.iter() {
+            assert!(mapping.map_x_to_y(column).contains(&row));
+            assert!(mapping.map_y_to_x(row).contains(&column));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "the warp path does not cover all indices of x and y")]
+fn test_index_mapping_incomplete_path() {
+    IndexMapping::new(&Array1::from_vec(vec![(0, 0), (1, 2)]), 3, 2);
+}
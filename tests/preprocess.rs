@@ -0,0 +1,240 @@
+use dtw::{
+    derivative_estimate, downsample, dtw_augmented, dtw_rank, dtw_smoothed, gaussian_smooth,
+    min_max_scale, paa, preprocess, rank_transform, rolling_znormalize, znormalize, DistanceMode,
+    Pooling, Preprocessing,
+};
+use ndarray::Array1;
+
+/// A sine wave with deterministic pseudo random noise in [-amplitude, amplitude].
+fn noisy_sine(size: usize, seed: u64, amplitude: f64) -> Array1<f64> {
+    let mut state = seed;
+    Array1::from_iter((0..size).map(|i| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let noise = ((state >> 33) as f64 / (1u64 << 31) as f64) * 2f64 - 1f64;
+        (0.2 * i as f64).sin() + amplitude * noise
+    }))
+}
+
+#[test]
+fn test_gaussian_smooth() {
+    // a constant time series stays constant, also at the boundaries
+    let constant = Array1::from_elem(10, 3f64);
+    let smoothed = gaussian_smooth(&constant.view(), 1.5);
+    assert!(smoothed.iter().all(|&v| (v - 3f64).abs() < 1e-12));
+
+    // an impulse is spread symmetrically and keeps its mass away from the boundaries
+    let mut impulse = Array1::<f64>::zeros(21);
+    impulse[10] = 1f64;
+    let smoothed = gaussian_smooth(&impulse.view(), 1f64);
+    assert!((smoothed.sum() - 1f64).abs() < 1e-12);
+    for i in 1..4 {
+        assert!((smoothed[10 - i] - smoothed[10 + i]).abs() < 1e-12);
+        assert!(smoothed[10 + i] < smoothed[10 + i - 1]);
+    }
+
+    // a sigma of zero leaves the time series unchanged
+    assert_eq!(gaussian_smooth(&impulse.view(), 0f64), impulse);
+}
+
+#[test]
+fn test_dtw_smoothed() {
+    // for noisy versions of the same signal, smoothing removes part of the distance caused by the noise
+    for seed in 0..10 {
+        let x = noisy_sine(80, 2 * seed + 1, 0.3);
+        let y = noisy_sine(80, 2 * seed + 2, 0.3);
+        let (raw, _) = dtw_smoothed(&x.view(), &y.view(), 0f64, DistanceMode::Euclidean).unwrap();
+        let (smoothed, path) =
+            dtw_smoothed(&x.view(), &y.view(), 2f64, DistanceMode::Euclidean).unwrap();
+        assert!(smoothed < raw);
+        assert_eq!(path[path.len() - 1], (79, 79));
+    }
+}
+
+#[test]
+fn test_rank_transform() {
+    let ts = Array1::from_vec(vec![3f64, 1f64, 4f64, 1f64, 5f64, 9f64, 2f64, 6f64]);
+    assert_eq!(
+        rank_transform(&ts.view()),
+        Array1::from_vec(vec![4f64, 1.5, 5f64, 1.5, 6f64, 8f64, 3f64, 7f64])
+    );
+
+    let constant = Array1::from_elem(4, 7f64);
+    assert_eq!(rank_transform(&constant.view()), Array1::from_elem(4, 2.5));
+}
+
+#[test]
+fn test_dtw_rank() {
+    let x = noisy_sine(60, 1, 0.1);
+    let y = noisy_sine(60, 2, 0.1);
+    // a strictly increasing but strongly nonlinear distortion of the amplitude
+    let distorted_y = y.map(|v| (3f64 * v).exp());
+
+    let (raw_distance, _) = dtw::dtw(&x.view(), &y.view()).unwrap();
+    let (distorted_raw_distance, _) = dtw::dtw(&x.view(), &distorted_y.view()).unwrap();
+    assert!(distorted_raw_distance > 5f64 * raw_distance);
+
+    let (rank_distance, rank_path) =
+        dtw_rank(&x.view(), &y.view(), DistanceMode::Euclidean).unwrap();
+    let (distorted_rank_distance, distorted_rank_path) =
+        dtw_rank(&x.view(), &distorted_y.view(), DistanceMode::Euclidean).unwrap();
+    assert_eq!(distorted_rank_distance, rank_distance);
+    assert_eq!(distorted_rank_path, rank_path);
+}
+
+#[test]
+fn test_dtw_augmented() {
+    // a rising ramp, the same ramp delayed by a few samples, and a falling ramp over the same levels
+    let ramp = Array1::from_iter((0..20).map(|i| (i as f64 / 2f64).min(6f64)));
+    let shifted_ramp =
+        Array1::from_iter((0..20).map(|i| ((i as f64 - 4f64) / 2f64).clamp(0f64, 6f64)));
+    let falling = Array1::from_iter((0..20).map(|i| 6f64 - (i as f64 / 3f64).min(6f64)));
+
+    /* the delayed ramp has the same levels and slopes, so its distance stays zero, while the distance
+     * to the falling ramp grows with the weight of the slopes
+     */
+    let mut previous_distance = 0f64;
+    for delta_weight in [0f64, 1f64, 4f64, 16f64] {
+        let (same_slope, _) = dtw_augmented(
+            &ramp.view(),
+            &shifted_ramp.view(),
+            delta_weight,
+            DistanceMode::Manhattan,
+        )
+        .unwrap();
+        let (other_slope, _) = dtw_augmented(
+            &ramp.view(),
+            &falling.view(),
+            delta_weight,
+            DistanceMode::Manhattan,
+        )
+        .unwrap();
+        assert_eq!(same_slope, 0f64);
+        assert!(other_slope > previous_distance);
+        previous_distance = other_slope;
+    }
+
+    // without a weight on the slopes, this is the standard dtw
+    assert_eq!(
+        dtw_augmented(&ramp.view(), &falling.view(), 0f64, DistanceMode::Euclidean).unwrap(),
+        dtw::dtw(&ramp.view(), &falling.view()).unwrap()
+    );
+}
+
+#[test]
+fn test_derivative_estimate() {
+    let ts = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0]);
+    // ((3 - 1) + (4 - 1) / 2) / 2, ((4 - 3) + (9 - 3) / 2) / 2, ((9 - 4) + (8 - 4) / 2) / 2
+    let expected = Array1::from(vec![1.75, 1.75, 2.0, 3.5, 3.5]);
+    assert_eq!(derivative_estimate(&ts.view()), expected);
+
+    // the derivative of a line is its slope
+    let line = Array1::from_iter((0..10).map(|i| 2f64 * i as f64 - 3f64));
+    assert_eq!(
+        derivative_estimate(&line.view()),
+        Array1::from_elem(10, 2f64)
+    );
+
+    assert_eq!(
+        derivative_estimate(&Array1::from(vec![1.0, 4.0]).view()),
+        Array1::from(vec![3.0, 3.0])
+    );
+    assert_eq!(
+        derivative_estimate(&Array1::from(vec![5.0]).view()),
+        Array1::from(vec![0.0])
+    );
+    assert_eq!(
+        preprocess(&ts.view(), Preprocessing::Derivative),
+        derivative_estimate(&ts.view())
+    );
+    assert_eq!(preprocess(&ts.view(), Preprocessing::None), ts);
+}
+
+#[test]
+fn test_znormalize() {
+    let ts = Array1::from(vec![2f64, 4f64, 4f64, 4f64, 5f64, 5f64, 7f64, 9f64]);
+    // the mean is 5 and the standard deviation 2
+    assert_eq!(
+        znormalize(&ts.view()).to_vec(),
+        vec![-1.5, -0.5, -0.5, -0.5, 0f64, 0f64, 1f64, 2f64]
+    );
+    let normalized = znormalize(&noisy_sine(50, 3, 0.2).view());
+    assert!(normalized.mean().unwrap().abs() < 1e-12);
+    assert!((normalized.std(0f64) - 1f64).abs() < 1e-12);
+    assert_eq!(
+        preprocess(&ts.view(), Preprocessing::ZNormalize),
+        znormalize(&ts.view())
+    );
+
+    // constant and empty time series
+    assert_eq!(
+        znormalize(&Array1::from_elem(4, 3u8).view()),
+        Array1::<f64>::zeros(4)
+    );
+    assert_eq!(znormalize(&Array1::<f64>::zeros(0).view()).len(), 0);
+}
+
+#[test]
+fn test_min_max_scale() {
+    let ts = Array1::from(vec![-2i32, 0, 6, 2]);
+    assert_eq!(
+        min_max_scale(&ts.view()).to_vec(),
+        vec![0f64, 0.25, 1f64, 0.5]
+    );
+    assert_eq!(
+        min_max_scale(&Array1::from_elem(3, 7f64).view()),
+        Array1::<f64>::zeros(3)
+    );
+    assert_eq!(min_max_scale(&Array1::<f64>::zeros(0).view()).len(), 0);
+}
+
+#[test]
+fn test_rolling_znormalize() {
+    // a sine wave whose level and amplitude drift
+    let ts = Array1::from_iter(
+        (0..200).map(|i| (1f64 + i as f64 / 50f64) * f64::sin(i as f64 / 3f64) + i as f64 / 10f64),
+    );
+    let window = 25;
+    let normalized = rolling_znormalize(&ts.view(), window);
+    assert_eq!(normalized[0], 0f64);
+    for i in [30, 100, 199] {
+        let trailing = ts.slice(ndarray::s![i + 1 - window..=i]);
+        assert_eq!(normalized[i], znormalize(&trailing)[window - 1]);
+    }
+    // the drift is removed
+    assert!(normalized.iter().skip(window).all(|v| v.abs() < 3f64));
+
+    // a window as large as the time series normalizes its last sample as znormalize
+    let last = rolling_znormalize(&ts.view(), 500)[199];
+    assert!((last - znormalize(&ts.view())[199]).abs() < 1e-12);
+}
+
+#[test]
+fn test_downsample() {
+    let ts = Array1::from(vec![3i32, 1, 2, 8, 5, 4, 9, 7]);
+    assert_eq!(paa(&ts.view(), 3).to_vec(), vec![2f64, 17f64 / 3f64, 8f64]);
+    assert_eq!(paa(&ts.view(), 1), ts.mapv(f64::from));
+    assert_eq!(downsample(&ts.view(), 3, Pooling::Mean), paa(&ts.view(), 3));
+    assert_eq!(
+        downsample(&ts.view(), 3, Pooling::Max).to_vec(),
+        vec![3f64, 8f64, 9f64]
+    );
+    assert_eq!(
+        downsample(&ts.view(), 3, Pooling::Min).to_vec(),
+        vec![1f64, 4f64, 7f64]
+    );
+    // the last block has an even size
+    assert_eq!(
+        downsample(&ts.view(), 3, Pooling::Median).to_vec(),
+        vec![2f64, 5f64, 8f64]
+    );
+    assert_eq!(
+        downsample(&ts.view(), 4, Pooling::Median).to_vec(),
+        vec![2.5, 6f64]
+    );
+    assert_eq!(
+        downsample(&ts.view(), 10, Pooling::Max).to_vec(),
+        vec![9f64]
+    );
+}
@@ -0,0 +1,117 @@
+use dtw::{
+    config_max_cost_storage_matrix, dtw_ex, fastdtw_ex, DistanceMode, FullWindow, StorageStrategy,
+};
+use ndarray::Array1;
+
+/// Minimal linear congruential generator, so that the random inputs are reproducible.
+struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state >> 33
+    }
+
+    fn next_size(&mut self, max: usize) -> usize {
+        1 + (self.next_u64() as usize) % max
+    }
+
+    /// A random time series with values in [-range, range].
+    fn next_time_series(&mut self, size: usize, range: f64) -> Array1<f64> {
+        Array1::from_iter((0..size).map(|_| {
+            let unit = self.next_u64() as f64 / (1u64 << 31) as f64;
+            (unit * 2f64 - 1f64) * range
+        }))
+    }
+}
+
+fn assert_valid_path(path: &Array1<(usize, usize)>, rows: usize, columns: usize) {
+    assert_eq!(path[0], (0, 0));
+    assert_eq!(path[path.len() - 1], (rows - 1, columns - 1));
+    for i in 1..path.len() {
+        let (previous_row, previous_column) = path[i - 1];
+        let (row, column) = path[i];
+        assert!(row - previous_row <= 1 && column - previous_column <= 1);
+        assert!((row, column) != (previous_row, previous_column));
+    }
+}
+
+/// Check the DTW invariants on random inputs of varied lengths, for both distance modes.
+fn check_properties(seed: u64) -> Vec<(f64, Array1<(usize, usize)>)> {
+    let mut generator = Generator::new(seed);
+    let mut results = Vec::new();
+
+    for _ in 0..200 {
+        let x_size = generator.next_size(30);
+        let y_size = generator.next_size(30);
+        let x = generator.next_time_series(x_size, 100f64);
+        let y = generator.next_time_series(y_size, 100f64);
+        let rows = y.len();
+        let columns = x.len();
+
+        for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+            // the distance is non-negative and finite for finite inputs
+            let (distance, path) = dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(rows, columns),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(distance >= 0f64);
+            assert!(distance.is_finite());
+            assert_valid_path(&path, rows, columns);
+
+            // the distance of a time series to itself is zero, with a diagonal warp path
+            let (self_distance, self_path) = dtw_ex(
+                &x.view(),
+                &x.view(),
+                FullWindow::new(columns, columns),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert_eq!(self_distance, 0f64);
+            assert_eq!(self_path, Array1::from_iter((0..columns).map(|i| (i, i))));
+
+            // the constrained windows of fastdtw can only lead to a larger distance
+            for search_radius in [0, 1, 3] {
+                let (fast_distance, fast_path) =
+                    fastdtw_ex(&x.view(), &y.view(), 2, search_radius, distance_mode).unwrap();
+                assert!(fast_distance >= distance);
+                assert!(fast_distance.is_finite());
+                assert_valid_path(&fast_path, rows, columns);
+            }
+
+            results.push((distance, path));
+        }
+    }
+
+    results
+}
+
+#[test]
+fn test_properties() {
+    /* this is the only test in this binary, so the storage threshold can be changed without affecting
+     * other tests running in parallel
+     */
+    let matrix_results = check_properties(42);
+    config_max_cost_storage_matrix(0);
+    let cache_results = check_properties(42);
+    config_max_cost_storage_matrix(32 * 1024 * 1024 * 1024);
+
+    // both cost storage backends lead to the same results
+    assert_eq!(matrix_results, cache_results);
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "testdata")]
+
+use dtw::{
+    dtw_ex, dtw_pruned, load_dtw_cases, DistanceMode, DtwError, FullWindow, StorageStrategy,
+};
+use ndarray::Array1;
+use std::path::PathBuf;
+
+#[test]
+fn test_dtw_pruned() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    let mut cases: Vec<(Array1<f64>, Array1<f64>, DistanceMode)> = test_cases
+        .into_iter()
+        .map(|tc| {
+            let distance_mode = tc
+                .parsed_distance_mode()
+                .expect("unknown distance mode specified");
+            (tc.time_series_a, tc.time_series_b, distance_mode)
+        })
+        .collect();
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        // similar and dissimilar time series, with wide, tall and square cost matrices
+        for (columns, rows, shift) in [(60, 60, 2f64), (53, 31, 0.4), (17, 40, 5f64), (9, 1, 0f64)]
+        {
+            let x = Array1::from_iter((0..columns).map(|i| f64::sin(i as f64 / 5f64)));
+            let y = Array1::from_iter((0..rows).map(|i| f64::sin(i as f64 / 4f64 + shift)));
+            cases.push((x, y, distance_mode));
+        }
+        // identical time series, where the upper bound is the distance
+        let x = Array1::from_iter((0..30).map(|i| (i % 7) as f64));
+        cases.push((x.clone(), x, distance_mode));
+    }
+
+    for (x, y, distance_mode) in cases {
+        let (rows, columns) = (y.shape()[0], x.shape()[0]);
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        );
+        assert_eq!(dtw_pruned(&x.view(), &y.view(), distance_mode), expected);
+    }
+
+    // a sample which is not a number gives the distance of dtw_ex instead of a panic
+    let x = Array1::from(vec![1f64, f64::NAN, 3f64, 4f64]);
+    let y = Array1::from(vec![1f64, 2f64, 4f64]);
+    let (distance, path) = dtw_pruned(&x.view(), &y.view(), DistanceMode::Euclidean).unwrap();
+    let (expected_distance, expected_path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(3, 4),
+        DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    assert_eq!(distance.is_nan(), expected_distance.is_nan());
+    assert_eq!(path, expected_path);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        dtw_pruned(&empty.view(), &empty.view(), DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
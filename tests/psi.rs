@@ -0,0 +1,67 @@
+use dtw::{dtw_ex, dtw_psi, DistanceMode, DtwError, FullWindow, StorageStrategy};
+use ndarray::{s, Array1};
+
+#[test]
+fn test_dtw_psi() {
+    let x = Array1::from_iter((0..20).map(|i| f64::sin(i as f64 / 3f64)));
+    let y = Array1::from_iter((0..16).map(|i| f64::sin(i as f64 / 2.5 + 1.3)));
+    let (rows, columns) = (y.len(), x.len());
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        // without relaxation, psi DTW is the standard DTW
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert_eq!(
+            dtw_psi(&x.view(), &y.view(), 0, 0, distance_mode).unwrap(),
+            expected
+        );
+
+        for (psi_begin, psi_end) in [(3, 0), (0, 4), (2, 5), (7, 7)] {
+            /* the distance is the minimum of the DTW over the time series trimmed at one end, by at
+             * most psi samples of either of them
+             */
+            let trims = |psi: usize| (0..=psi).flat_map(move |trim| [(trim, 0), (0, trim)]);
+            let mut minimum = f64::INFINITY;
+            for (x_begin, y_begin) in trims(psi_begin) {
+                for (x_end, y_end) in trims(psi_end) {
+                    let x = x.slice(s![x_begin..columns - x_end]);
+                    let y = y.slice(s![y_begin..rows - y_end]);
+                    let (distance, _) = dtw_ex(
+                        &x,
+                        &y,
+                        FullWindow::new(y.len(), x.len()),
+                        distance_mode,
+                        StorageStrategy::Auto,
+                        f64::INFINITY,
+                    )
+                    .unwrap();
+                    minimum = minimum.min(distance);
+                }
+            }
+
+            let (distance, path) =
+                dtw_psi(&x.view(), &y.view(), psi_begin, psi_end, distance_mode).unwrap();
+            assert!((distance - minimum).abs() < 1e-12);
+            assert!(distance <= expected.0);
+
+            let (first, last) = (path[0], path[path.len() - 1]);
+            assert!(first.0 == 0 || first.1 == 0);
+            assert!(first.0 <= psi_begin && first.1 <= psi_begin);
+            assert!(last.0 == rows - 1 || last.1 == columns - 1);
+            assert!(last.0 + psi_end >= rows - 1 && last.1 + psi_end >= columns - 1);
+        }
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        dtw_psi(&empty.view(), &y.view(), 2, 2, DistanceMode::Euclidean),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
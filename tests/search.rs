@@ -0,0 +1,75 @@
+use dtw::{
+    dtw_ex, ucr_search, znormalize, DistanceMode, DtwError, SakoeChibaWindow, StorageStrategy,
+};
+use ndarray::{s, Array1};
+
+#[test]
+fn test_ucr_search() {
+    let series = Array1::from_iter((0..400).map(|i| {
+        f64::sin(i as f64 / 7f64) * (1f64 + (i % 23) as f64 / 10f64) + (i % 5) as f64 / 3f64
+    }));
+    let query = Array1::from_iter((0..32).map(|i| f64::sin(i as f64 / 6f64) * 3f64 + 10f64));
+    let normalized_query = znormalize(&query.view());
+
+    for distance_mode in [
+        DistanceMode::Manhattan,
+        DistanceMode::Euclidean,
+        DistanceMode::SquaredEuclidean,
+        DistanceMode::Chebyshev,
+        DistanceMode::Canberra,
+        DistanceMode::Minkowski(3f64),
+    ] {
+        for band in [0, 3, 8] {
+            let mut expected = (0, f64::INFINITY);
+            for location in 0..=series.len() - query.len() {
+                let candidate = znormalize(&series.slice(s![location..location + query.len()]));
+                let (distance, _) = dtw_ex(
+                    &candidate.view(),
+                    &normalized_query.view(),
+                    SakoeChibaWindow::new(query.len(), query.len(), band),
+                    distance_mode,
+                    StorageStrategy::Auto,
+                    f64::INFINITY,
+                )
+                .unwrap();
+                if distance < expected.1 {
+                    expected = (location, distance);
+                }
+            }
+
+            let result = ucr_search(&query.view(), &series.view(), band, distance_mode).unwrap();
+            assert_eq!(result.location, expected.0);
+            assert!((result.distance - expected.1).abs() < 1e-9);
+
+            let statistics = result.statistics;
+            assert_eq!(statistics.candidates, series.len() - query.len() + 1);
+            assert_eq!(
+                statistics.lb_kim
+                    + statistics.lb_keogh
+                    + statistics.lb_keogh_reversed
+                    + statistics.abandoned
+                    + statistics.computed,
+                statistics.candidates
+            );
+            assert!(statistics.computed < statistics.candidates);
+        }
+    }
+}
+
+#[test]
+fn test_ucr_search_errors() {
+    let series = Array1::from(vec![1.0, 2.0, 3.0]);
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        ucr_search(&empty.view(), &series.view(), 1, DistanceMode::Euclidean),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+#[should_panic(expected = "the query must not be longer than the series")]
+fn test_ucr_search_long_query() {
+    let series = Array1::from(vec![1.0, 2.0, 3.0]);
+    let long = Array1::from(vec![1.0, 2.0, 3.0, 4.0]);
+    let _ = ucr_search(&long.view(), &series.view(), 1, DistanceMode::Euclidean);
+}
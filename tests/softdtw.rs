@@ -0,0 +1,113 @@
+use dtw::{
+    dtw_ex, softdtw_ex, softdtw_gradient, DistanceMode, DtwError, FullWindow, StorageStrategy,
+};
+use ndarray::Array1;
+
+#[test]
+fn test_softdtw() {
+    // all three predecessors of the last cell have a cost of 0
+    let zeros = Array1::from(vec![0.0, 0.0]);
+    let value = softdtw_ex(&zeros.view(), &zeros.view(), 1f64).unwrap();
+    assert!((value + 3f64.ln()).abs() < 1e-12);
+
+    let x = Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 4f64)));
+    let y = Array1::from_iter((0..25).map(|i| f64::cos(i as f64 / 3f64)));
+    let (distance, _) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(y.len(), x.len()),
+        DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let hard = distance * distance;
+    assert!((softdtw_ex(&x.view(), &y.view(), 0f64).unwrap() - hard).abs() < 1e-9);
+
+    // the soft minimum decreases with gamma, and tends to the minimum
+    let mut previous = hard;
+    for gamma in [0.001, 0.01, 0.1, 1f64, 10f64] {
+        let value = softdtw_ex(&x.view(), &y.view(), gamma).unwrap();
+        assert!(value < previous);
+        previous = value;
+    }
+    assert!((softdtw_ex(&x.view(), &y.view(), 1e-6).unwrap() - hard).abs() < 1e-3);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        softdtw_ex(&empty.view(), &y.view(), 1f64),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_softdtw_negative_gamma() {
+    let x = Array1::from(vec![1.0, 2.0]);
+    let _ = softdtw_ex(&x.view(), &x.view(), -1f64);
+}
+
+#[test]
+fn test_softdtw_gradient() {
+    let x = Array1::from_iter((0..12).map(|i| f64::sin(i as f64 / 2f64)));
+    let y = Array1::from_iter((0..9).map(|i| f64::cos(i as f64 / 2f64) * 0.8));
+    let gamma = 0.5;
+    let result = softdtw_gradient(&x.view(), &y.view(), gamma).unwrap();
+    assert_eq!(
+        result.value,
+        softdtw_ex(&x.view(), &y.view(), gamma).unwrap()
+    );
+    assert_eq!(result.expected_alignment.shape(), &[y.len(), x.len()]);
+
+    // every warp path goes through the first and the last cell
+    assert!((result.expected_alignment[(0, 0)] - 1f64).abs() < 1e-9);
+    assert!((result.expected_alignment[(y.len() - 1, x.len() - 1)] - 1f64).abs() < 1e-9);
+    assert!(result
+        .expected_alignment
+        .iter()
+        .all(|&p| (-1e-12..=1f64 + 1e-12).contains(&p)));
+
+    // compare to central finite differences
+    let epsilon = 1e-6;
+    for (ts, gradient, is_x) in [
+        (&x, &result.gradient_x, true),
+        (&y, &result.gradient_y, false),
+    ] {
+        for i in 0..ts.len() {
+            let (mut plus, mut minus) = (ts.clone(), ts.clone());
+            plus[i] += epsilon;
+            minus[i] -= epsilon;
+            let (plus, minus) = match is_x {
+                true => (
+                    softdtw_ex(&plus.view(), &y.view(), gamma).unwrap(),
+                    softdtw_ex(&minus.view(), &y.view(), gamma).unwrap(),
+                ),
+                false => (
+                    softdtw_ex(&x.view(), &plus.view(), gamma).unwrap(),
+                    softdtw_ex(&x.view(), &minus.view(), gamma).unwrap(),
+                ),
+            };
+            let numerical = (plus - minus) / (2f64 * epsilon);
+            assert!((gradient[i] - numerical).abs() < 1e-5);
+        }
+    }
+
+    // with a small gamma, the expected alignment is the optimal warp path
+    let (_, path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(y.len(), x.len()),
+        DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let result = softdtw_gradient(&x.view(), &y.view(), 1e-5).unwrap();
+    for ((row, column), &probability) in result.expected_alignment.indexed_iter() {
+        let expected = match path.iter().any(|&cell| cell == (row, column)) {
+            true => 1f64,
+            false => 0f64,
+        };
+        assert!((probability - expected).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,187 @@
+use dtw::{
+    dtw_ex, subsequence_dtw, DistanceMode, DtwError, FullWindow, Match, Spring, StorageStrategy,
+    StreamingMatcher,
+};
+use ndarray::{s, Array1};
+
+#[test]
+fn test_subsequence_dtw() {
+    let series = Array1::from_iter((0..80).map(|i| f64::sin(i as f64 / 5f64) * (i % 7) as f64));
+    // a stretched excerpt of the series
+    let query = Array1::from_iter((0..15).map(|i| series[30 + i * 2 / 3] + 0.1));
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let result = subsequence_dtw(&query.view(), &series.view(), 0, distance_mode).unwrap();
+        let interval = result.interval.clone();
+        assert_eq!(result.path[0], (0, interval.start));
+        assert_eq!(
+            result.path[result.path.len() - 1],
+            (query.len() - 1, interval.end - 1)
+        );
+
+        // the match is the region of the series with the smallest DTW distance to the query
+        let mut minimum = f64::INFINITY;
+        for start in 0..series.len() {
+            for end in start + 1..=series.len() {
+                let region = series.slice(s![start..end]);
+                let (distance, _) = dtw_ex(
+                    &region,
+                    &query.view(),
+                    FullWindow::new(query.len(), region.len()),
+                    distance_mode,
+                    StorageStrategy::Auto,
+                    f64::INFINITY,
+                )
+                .unwrap();
+                minimum = minimum.min(distance);
+            }
+        }
+        assert!((result.distance - minimum).abs() < 1e-12);
+
+        let region = series.slice(s![interval]);
+        let (distance, path) = dtw_ex(
+            &region,
+            &query.view(),
+            FullWindow::new(query.len(), region.len()),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert!((result.distance - distance).abs() < 1e-12);
+        assert_eq!(
+            result.path,
+            path.map(|&(row, column)| (row, column + result.interval.start))
+        );
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        subsequence_dtw(&empty.view(), &series.view(), 0, DistanceMode::Euclidean),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_subsequence_dtw_min_path_length() {
+    let query = Array1::from(vec![0.0, 2.0, 4.0, 4.0, 2.0, 0.0]);
+    // far from the query, except for a short spike and a stretched copy of the query
+    let mut series = vec![10f64; 80];
+    series[10..13].copy_from_slice(&[0.0, 4.0, 0.0]);
+    for i in 0..18 {
+        series[40 + i] = 4f64 - f64::abs(i as f64 - 8.5) * 4f64 / 8.5;
+    }
+    let series = Array1::from(series);
+
+    // the whole query is squeezed onto the spike
+    let spurious =
+        subsequence_dtw(&query.view(), &series.view(), 0, DistanceMode::Manhattan).unwrap();
+    assert_eq!(spurious.interval, 10..13);
+    assert_eq!(spurious.path.len(), query.len());
+
+    // which is rejected once the warp path has to be longer than the query
+    let genuine =
+        subsequence_dtw(&query.view(), &series.view(), 10, DistanceMode::Manhattan).unwrap();
+    assert!(genuine.interval.start >= 40 && genuine.interval.end <= 58);
+    assert!(genuine.path.len() >= 10);
+    assert!(genuine.distance > spurious.distance);
+
+    // no warp path is longer than the query and the series together
+    let none =
+        subsequence_dtw(&query.view(), &series.view(), 100, DistanceMode::Manhattan).unwrap();
+    assert_eq!(none.distance, f64::INFINITY);
+    assert!(none.path.is_empty() && none.interval.is_empty());
+}
+
+#[test]
+fn test_spring() {
+    let query = Array1::from_iter(
+        (0..20).map(|i| 3f64 * f64::sin(i as f64 * std::f64::consts::PI / 19f64)),
+    );
+    // a flat stream with small oscillations, with a copy of the query and a stretched copy
+    let mut stream: Vec<f64> = (0..200).map(|i| 0.05 * f64::sin(i as f64)).collect();
+    for i in 0..20 {
+        stream[40 + i] += query[i];
+    }
+    for i in 0..30 {
+        stream[120 + i] += query[i * 2 / 3];
+    }
+    let stream = Array1::from(stream);
+
+    for (distance_mode, threshold) in [
+        (DistanceMode::Manhattan, 3f64),
+        (DistanceMode::Euclidean, 1f64),
+    ] {
+        let mut spring = Spring::new(&query.view(), threshold, distance_mode);
+        let mut matches = Vec::new();
+        for (time, &sample) in stream.iter().enumerate() {
+            if let Some(found) = spring.push(sample) {
+                // a match is reported after its end
+                assert!(found.interval.end <= time);
+                matches.push(found);
+            }
+        }
+        matches.extend(spring.finish());
+
+        assert_eq!(matches.len(), 2);
+        assert!(
+            matches[0].interval.start.abs_diff(40) <= 2
+                && matches[0].interval.end.abs_diff(60) <= 2
+        );
+        assert!(
+            matches[1].interval.start.abs_diff(120) <= 2
+                && matches[1].interval.end.abs_diff(150) <= 2
+        );
+        for found in matches {
+            let region = stream.slice(s![found.interval.clone()]);
+            let (distance, _) = dtw_ex(
+                &region,
+                &query.view(),
+                FullWindow::new(query.len(), region.len()),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(found.distance <= threshold);
+            assert!((found.distance - distance).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_streaming_matcher() {
+    let template = Array1::from_iter(
+        (0..20).map(|i| 3f64 * f64::sin(i as f64 * std::f64::consts::PI / 19f64)),
+    );
+    // a flat stream with small oscillations and two well separated copies of the template
+    let mut stream: Vec<f64> = (0..300).map(|i| 0.05 * f64::sin(i as f64)).collect();
+    for i in 0..20 {
+        stream[50 + i] += template[i];
+        stream[200 + i] += template[i];
+    }
+
+    let run = |cooldown| {
+        let mut matcher =
+            StreamingMatcher::new(&template.view(), 1f64, cooldown, DistanceMode::Euclidean);
+        let mut matches: Vec<Match> = stream
+            .iter()
+            .filter_map(|&sample| matcher.push(sample))
+            .collect();
+        matches.extend(matcher.finish());
+        matches
+    };
+
+    let matches = run(10);
+    assert_eq!(matches.len(), 2);
+    for (found, start) in matches.iter().zip([50, 200]) {
+        assert!(found.range.start.abs_diff(start) <= 2);
+        assert!(found.range.end.abs_diff(start + 20) <= 2);
+        assert!(found.distance <= 1f64);
+    }
+
+    // the second occurrence ends within a cooldown of 200 samples of the first one
+    let matches = run(200);
+    assert_eq!(matches.len(), 1);
+    assert!(matches[0].range.start.abs_diff(50) <= 2);
+}
@@ -1,52 +1,41 @@
-use dtw::{dtw_ex, fastdtw_ex, ConstrainedWindow, DistanceMode, FullWindow};
-use ndarray::Array1;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-
-#[derive(Serialize, Deserialize)]
-struct DtwTestCase {
-    name: String,
-    time_series_a: Array1<f64>,
-    time_series_b: Array1<f64>,
-    distance: f64,
-    distance_mode: String,
-    warp_path: Array1<(usize, usize)>,
-}
+#![cfg(feature = "testdata")]
 
-#[derive(Serialize, Deserialize)]
-struct ProjectionTestCase {
-    name: String,
-    low_res_path: Array1<(usize, usize)>,
-    resolution_factor: usize,
-    search_radius: usize,
-    high_res_rows: usize,
-    high_res_columns: usize,
-    projected_window: Array1<(usize, usize)>,
-}
+use dtw::{
+    backtrack, dtw_best_orientation, dtw_budgeted, dtw_by, dtw_ex, dtw_ex_in, dtw_ex_with_matrix,
+    dtw_fractional, dtw_frames, dtw_from_distance_grid, dtw_prefix_distances,
+    dtw_slope_constrained, dtw_symmetric2_normalized, dtw_timestamped, dtw_tradeoff_report,
+    dtw_with_cost, dump_matrices, estimate_memory, fastdtw_adaptive, fastdtw_budgeted, fastdtw_ex,
+    fastdtw_ex_in, fastdtw_iterative, fastdtw_pyramid, fastdtw_scheduled, fastdtw_weighted,
+    load_dtw_cases, load_matrices, load_projection_cases, save_dtw_cases, Action, BlockDtw,
+    BoundaryPolicy, ConstrainedWindow, CostBand, CostCache, CostMatrix, CostStorage, DistanceMode,
+    DtwError, DtwTestCase, FullWindow, ItakuraWindow, Orientation, SakoeChibaWindow,
+    StorageBackend, StorageStrategy,
+};
+use ndarray::{s, Array1, Array2};
+use std::path::PathBuf;
 
 #[test]
 fn test_dtw() {
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push("tests/dtw.yaml");
-    let f = std::fs::File::open(d).expect("could not open dtw.yaml");
-    let test_cases: Vec<DtwTestCase> =
-        serde_yaml::from_reader(f).expect("could not read test values from dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
 
     for tc in test_cases {
         let rows = tc.time_series_b.shape()[0];
         let columns = tc.time_series_a.shape()[0];
-        let distance_mode = match tc.distance_mode.as_str() {
-            "manhattan" => DistanceMode::Manhattan,
-            "euclidean" => DistanceMode::Euclidean,
-            _ => panic!("unknown distance mode specified"),
-        };
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
 
         let (distance, path) = dtw_ex(
             &tc.time_series_a.view(),
             &tc.time_series_b.view(),
             FullWindow::new(rows, columns),
             distance_mode,
-        );
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
 
         assert_eq!(distance, tc.distance);
         assert_eq!(path, tc.warp_path);
@@ -57,15 +46,17 @@ fn test_dtw() {
 fn test_window_projection() {
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push("tests/projection.yaml");
-    let f = std::fs::File::open(d).expect("could not open projection.yaml");
-    let test_cases: Vec<ProjectionTestCase> =
-        serde_yaml::from_reader(f).expect("could not read test values from projection.yaml");
+    let test_cases =
+        load_projection_cases(d).expect("could not read test values from projection.yaml");
 
     for tc in test_cases {
-        let mut window = ConstrainedWindow::from_low_res_path(
+        let mut window = ConstrainedWindow::from_low_res_path_anisotropic(
             tc.low_res_path,
             tc.resolution_factor,
-            tc.search_radius,
+            (
+                tc.search_radius,
+                tc.column_search_radius.unwrap_or(tc.search_radius),
+            ),
             tc.high_res_rows,
             tc.high_res_columns,
         );
@@ -76,20 +67,38 @@ fn test_window_projection() {
     }
 }
 
+/// Compute the distance along a warp path, to check that a path is optimal for a given distance.
+fn path_distance(
+    x: &Array1<f64>,
+    y: &Array1<f64>,
+    path: &Array1<(usize, usize)>,
+    distance_mode: DistanceMode,
+) -> f64 {
+    let cost: f64 = path
+        .iter()
+        .map(|&(row, column)| match distance_mode {
+            DistanceMode::Manhattan => f64::abs(x[column] - y[row]),
+            DistanceMode::Euclidean => (x[column] - y[row]).powi(2),
+            _ => unreachable!(),
+        })
+        .sum();
+    match distance_mode {
+        DistanceMode::Manhattan => cost,
+        DistanceMode::Euclidean => cost.sqrt(),
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn test_fast_dtw() {
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push("tests/dtw.yaml");
-    let f = std::fs::File::open(d).expect("could not open dtw.yaml");
-    let test_cases: Vec<DtwTestCase> =
-        serde_yaml::from_reader(f).expect("could not read test values from dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
 
     for tc in test_cases {
-        let distance_mode = match tc.distance_mode.as_str() {
-            "manhattan" => DistanceMode::Manhattan,
-            "euclidean" => DistanceMode::Euclidean,
-            _ => panic!("unknown distance mode specified"),
-        };
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
 
         let (distance, path) = fastdtw_ex(
             &tc.time_series_a.view(),
@@ -97,9 +106,1630 @@ fn test_fast_dtw() {
             2,
             10,
             distance_mode,
-        );
+        )
+        .unwrap();
 
+        /* only the distance is guaranteed to be equal to the one of the exact dtw, the warp path may be
+         * any of the warp paths with this distance
+         */
         assert_eq!(distance, tc.distance);
-        assert_eq!(path, tc.warp_path);
+        let expected_distance =
+            path_distance(&tc.time_series_a, &tc.time_series_b, &path, distance_mode);
+        assert!((distance - expected_distance).abs() <= 1e-9 * distance.max(1f64));
     }
 }
+
+#[test]
+fn test_fast_dtw_ties() {
+    let x = Array1::from_vec(vec![1f64, 1f64, 1f64, 0f64, 0f64, 0f64, 1f64, 2f64]);
+    let y = Array1::from_vec(vec![1f64, 0f64, 2f64, 0f64, 0f64, 1f64, 1f64, 0f64]);
+
+    /* the constrained window excludes cells which the exact dtw prefers on ties, so fastdtw finds the
+     * same distance along another optimal warp path
+     */
+    let (exact_distance, exact_path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(8, 8),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let (distance, path) = fastdtw_ex(&x.view(), &y.view(), 2, 1, DistanceMode::Manhattan).unwrap();
+    assert_eq!(distance, exact_distance);
+    assert_ne!(path, exact_path);
+    assert_eq!(
+        path_distance(&x, &y, &path, DistanceMode::Manhattan),
+        distance
+    );
+    assert_eq!(
+        path_distance(&x, &y, &exact_path, DistanceMode::Manhattan),
+        distance
+    );
+}
+
+#[test]
+fn test_estimate_memory() {
+    let estimate = estimate_memory(300, 200);
+    assert_eq!(
+        estimate.dense_bytes,
+        200 * 300 * std::mem::size_of::<f64>() * 2
+    );
+    assert_eq!(estimate.backend, StorageBackend::Matrix);
+
+    // 100k x 100k cells of f64 exceed the default threshold of 32 GiB
+    let estimate = estimate_memory(100_000, 100_000);
+    assert_eq!(
+        estimate.dense_bytes,
+        100_000 * 100_000 * std::mem::size_of::<f64>() * 2
+    );
+    assert_eq!(estimate.backend, StorageBackend::Cache);
+}
+
+#[test]
+fn test_block_dtw() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+
+        // blocks of different sizes for x and y, including empty blocks once one series runs out
+        let mut block_dtw = BlockDtw::new(DistanceMode::Euclidean);
+        let (mut x_pos, mut y_pos) = (0, 0);
+        while x_pos < x.len() || y_pos < y.len() {
+            let x_end = usize::min(x_pos + 3, x.len());
+            let y_end = usize::min(y_pos + 5, y.len());
+            block_dtw.push_block(&x.slice(s![x_pos..x_end]), &y.slice(s![y_pos..y_end]));
+            (x_pos, y_pos) = (x_end, y_end);
+        }
+
+        let (distance, path) = block_dtw.finish();
+        let (expected_distance, expected_path) = dtw::dtw(&x, &y).unwrap();
+        assert_eq!(distance, expected_distance);
+        assert_eq!(path, expected_path);
+    }
+}
+
+#[test]
+fn test_dtw_fractional() {
+    // y lags behind x by 0.4 samples, i.e. y[i] is aligned with x at the fractional index i + 0.4
+    let lag = 0.4;
+    let x = Array1::from_iter((0..60).map(|i| (0.25 * i as f64).sin()));
+    let y = Array1::from_iter((0..60).map(|i| (0.25 * (i as f64 + lag)).sin()));
+
+    let (distance, path) = dtw::dtw(&x.view(), &y.view()).unwrap();
+    let (fractional_distance, fractional_path) =
+        dtw_fractional(&x.view(), &y.view(), DistanceMode::Euclidean).unwrap();
+    assert_eq!(distance, fractional_distance);
+    assert_eq!(path.shape(), fractional_path.shape());
+
+    let mut integer_error = 0f64;
+    let mut fractional_error = 0f64;
+    for (&(row, column), &(fractional_row, fractional_column)) in path.iter().zip(&fractional_path)
+    {
+        assert!((fractional_row - row as f64).abs() < 1f64);
+        assert!((fractional_column - column as f64).abs() < 1f64);
+        integer_error += (column as f64 - row as f64 - lag).abs();
+        fractional_error += (fractional_column - fractional_row - lag).abs();
+    }
+    assert!(fractional_error < integer_error / 2f64);
+}
+
+#[test]
+fn test_dtw_prefix_distances() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let distances = dtw_prefix_distances(&x, &y, DistanceMode::Euclidean).unwrap();
+        assert_eq!(distances.shape()[0], y.shape()[0]);
+
+        let last = y.shape()[0] - 1;
+        assert_eq!(distances[last], dtw::dtw(&x, &y).unwrap().0);
+        let middle = y.shape()[0] / 2;
+        assert_eq!(
+            distances[middle],
+            dtw::dtw(&x, &y.slice(s![..middle + 1])).unwrap().0
+        );
+    }
+}
+
+#[test]
+fn test_fast_dtw_iterative() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        for search_radius in [1, 2, 10] {
+            let x = tc.time_series_a.view();
+            let y = tc.time_series_b.view();
+            let (distance, path) =
+                fastdtw_iterative(&x, &y, 2, search_radius, DistanceMode::Euclidean).unwrap();
+            let (expected_distance, expected_path) =
+                fastdtw_ex(&x, &y, 2, search_radius, DistanceMode::Euclidean).unwrap();
+            assert_eq!(distance, expected_distance);
+            assert_eq!(path, expected_path);
+        }
+    }
+
+    // a long time series with many resolution levels
+    let x = Array1::from_iter((0..3000).map(|i| (0.01 * i as f64).sin()));
+    let y = Array1::from_iter((0..2500).map(|i| (0.012 * i as f64).sin()));
+    let (distance, path) =
+        fastdtw_iterative(&x.view(), &y.view(), 2, 1, DistanceMode::Manhattan).unwrap();
+    let (expected_distance, expected_path) =
+        fastdtw_ex(&x.view(), &y.view(), 2, 1, DistanceMode::Manhattan).unwrap();
+    assert_eq!(distance, expected_distance);
+    assert_eq!(path, expected_path);
+}
+
+#[test]
+#[should_panic(expected = "the resolution factor must be at least 2")]
+fn test_fast_dtw_iterative_resolution_factor() {
+    let x = Array1::from_iter((0..10).map(|i| i as f64));
+    let _ = fastdtw_iterative(&x.view(), &x.view(), 1, 1, DistanceMode::Euclidean);
+}
+
+#[test]
+fn test_fast_dtw_pyramid() {
+    let x = Array1::from_iter((0..100).map(|i| i as f64));
+    let y = Array1::from_iter((0..70).map(|i| i as f64));
+
+    let pyramid = fastdtw_pyramid(&x.view(), &y.view(), 2);
+    // 70 -> 35 -> 18 -> 9 -> 5 -> 3, where 3 is the base case for a search radius of 1
+    assert_eq!(pyramid.len(), 6);
+    assert_eq!(pyramid[0].0, x);
+    assert_eq!(pyramid[0].1, y);
+    for level in 1..pyramid.len() {
+        let (previous_x, previous_y) = &pyramid[level - 1];
+        let (coarse_x, coarse_y) = &pyramid[level];
+        assert_eq!(coarse_x.len(), previous_x.len().div_ceil(2));
+        assert_eq!(coarse_y.len(), previous_y.len().div_ceil(2));
+    }
+    let (base_x, base_y) = pyramid.last().unwrap();
+    assert!(base_x.len() <= 3 || base_y.len() <= 3);
+    assert_eq!(base_x[0], 15.5);
+
+    // time series which are already small enough only have the full resolution level
+    let small = Array1::from(vec![1f64, 2f64, 3f64]);
+    assert_eq!(fastdtw_pyramid(&x.view(), &small.view(), 2).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "the resolution factor must be at least 2")]
+fn test_fast_dtw_pyramid_resolution_factor() {
+    let x = Array1::from_iter((0..10).map(|i| i as f64));
+    fastdtw_pyramid(&x.view(), &x.view(), 1);
+}
+
+#[test]
+fn test_warp_path_endpoints() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let last = (y.shape()[0] - 1, x.shape()[0] - 1);
+
+        let (_, path) = dtw::dtw(&x, &y).unwrap();
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[path.len() - 1], last);
+
+        for search_radius in [0, 1, 2] {
+            let (_, path) = fastdtw_ex(&x, &y, 2, search_radius, DistanceMode::Euclidean).unwrap();
+            assert_eq!(path[0], (0, 0));
+            assert_eq!(path[path.len() - 1], last);
+        }
+    }
+
+    // a window which does not connect the last cell to the origin has no warp path
+    let x = Array1::from_vec(vec![1f64, 2f64, 3f64]);
+    let y = Array1::from_vec(vec![1f64, 3f64]);
+    let window = vec![(1, 1), (2, 3)].into_iter();
+    let (distance, path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        window,
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    assert_eq!(distance, f64::INFINITY);
+    assert_eq!(path.len(), 0);
+}
+
+#[test]
+fn test_backtrack_uncomputed_cell() {
+    let mut cost_matrix = CostMatrix::<f64>::new(2, 3);
+    assert_eq!(cost_matrix.get_action(2, 3), None);
+    assert_eq!(
+        backtrack(&cost_matrix, 2, 3),
+        Err(DtwError::UncomputedCell { row: 2, column: 3 })
+    );
+
+    cost_matrix.set_action(2, 3, Action::Deleted);
+    cost_matrix.set_action(2, 2, Action::Matched);
+    assert_eq!(
+        backtrack(&cost_matrix, 2, 3),
+        Err(DtwError::UncomputedCell { row: 1, column: 1 })
+    );
+
+    cost_matrix.set_action(1, 1, Action::Matched);
+    assert_eq!(
+        backtrack(&cost_matrix, 2, 3),
+        Ok(Array1::from_vec(vec![(0, 0), (1, 1), (1, 2)]))
+    );
+}
+
+#[test]
+fn test_fast_dtw_weighted() {
+    /* with a resolution factor of 3, the last coarse sample of x summarizes a single sample and the
+     * last coarse sample of y summarizes a single sample as well, while all other coarse samples
+     * summarize 3 samples
+     */
+    let x = Array1::from_vec(vec![1f64, 4f64, 4f64, 3f64, 4f64, 0f64, 3f64]);
+    let y = Array1::from_vec(vec![4f64, 1f64, 0f64, 3f64]);
+
+    let (exact_distance, exact_path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 7),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let (distance, _) = fastdtw_ex(&x.view(), &y.view(), 3, 0, DistanceMode::Manhattan).unwrap();
+    let (weighted_distance, weighted_path) =
+        fastdtw_weighted(&x.view(), &y.view(), 3, 0, DistanceMode::Manhattan).unwrap();
+    assert_eq!(exact_distance, 5f64);
+    assert_eq!(distance, 10f64);
+    assert_eq!(weighted_distance, exact_distance);
+    assert_eq!(weighted_path, exact_path);
+
+    // the weighted variant never finds a shorter distance than the exact DTW
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let (distance, _) = fastdtw_weighted(
+            &tc.time_series_a.view(),
+            &tc.time_series_b.view(),
+            3,
+            1,
+            tc.parsed_distance_mode().unwrap(),
+        )
+        .unwrap();
+        assert!(distance >= tc.distance);
+    }
+}
+
+#[test]
+fn test_dtw_slope_constrained() {
+    // without an effective constraint, the distance is the one of the unconstrained dtw
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let rows = tc.time_series_b.shape()[0];
+        let columns = tc.time_series_a.shape()[0];
+        if rows > 50 || columns > 50 {
+            // keep the number of states per cell small
+            continue;
+        }
+        let (distance, path) = dtw_slope_constrained(
+            &tc.time_series_a.view(),
+            &tc.time_series_b.view(),
+            FullWindow::new(rows, columns),
+            usize::max(rows, columns),
+            tc.parsed_distance_mode().unwrap(),
+        )
+        .unwrap();
+        assert!((distance - tc.distance).abs() < 1e-9);
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[path.len() - 1], (rows - 1, columns - 1));
+    }
+
+    // aligning 12 samples to 4 samples requires a slope of about 3
+    let x = Array1::from_iter((0..12).map(|i| i as f64 / 3f64));
+    let y = Array1::from_vec(vec![0f64, 1f64, 2f64, 3f64]);
+    let (distance, path) = dtw_slope_constrained(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 12),
+        2,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(distance, f64::INFINITY);
+    assert_eq!(path.len(), 0);
+
+    let (distance, path) = dtw_slope_constrained(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 12),
+        3,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert!(distance.is_finite());
+    assert!(
+        distance
+            > dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(4, 12),
+                DistanceMode::Manhattan,
+                StorageStrategy::Auto,
+                f64::INFINITY
+            )
+            .unwrap()
+            .0
+    );
+    // no more than 2 consecutive horizontal or vertical steps
+    for i in 3..path.len() {
+        let steps: Vec<(usize, usize)> = (i - 2..i + 1)
+            .map(|j| (path[j].0 - path[j - 1].0, path[j].1 - path[j - 1].1))
+            .collect();
+        assert!(!steps.iter().all(|&step| step == (0, 1)));
+        assert!(!steps.iter().all(|&step| step == (1, 0)));
+    }
+
+    // a ratio of 1 only allows the diagonal
+    let (distance, path) = dtw_slope_constrained(
+        &y.view(),
+        &y.view(),
+        FullWindow::new(4, 4),
+        1,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(distance, 0f64);
+    assert_eq!(path, Array1::from_vec(vec![(0, 0), (1, 1), (2, 2), (3, 3)]));
+}
+
+#[test]
+fn test_dtw_tradeoff_report() {
+    let factors = [2, 3, 4];
+
+    // time series which are small enough for the base case of fastdtw are aligned exactly
+    let x = Array1::from_vec(vec![1f64, 5f64, 2f64]);
+    let y = Array1::from_vec(vec![1f64, 3f64, 2f64]);
+    let report =
+        dtw_tradeoff_report(&x.view(), &y.view(), &factors, DistanceMode::Euclidean).unwrap();
+    assert_eq!(report.exact_distance, 2f64);
+    assert_eq!(report.entries.len(), factors.len());
+    for (entry, &factor) in report.entries.iter().zip(&factors) {
+        assert_eq!(entry.resolution_factor, factor);
+        assert_eq!(entry.distance, report.exact_distance);
+        assert_eq!(entry.relative_error, 0f64);
+    }
+
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let report = dtw_tradeoff_report(
+            &tc.time_series_a.view(),
+            &tc.time_series_b.view(),
+            &factors,
+            tc.parsed_distance_mode().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report.exact_distance, tc.distance);
+        assert_eq!(report.entries.len(), factors.len());
+        for entry in report.entries {
+            assert!(entry.distance >= report.exact_distance);
+            assert!(entry.relative_error >= 0f64);
+        }
+    }
+}
+
+#[test]
+fn test_dtw_best_orientation() {
+    let x = Array1::from_vec(vec![0f64, 1f64, 2f64, 4f64, 8f64, 16f64]);
+
+    // a time series which follows x in the same direction
+    let y = Array1::from_vec(vec![0f64, 0f64, 1f64, 2f64, 4f64, 8f64, 15f64]);
+    let (orientation, (distance, path)) =
+        dtw_best_orientation(&x.view(), &y.view(), DistanceMode::Manhattan).unwrap();
+    assert_eq!(orientation, Orientation::Forward);
+    assert_eq!(
+        (distance, path),
+        dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(7, 6),
+            DistanceMode::Manhattan,
+            StorageStrategy::Auto,
+            f64::INFINITY
+        )
+        .unwrap()
+    );
+
+    // a time reversed version of the same time series
+    let reversed_y = y.slice(s![..;-1]).to_owned();
+    let (orientation, (distance, _)) =
+        dtw_best_orientation(&x.view(), &reversed_y.view(), DistanceMode::Manhattan).unwrap();
+    assert_eq!(orientation, Orientation::Reversed);
+    assert_eq!(distance, 1f64);
+
+    // a palindromic time series aligns equally well in both orientations
+    let palindrome = Array1::from_vec(vec![0f64, 1f64, 3f64, 1f64, 0f64]);
+    let (orientation, (distance, _)) = dtw_best_orientation(
+        &palindrome.view(),
+        &palindrome.view(),
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(orientation, Orientation::Forward);
+    assert_eq!(distance, 0f64);
+}
+
+#[test]
+fn test_dump_matrices() {
+    let x = Array1::from_vec(vec![1f64, 3f64, 4f64, 9f64]);
+    let y = Array1::from_vec(vec![1f64, 2f64, 9f64]);
+
+    // fill the cost matrix like dtw_ex, leaving the last cell uncomputed
+    let mut cost_matrix = CostMatrix::new(3, 4);
+    for (row, column) in FullWindow::new(3, 4).take(11) {
+        let cost = f64::abs(x[column - 1] - y[row - 1]);
+        let (value, action) = [
+            (cost_matrix.get_cost(row - 1, column), Action::Inserted),
+            (cost_matrix.get_cost(row, column - 1), Action::Deleted),
+            (cost_matrix.get_cost(row - 1, column - 1), Action::Matched),
+        ]
+        .into_iter()
+        .fold((f64::INFINITY, Action::Matched), |a, b| match b.0 < a.0 {
+            true => b,
+            false => a,
+        });
+        cost_matrix.set_cost(row, column, cost + value);
+        cost_matrix.set_action(row, column, action);
+    }
+
+    let mut path = std::env::temp_dir();
+    path.push(format!("dtw_dump_matrices_{}.bin", std::process::id()));
+    dump_matrices(&cost_matrix, &path).expect("could not dump the matrices");
+    let loaded = load_matrices(&path).expect("could not load the matrices");
+    std::fs::remove_file(&path).expect("could not remove the dump");
+
+    assert_eq!(loaded.rows(), 3);
+    assert_eq!(loaded.columns(), 4);
+    for row in 1..4 {
+        for column in 1..5 {
+            assert_eq!(
+                loaded.get_cost(row, column),
+                cost_matrix.get_cost(row, column)
+            );
+            assert_eq!(
+                loaded.get_action(row, column),
+                cost_matrix.get_action(row, column)
+            );
+        }
+    }
+    assert_eq!(loaded.get_action(3, 4), None);
+    assert_eq!(loaded.get_cost(3, 4), f64::INFINITY);
+
+    // a truncated file and a header with overflowing sizes are rejected before allocating
+    let mut truncated = 3u64.to_le_bytes().to_vec();
+    truncated.extend_from_slice(&4u64.to_le_bytes());
+    truncated.extend_from_slice(&[0u8; 20]);
+    let mut overflowing = u64::MAX.to_le_bytes().to_vec();
+    overflowing.extend_from_slice(&2u64.to_le_bytes());
+    for bytes in [truncated, overflowing] {
+        std::fs::write(&path, bytes).expect("could not write the dump");
+        let result = load_matrices(&path);
+        std::fs::remove_file(&path).expect("could not remove the dump");
+        assert!(matches!(result, Err(e) if e.kind() == std::io::ErrorKind::InvalidData));
+    }
+}
+
+#[test]
+fn test_fast_dtw_adaptive() {
+    let x = Array1::from_vec(vec![
+        3f64, 5f64, 4f64, 2f64, 7f64, 5f64, 6f64, 5f64, 8f64, 5f64, 8f64, 2f64, 6f64, 6f64, 1f64,
+        3f64,
+    ]);
+    let y = Array1::from_vec(vec![
+        8f64, 2f64, 4f64, 2f64, 4f64, 9f64, 6f64, 9f64, 6f64, 2f64, 8f64, 8f64, 1f64, 7f64, 2f64,
+        1f64,
+    ]);
+    let (exact_distance, exact_path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(16, 16),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let (distance, _) = fastdtw_ex(&x.view(), &y.view(), 2, 0, DistanceMode::Manhattan).unwrap();
+    assert!(distance > exact_distance);
+
+    // the warp path touches the window boundary until the radius is large enough
+    let ((distance, path), search_radius) =
+        fastdtw_adaptive(&x.view(), &y.view(), 2, 0, 8, DistanceMode::Manhattan).unwrap();
+    assert_eq!(search_radius, 2);
+    assert_eq!(distance, exact_distance);
+    assert_eq!(path, exact_path);
+
+    // the radius never exceeds the maximum radius
+    let ((distance, _), search_radius) =
+        fastdtw_adaptive(&x.view(), &y.view(), 2, 0, 1, DistanceMode::Manhattan).unwrap();
+    assert_eq!(search_radius, 1);
+    assert!(distance >= exact_distance);
+}
+
+#[test]
+fn test_window_row_ranges() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/projection.yaml");
+    let test_cases =
+        load_projection_cases(d).expect("could not read test values from projection.yaml");
+
+    for tc in test_cases {
+        let window = ConstrainedWindow::from_low_res_path_anisotropic(
+            tc.low_res_path,
+            tc.resolution_factor,
+            (
+                tc.search_radius,
+                tc.column_search_radius.unwrap_or(tc.search_radius),
+            ),
+            tc.high_res_rows,
+            tc.high_res_columns,
+        );
+
+        // the ranges of the rows are the first and last columns of the cells visited on each row
+        let mut expected: Vec<(usize, (usize, usize))> = Vec::new();
+        for &(row, column) in tc.projected_window.iter() {
+            match expected.last_mut() {
+                Some((last_row, (_, max))) if *last_row == row => *max = column,
+                _ => expected.push((row, (column, column))),
+            }
+        }
+        assert_eq!(window.row_ranges().collect::<Vec<_>>(), expected);
+        for &(row, range) in expected.iter() {
+            assert_eq!(window.row_range(row), Some(range));
+        }
+        assert_eq!(window.row_range(0), None);
+        assert_eq!(window.row_range(tc.high_res_rows + 1), None);
+        assert_eq!(window.cell_count(), tc.projected_window.len());
+    }
+
+    // a window from a low resolution path covering the top left corner only
+    let window = ConstrainedWindow::from_low_res_path(Array1::from_vec(vec![(0, 0)]), 2, 0, 6, 6);
+    assert_eq!(window.row_range(1), Some((1, 2)));
+    assert_eq!(window.row_range(2), Some((1, 2)));
+    assert_eq!(window.row_range(3), None);
+    assert_eq!(window.row_ranges().count(), 2);
+    assert_eq!(
+        window.collect::<Vec<_>>(),
+        vec![(1, 1), (1, 2), (2, 1), (2, 2)]
+    );
+}
+
+#[test]
+fn test_storage_strategies() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    let strategies = [
+        StorageStrategy::Dense,
+        StorageStrategy::Cache,
+        StorageStrategy::Banded,
+    ];
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let rows = y.shape()[0];
+        let columns = x.shape()[0];
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+
+        // the full window and a narrow window around the exact warp path
+        let expected = dtw_ex(
+            &x,
+            &y,
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        let window = ConstrainedWindow::from_low_res_path(expected.1.clone(), 1, 1, rows, columns);
+        let expected_constrained = dtw_ex(
+            &x,
+            &y,
+            window.clone(),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        for storage_strategy in strategies {
+            assert_eq!(
+                dtw_ex(
+                    &x,
+                    &y,
+                    FullWindow::new(rows, columns),
+                    distance_mode,
+                    storage_strategy,
+                    f64::INFINITY
+                )
+                .unwrap(),
+                expected
+            );
+            assert_eq!(
+                dtw_ex(
+                    &x,
+                    &y,
+                    window.clone(),
+                    distance_mode,
+                    storage_strategy,
+                    f64::INFINITY
+                )
+                .unwrap(),
+                expected_constrained
+            );
+        }
+    }
+}
+
+#[test]
+fn test_f32_costs() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.mapv(|v| v as f32);
+        let y = tc.time_series_b.mapv(|v| v as f32);
+        let rows = y.shape()[0];
+        let columns = x.shape()[0];
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+
+        // the f32 costs agree with the f64 costs up to the precision of f32
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        for storage_strategy in [
+            StorageStrategy::Dense,
+            StorageStrategy::Cache,
+            StorageStrategy::Banded,
+        ] {
+            let (distance, path) = dtw_ex_in::<f32, _, _>(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(rows, columns),
+                distance_mode,
+                storage_strategy,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!((distance as f64 - expected.0).abs() <= 1e-4 * expected.0.max(1f64));
+            assert_eq!(path, expected.1);
+        }
+
+        let expected = fastdtw_ex(&x.view(), &y.view(), 2, 1, distance_mode).unwrap();
+        let (distance, _) =
+            fastdtw_ex_in::<f32, _>(&x.view(), &y.view(), 2, 1, distance_mode).unwrap();
+        assert!((distance as f64 - expected.0).abs() <= 1e-4 * expected.0.max(1f64));
+    }
+
+    let mut cost_matrix = CostMatrix::<f32>::new(2, 3);
+    cost_matrix.set_cost(2, 3, 1.5f32);
+    assert_eq!(cost_matrix.get_cost(2, 3), 1.5f32);
+    assert_eq!(cost_matrix.get_cost(2, 2), f32::INFINITY);
+}
+
+#[test]
+fn test_dtw_timestamped() {
+    let x = Array1::from_vec(vec![0f64, 1f64, 2f64, 1f64, 0f64, 0f64]);
+    let y = Array1::from_vec(vec![0f64, 0f64, 1f64, 2f64, 1f64, 0f64]);
+    let exact = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(6, 6),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+
+    // without a time penalty, the timestamps do not matter
+    let uniform = Array1::from_iter((0..6).map(|i| i as f64));
+    let irregular = Array1::from_vec(vec![0f64, 0.5, 4f64, 4.5, 5f64, 9f64]);
+    for (tx, ty) in [(&uniform, &uniform), (&uniform, &irregular)] {
+        let result = dtw_timestamped(
+            &x.view(),
+            &tx.view(),
+            &y.view(),
+            &ty.view(),
+            0f64,
+            DistanceMode::Manhattan,
+        );
+        assert_eq!(result, Ok(exact.clone()));
+    }
+
+    /* the y time series is x delayed by one sample, and its timestamps are shifted by one time unit so
+     * that the samples coincide in time: with a time penalty, the alignment still matches the samples
+     * with the same values and only the first and last cells, which pair samples one time unit apart,
+     * have a time cost
+     */
+    let shifted = Array1::from_iter((0..6).map(|i| i as f64 - 1f64));
+    let (distance, path) = dtw_timestamped(
+        &x.view(),
+        &uniform.view(),
+        &y.view(),
+        &shifted.view(),
+        0.5,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(distance, exact.0 + 1f64);
+    assert_eq!(path, exact.1);
+
+    // samples which are close in value but far apart in time are not aligned with a large penalty
+    let (distance, path) = dtw_timestamped(
+        &x.view(),
+        &uniform.view(),
+        &y.view(),
+        &uniform.view(),
+        10f64,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert_eq!(path, Array1::from_iter((0..6).map(|i| (i, i))));
+    assert_eq!(distance, 4f64);
+    assert!(distance > exact.0);
+
+    // the lengths of the timestamps are checked
+    assert_eq!(
+        dtw_timestamped(
+            &x.view(),
+            &uniform.slice(s![..5]),
+            &y.view(),
+            &uniform.view(),
+            1f64,
+            DistanceMode::Manhattan,
+        ),
+        Err(DtwError::TimestampLengthMismatch {
+            values: 6,
+            timestamps: 5
+        })
+    );
+}
+
+#[test]
+fn test_dtw_clamp_delta() {
+    // two identical time series, except for a spike in y
+    let x = Array1::from_iter((0..20).map(|i| f64::sin(i as f64 / 3f64)));
+    let mut y = x.clone();
+    y[10] += 50f64;
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let (distance, _) = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(20, 20),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        let (robust_distance, robust_path) = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(20, 20),
+            distance_mode,
+            StorageStrategy::Auto,
+            1f64,
+        )
+        .unwrap();
+        assert!(distance > 10f64);
+        assert!(robust_distance <= 1f64);
+        assert_eq!(robust_path, Array1::from_iter((0..20).map(|i| (i, i))));
+    }
+
+    // the canberra cost is at most 1, so a spike is only damped by a small clamp delta
+    let (distance, _) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(20, 20),
+        DistanceMode::Canberra,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let (robust_distance, robust_path) = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(20, 20),
+        DistanceMode::Canberra,
+        StorageStrategy::Auto,
+        0.1f64,
+    )
+    .unwrap();
+    assert!(distance > 0.9f64);
+    assert!(robust_distance < distance / 2f64);
+    assert_eq!(robust_path, Array1::from_iter((0..20).map(|i| (i, i))));
+}
+
+#[test]
+fn test_dtw_frames() {
+    let x = Array1::from_vec(vec![1f64, 3f64, 4f64, 9f64, 8f64]);
+    let y = Array1::from_vec(vec![1f64, 4f64, 8f64, 9f64]);
+    let expected = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 5),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+
+    // 20 cells give a frame every 4 cells, or every 3 cells with a final frame for the last 2 cells
+    for (frame_interval, frame_count) in [(4, 5), (3, 7), (1, 20), (100, 1)] {
+        let (result, frames) = dtw_frames(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(4, 5),
+            DistanceMode::Manhattan,
+            frame_interval,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(frames.len(), frame_count);
+        assert!(frames.iter().all(|frame| frame.shape() == [4, 5]));
+        assert_eq!(frames[frames.len() - 1][[3, 4]], expected.0);
+    }
+
+    // each frame holds the cells computed so far, row by row
+    let (_, frames) = dtw_frames(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 5),
+        DistanceMode::Manhattan,
+        4,
+    )
+    .unwrap();
+    assert_eq!(
+        frames[0].row(0),
+        Array1::from_vec(vec![0f64, 2f64, 5f64, 13f64, f64::INFINITY])
+    );
+    assert!(frames[0].row(1).iter().all(|cost| cost.is_infinite()));
+    assert_eq!(frames[1].row(0), frames[4].row(0));
+}
+
+#[test]
+fn test_dtw_from_distance_grid() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let grid = Array2::from_shape_fn((y.len(), x.len()), |(i, j)| f64::abs(x[j] - y[i]));
+        assert_eq!(
+            dtw_from_distance_grid(&grid.view()).unwrap(),
+            dtw_ex(
+                &x,
+                &y,
+                FullWindow::new(y.len(), x.len()),
+                DistanceMode::Manhattan,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_boundary_policy() {
+    // the query y occurs with a small distortion in the middle of x
+    let x = Array1::from_vec(vec![0f64, 0f64, 1f64, 2f64, 3.5, 4f64, 0f64, 0f64]);
+    let y = Array1::from_vec(vec![2f64, 3f64, 4f64]);
+    let rows = y.len();
+    let columns = x.len();
+
+    let fill = |cost_storage: &mut dyn CostStorage| {
+        for (row, column) in FullWindow::new(rows, columns) {
+            let cost = f64::abs(x[column - 1] - y[row - 1]);
+            let value = [
+                cost_storage.get_cost(row - 1, column),
+                cost_storage.get_cost(row, column - 1),
+                cost_storage.get_cost(row - 1, column - 1),
+            ]
+            .into_iter()
+            .fold(f64::INFINITY, f64::min);
+            cost_storage.set_cost(row, column, cost + value);
+        }
+    };
+
+    /* with a free first row and the minimum over the last row, the cost matrix gives the subsequence
+     * DTW distance, i.e. the minimum DTW distance between y and any subsequence of x
+     */
+    let mut subsequence_distance = f64::INFINITY;
+    for start in 0..columns {
+        for end in start + 1..columns + 1 {
+            let (distance, _) = dtw_ex(
+                &x.slice(s![start..end]),
+                &y.view(),
+                FullWindow::new(rows, end - start),
+                DistanceMode::Manhattan,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            subsequence_distance = subsequence_distance.min(distance);
+        }
+    }
+    assert_eq!(subsequence_distance, 0.5);
+
+    let mut storages: Vec<Box<dyn CostStorage>> = vec![
+        Box::new(CostMatrix::with_boundary(
+            rows,
+            columns,
+            BoundaryPolicy::FreeFirstRow,
+        )),
+        Box::new(CostCache::with_boundary(rows, BoundaryPolicy::FreeFirstRow)),
+        Box::new(CostBand::with_boundary(rows, BoundaryPolicy::FreeFirstRow)),
+    ];
+    for cost_storage in storages.iter_mut() {
+        assert_eq!(cost_storage.get_cost(0, 5), 0f64);
+        assert_eq!(cost_storage.get_cost(2, 0), f64::INFINITY);
+        fill(cost_storage.as_mut());
+        let distance = (1..columns + 1)
+            .map(|column| cost_storage.get_cost(rows, column))
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(distance, subsequence_distance);
+    }
+
+    // the default policy gives the DTW distance over the whole of x
+    let mut cost_matrix = CostMatrix::new(rows, columns);
+    fill(&mut cost_matrix);
+    assert!(cost_matrix.get_cost(rows, columns) > subsequence_distance);
+
+    // a free first column lets the warp path start at any sample of y
+    let cost_cache = CostCache::<f64>::with_boundary(rows, BoundaryPolicy::FreeFirstColumn);
+    assert_eq!(cost_cache.get_cost(2, 0), 0f64);
+    assert_eq!(cost_cache.get_cost(0, 5), f64::INFINITY);
+}
+
+#[test]
+fn test_fastdtw_scheduled() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+
+        // a constant schedule is the same as a fixed resolution factor
+        for resolution_factor in [2, 3] {
+            assert_eq!(
+                fastdtw_scheduled(&x, &y, &[resolution_factor], 1, distance_mode).unwrap(),
+                fastdtw_ex(&x, &y, resolution_factor, 1, distance_mode).unwrap()
+            );
+            assert_eq!(
+                fastdtw_scheduled(&x, &y, &[resolution_factor; 10], 1, distance_mode).unwrap(),
+                fastdtw_ex(&x, &y, resolution_factor, 1, distance_mode).unwrap()
+            );
+        }
+
+        // the schedule only changes the coarse levels, the result is never better than the exact dtw
+        for schedule in [vec![2, 4], vec![4, 2], vec![2, 2, 8]] {
+            let (distance, _) = fastdtw_scheduled(&x, &y, &schedule, 1, distance_mode).unwrap();
+            assert!(distance >= tc.distance);
+        }
+    }
+}
+
+#[test]
+fn test_single_sample() {
+    let one = Array1::from_vec(vec![2f64]);
+    let long = Array1::from_vec(vec![1f64, 4f64, 2f64, 0f64, 7f64]);
+
+    // a single sample x is a single column, which every sample of y is aligned to
+    let (distance, path) = dtw::dtw(&one.view(), &long.view()).unwrap();
+    assert_eq!(distance, f64::sqrt(1f64 + 4f64 + 0f64 + 4f64 + 25f64));
+    assert_eq!(path, Array1::from_iter((0..5).map(|row| (row, 0))));
+
+    // and a single sample y is a single row
+    let (distance, path) = dtw::dtw(&long.view(), &one.view()).unwrap();
+    assert_eq!(distance, f64::sqrt(34f64));
+    assert_eq!(path, Array1::from_iter((0..5).map(|column| (0, column))));
+
+    // the closed form agrees with the dynamic programming, also through fastdtw
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        for (x, y) in [(&one, &long), (&long, &one), (&one, &one)] {
+            let expected = dtw_ex(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(y.len(), x.len()),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert_eq!(dtw::dtw(&x.view(), &y.view()).unwrap().1, expected.1);
+            assert_eq!(
+                fastdtw_ex(&x.view(), &y.view(), 2, 0, distance_mode).unwrap(),
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_budget() {
+    let x = Array1::from_iter((0..200).map(|i| f64::sin(i as f64 / 10f64)));
+    let y = Array1::from_iter((0..150).map(|i| f64::sin(i as f64 / 8f64)));
+    let expected = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(150, 200),
+        DistanceMode::Euclidean,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+
+    // the full window has exactly 150 * 200 cells
+    assert_eq!(
+        dtw_budgeted(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(150, 200),
+            DistanceMode::Euclidean,
+            150 * 200,
+        ),
+        Ok(expected)
+    );
+    assert_eq!(
+        dtw_budgeted(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(150, 200),
+            DistanceMode::Euclidean,
+            150 * 200 - 1,
+        ),
+        Err(DtwError::BudgetExceeded {
+            max_cells: 150 * 200 - 1
+        })
+    );
+
+    // fastdtw fits into a much smaller budget over all its levels
+    let fast = fastdtw_ex(&x.view(), &y.view(), 2, 1, DistanceMode::Euclidean).unwrap();
+    assert_eq!(
+        fastdtw_budgeted(&x.view(), &y.view(), 2, 1, DistanceMode::Euclidean, 5000),
+        Ok(fast)
+    );
+    assert_eq!(
+        fastdtw_budgeted(&x.view(), &y.view(), 2, 1, DistanceMode::Euclidean, 500),
+        Err(DtwError::BudgetExceeded { max_cells: 500 })
+    );
+}
+
+#[test]
+fn test_errors() {
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let empty = Array1::<f64>::zeros(0);
+
+    assert_eq!(
+        dtw::dtw(&x.view(), &empty.view()),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        dtw::dtw(&empty.view(), &x.view()),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        fastdtw_ex(&x.view(), &empty.view(), 2, 1, DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        fastdtw_iterative(&empty.view(), &x.view(), 2, 1, DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        fastdtw_weighted(&x.view(), &empty.view(), 2, 1, DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        dtw_symmetric2_normalized(&x.view(), &empty.view(), DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        dtw_by(&[], x.as_slice().unwrap(), |&v| v, DistanceMode::Manhattan),
+        Err(DtwError::EmptyTimeSeries)
+    );
+    assert_eq!(
+        dtw_from_distance_grid(&Array2::<f64>::zeros((0, 3)).view()),
+        Err(DtwError::EmptyTimeSeries)
+    );
+
+    // a window for larger time series visits cells outside of the cost matrix
+    let y = Array1::from(vec![1.0, 2.0, 7.0]);
+    assert_eq!(
+        dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(4, 6),
+            DistanceMode::Manhattan,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        ),
+        Err(DtwError::MalformedWindow { row: 4, column: 1 })
+    );
+    assert_eq!(
+        dtw_ex(
+            &x.view(),
+            &y.view(),
+            [(1, 1), (0, 2)].into_iter(),
+            DistanceMode::Manhattan,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        ),
+        Err(DtwError::MalformedWindow { row: 0, column: 2 })
+    );
+    assert_eq!(
+        dtw_slope_constrained(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(4, 6),
+            2,
+            DistanceMode::Manhattan,
+        ),
+        Err(DtwError::MalformedWindow { row: 4, column: 1 })
+    );
+}
+
+#[test]
+fn test_dtw_by() {
+    struct Sample {
+        _timestamp: u64,
+        temperature: f64,
+        pressure: f64,
+    }
+
+    let samples = |values: &[(f64, f64)]| -> Vec<Sample> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &(temperature, pressure))| Sample {
+                _timestamp: i as u64,
+                temperature,
+                pressure,
+            })
+            .collect()
+    };
+    let x = samples(&[(20.5, 1.0), (21.0, 1.2), (23.5, 0.9), (22.0, 1.1)]);
+    let y = samples(&[(20.0, 1.1), (23.0, 1.0), (23.5, 1.3)]);
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let temperatures = |samples: &[Sample]| -> Array1<f64> {
+            samples.iter().map(|sample| sample.temperature).collect()
+        };
+        let (x_temperatures, y_temperatures) = (temperatures(&x), temperatures(&y));
+        assert_eq!(
+            dtw_by(&x, &y, |sample| sample.temperature, distance_mode).unwrap(),
+            dtw_ex(
+                &x_temperatures.view(),
+                &y_temperatures.view(),
+                FullWindow::new(3, 4),
+                distance_mode,
+                StorageStrategy::Auto,
+                f64::INFINITY,
+            )
+            .unwrap()
+        );
+    }
+
+    // a derived scalar works the same way
+    let (distance, _) = dtw_by(
+        &x,
+        &y,
+        |sample| sample.temperature * sample.pressure,
+        DistanceMode::Manhattan,
+    )
+    .unwrap();
+    assert!(distance.is_finite());
+}
+
+#[test]
+fn test_dtw_with_cost() {
+    // the absolute difference as cost function is the manhattan mode
+    let x = Array1::from(vec![1.0, 3.0, 4.0, 9.0, 8.0, 2.0]);
+    let y = Array1::from(vec![1.0, 2.0, 7.0, 9.0, 3.0]);
+    let (rows, columns) = (y.shape()[0], x.shape()[0]);
+    assert_eq!(
+        dtw_with_cost(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            |a: &f64, b: &f64| (a - b).abs()
+        )
+        .unwrap(),
+        dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            DistanceMode::Manhattan,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap()
+    );
+
+    // categorical labels which only match themselves
+    #[derive(PartialEq)]
+    enum Label {
+        Walk,
+        Run,
+        Sit,
+    }
+    let mismatch = |a: &Label, b: &Label| if a == b { 0f64 } else { 1f64 };
+    let x = Array1::from(vec![Label::Walk, Label::Walk, Label::Run, Label::Sit]);
+    let y = Array1::from(vec![Label::Walk, Label::Run, Label::Run, Label::Sit]);
+    let (distance, path) =
+        dtw_with_cost(&x.view(), &y.view(), FullWindow::new(4, 4), mismatch).unwrap();
+    assert_eq!(distance, 0f64);
+    assert_eq!(path.len(), 5);
+
+    let y = Array1::from(vec![Label::Walk, Label::Sit]);
+    let (distance, _) =
+        dtw_with_cost(&x.view(), &y.view(), FullWindow::new(2, 4), mismatch).unwrap();
+    assert_eq!(distance, 1f64);
+}
+
+#[test]
+fn test_dtw_symmetric2_normalized() {
+    let x = Array1::from_vec(vec![1f64, 2f64, 3f64]);
+    let y = Array1::from_vec(vec![1f64, 3f64]);
+
+    /* the local costs are
+     *   y\x  1  2  3
+     *   1    0  1  2
+     *   3    2  1  0
+     * and the accumulated costs with the symmetric2 weights
+     *   g(1, 1) = 2 * 0 = 0
+     *   g(1, 2) = g(1, 1) + 1 = 1
+     *   g(1, 3) = g(1, 2) + 2 = 3
+     *   g(2, 1) = g(1, 1) + 2 = 2
+     *   g(2, 2) = min(g(1, 2) + 1, g(2, 1) + 1, g(1, 1) + 2 * 1) = 2
+     *   g(2, 3) = min(g(1, 3) + 0, g(2, 2) + 0, g(1, 2) + 2 * 0) = 1
+     * which is normalized by 3 + 2
+     */
+    let (distance, path) =
+        dtw_symmetric2_normalized(&x.view(), &y.view(), DistanceMode::Manhattan).unwrap();
+    assert_eq!(distance, 1f64 / 5f64);
+    assert_eq!(path, Array1::from_vec(vec![(0, 0), (0, 1), (1, 2)]));
+
+    let (distance, _) =
+        dtw_symmetric2_normalized(&x.view(), &y.view(), DistanceMode::Euclidean).unwrap();
+    assert_eq!(distance, f64::sqrt(1f64 / 5f64));
+
+    // identical time series have a zero distance, regardless of their sizes
+    let (distance, _) =
+        dtw_symmetric2_normalized(&x.view(), &x.view(), DistanceMode::Manhattan).unwrap();
+    assert_eq!(distance, 0f64);
+}
+
+#[test]
+fn test_dtw_test_case_from_result() {
+    let x = Array1::from_vec(vec![1i32, 5, 2, 8, 3]);
+    let y = Array1::from_vec(vec![2i32, 5, 7, 3]);
+    let result = dtw_ex(
+        &x.view(),
+        &y.view(),
+        FullWindow::new(4, 5),
+        DistanceMode::Manhattan,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    let tc = DtwTestCase::from_result(
+        "captured run",
+        &x.view(),
+        &y.view(),
+        DistanceMode::Manhattan,
+        &result,
+    );
+
+    let mut path = std::env::temp_dir();
+    path.push(format!("dtw-test-case-{}.yaml", std::process::id()));
+    save_dtw_cases(&path, &[tc]).expect("could not save the test case");
+    let loaded = load_dtw_cases(&path).expect("could not load the saved test case");
+    std::fs::remove_file(&path).expect("could not remove the saved test case");
+
+    // the reloaded test case passes like the fixtures do
+    assert_eq!(loaded.len(), 1);
+    let tc = &loaded[0];
+    assert_eq!(tc.name, "captured run");
+    let distance_mode = tc
+        .parsed_distance_mode()
+        .expect("unknown distance mode specified");
+    let (distance, path) = dtw_ex(
+        &tc.time_series_a.view(),
+        &tc.time_series_b.view(),
+        FullWindow::new(tc.time_series_b.len(), tc.time_series_a.len()),
+        distance_mode,
+        StorageStrategy::Auto,
+        f64::INFINITY,
+    )
+    .unwrap();
+    assert_eq!(distance, tc.distance);
+    assert_eq!(path, tc.warp_path);
+    assert_eq!((distance, path), result);
+}
+
+#[test]
+fn test_sakoe_chiba_window() {
+    // a band of width zero on a square matrix is the diagonal
+    assert_eq!(
+        SakoeChibaWindow::new(4, 4, 0).collect::<Vec<_>>(),
+        vec![(1, 1), (2, 2), (3, 3), (4, 4)]
+    );
+    assert_eq!(
+        SakoeChibaWindow::new(4, 4, 1).collect::<Vec<_>>(),
+        vec![
+            (1, 1),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+            (2, 3),
+            (3, 2),
+            (3, 3),
+            (3, 4),
+            (4, 3),
+            (4, 4)
+        ]
+    );
+
+    // for different sizes, the band follows the diagonal from corner to corner
+    assert_eq!(
+        SakoeChibaWindow::new(6, 3, 0).collect::<Vec<_>>(),
+        vec![(1, 1), (1, 2), (2, 3), (2, 4), (3, 5), (3, 6)]
+    );
+    assert_eq!(
+        SakoeChibaWindow::new(2, 4, 0).collect::<Vec<_>>(),
+        vec![(1, 1), (2, 1), (3, 2), (4, 2)]
+    );
+
+    // a band wider than the matrix is the full window
+    assert!(SakoeChibaWindow::new(5, 7, 7).eq(FullWindow::new(7, 5)));
+
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+
+        // the band always contains a warp path, whose distance decreases with the width of the band
+        let mut previous_distance = f64::INFINITY;
+        for band_width in [0, 1, 2, 5] {
+            let (distance, path) = dtw_ex(
+                &x,
+                &y,
+                SakoeChibaWindow::new(x.len(), y.len(), band_width),
+                distance_mode,
+                StorageStrategy::Banded,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(distance.is_finite());
+            assert!(distance >= tc.distance);
+            assert!(distance <= previous_distance);
+            assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+            previous_distance = distance;
+        }
+    }
+}
+
+#[test]
+fn test_itakura_window() {
+    /* with a maximum slope of 2 on a 5 x 5 matrix, the parallelogram is bounded by v = 2u, v = u / 2 and
+     * the same lines through the last cell, i.e. the cells (row, column) with 0 based indices satisfying
+     * column / 2 <= row <= 2 * column and (4 - column) / 2 <= 4 - row <= 2 * (4 - column)
+     */
+    assert_eq!(
+        ItakuraWindow::new(5, 5, 2f64).collect::<Vec<_>>(),
+        vec![
+            (1, 1),
+            (2, 2),
+            (2, 3),
+            (3, 2),
+            (3, 3),
+            (3, 4),
+            (4, 3),
+            (4, 4),
+            (5, 5)
+        ]
+    );
+
+    // a slope of 1 only allows the diagonal
+    assert_eq!(
+        ItakuraWindow::new(3, 3, 1f64).collect::<Vec<_>>(),
+        vec![(1, 1), (2, 2), (3, 3)]
+    );
+
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/dtw.yaml");
+    let test_cases = load_dtw_cases(d).expect("could not read test values from dtw.yaml");
+    for tc in test_cases {
+        let x = tc.time_series_a.view();
+        let y = tc.time_series_b.view();
+        let distance_mode = tc
+            .parsed_distance_mode()
+            .expect("unknown distance mode specified");
+
+        // the window always contains a warp path, also for time series of different sizes
+        for max_slope in [1f64, 1.5, 2f64, 3f64] {
+            let (distance, path) = dtw_ex(
+                &x,
+                &y,
+                ItakuraWindow::new(x.len(), y.len(), max_slope),
+                distance_mode,
+                StorageStrategy::Banded,
+                f64::INFINITY,
+            )
+            .unwrap();
+            assert!(distance.is_finite());
+            assert!(distance >= tc.distance);
+            assert_eq!(path[path.len() - 1], (y.len() - 1, x.len() - 1));
+        }
+    }
+}
+
+#[test]
+fn test_distance_modes() {
+    let dtw = |x: &Array1<f64>, y: &Array1<f64>, distance_mode| {
+        dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(y.len(), x.len()),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap()
+    };
+
+    // the local cost of the matched samples 1 and 2 is 1/3, and 0 for the others
+    let x = Array1::from(vec![0f64, 1f64, 3f64]);
+    let y = Array1::from(vec![0f64, 2f64, 3f64]);
+    let (distance, path) = dtw(&x, &y, DistanceMode::Canberra);
+    assert_eq!(distance, 1f64 / 3f64);
+    assert_eq!(path.to_vec(), vec![(0, 0), (1, 1), (2, 2)]);
+    // the cube root of 2^3
+    let (distance, _) = dtw(
+        &Array1::from(vec![0f64, 2f64]),
+        &Array1::from(vec![0f64, 0f64]),
+        DistanceMode::Minkowski(3f64),
+    );
+    assert!((distance - 2f64).abs() < 1e-12);
+
+    let x = Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 4f64)));
+    let y = Array1::from_iter((0..25).map(|i| f64::sin(i as f64 / 3f64 + 0.5)));
+    let manhattan = dtw(&x, &y, DistanceMode::Manhattan);
+    let euclidean = dtw(&x, &y, DistanceMode::Euclidean);
+    // Chebyshev only differs from Manhattan for multivariate samples
+    assert_eq!(dtw(&x, &y, DistanceMode::Chebyshev), manhattan);
+    assert_eq!(dtw(&x, &y, DistanceMode::Minkowski(1f64)), manhattan);
+    let (distance, path) = dtw(&x, &y, DistanceMode::Minkowski(2f64));
+    assert!((distance - euclidean.0).abs() < 1e-12);
+    assert_eq!(path, euclidean.1);
+    // the squared Euclidean distance is the accumulated cost, without the final square root
+    let (distance, path) = dtw(&x, &y, DistanceMode::SquaredEuclidean);
+    assert_eq!(distance.sqrt(), euclidean.0);
+    assert_eq!(path, euclidean.1);
+
+    // the local costs of Canberra are at most 1
+    let (distance, path) = dtw(&x, &y, DistanceMode::Canberra);
+    assert!(distance > 0f64 && distance <= path.len() as f64);
+}
+
+#[test]
+fn test_dtw_ex_with_matrix() {
+    let x = Array1::from_iter((0..20).map(|i| f64::sin(i as f64 / 3f64)));
+    let y = Array1::from_iter((0..16).map(|i| f64::sin(i as f64 / 2.5 + 0.2)));
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let (result, cost_matrix) = dtw_ex_with_matrix(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(16, 20),
+            distance_mode,
+            f64::INFINITY,
+        )
+        .unwrap();
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(16, 20),
+            distance_mode,
+            StorageStrategy::Cache,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+        assert_eq!((cost_matrix.rows(), cost_matrix.columns()), (16, 20));
+        assert_eq!(backtrack(&cost_matrix, 16, 20).unwrap(), expected.1);
+        if let DistanceMode::Manhattan = distance_mode {
+            assert_eq!(cost_matrix.costs()[[15, 19]], expected.0);
+        }
+        // an alternative warp path, ending before the last sample of x
+        let path = backtrack(&cost_matrix, 16, 12).unwrap();
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[path.len() - 1], (15, 11));
+    }
+
+    // the cells outside of the window are not computed
+    let (_, cost_matrix) = dtw_ex_with_matrix(
+        &x.view(),
+        &x.view(),
+        SakoeChibaWindow::new(20, 20, 2),
+        DistanceMode::Manhattan,
+        f64::INFINITY,
+    )
+    .unwrap();
+    assert_eq!(cost_matrix.costs()[[0, 3]], f64::INFINITY);
+    assert_eq!(cost_matrix.actions()[[0, 3]], None);
+    assert_eq!(cost_matrix.costs()[[19, 19]], 0f64);
+
+    let empty = Array1::<f64>::zeros(0);
+    assert!(matches!(
+        dtw_ex_with_matrix(
+            &x.view(),
+            &empty.view(),
+            FullWindow::new(0, 20),
+            DistanceMode::Manhattan,
+            f64::INFINITY
+        ),
+        Err(DtwError::EmptyTimeSeries)
+    ));
+    assert!(matches!(
+        dtw_ex_with_matrix(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(17, 20),
+            DistanceMode::Manhattan,
+            f64::INFINITY
+        ),
+        Err(DtwError::MalformedWindow { row: 17, column: 1 })
+    ));
+}
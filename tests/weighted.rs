@@ -0,0 +1,154 @@
+use dtw::{
+    dtw_amerced, dtw_ex, dtw_weighted, wdtw_weight, DistanceMode, DtwError, FullWindow,
+    SakoeChibaWindow, StorageStrategy,
+};
+use ndarray::Array1;
+
+#[test]
+fn test_wdtw_weight() {
+    assert_eq!(wdtw_weight(5, 10, 1f64), 0.5);
+    assert_eq!(wdtw_weight(0, 10, 0f64), 0.5);
+    assert!((wdtw_weight(0, 10, 1f64) - 1f64 / (1f64 + 5f64.exp())).abs() < 1e-15);
+    // the weight grows with the phase difference, and faster with a steeper curve
+    for steepness in [0.1, 1f64, 2f64] {
+        for phase_difference in 0..10 {
+            assert!(
+                wdtw_weight(phase_difference, 10, steepness)
+                    < wdtw_weight(phase_difference + 1, 10, steepness)
+            );
+        }
+    }
+    assert!(wdtw_weight(2, 10, 1f64) < wdtw_weight(2, 10, 0.1));
+    assert!(wdtw_weight(8, 10, 1f64) > wdtw_weight(8, 10, 0.1));
+}
+
+#[test]
+fn test_dtw_weighted() {
+    let x = Array1::from_iter((0..40).map(|i| f64::sin(i as f64 / 4f64)));
+    let y = Array1::from_iter((0..34).map(|i| f64::sin(i as f64 / 3f64 + 1f64)));
+    let (rows, columns) = (y.len(), x.len());
+    let max_phase_difference = |path: &Array1<(usize, usize)>| {
+        path.iter()
+            .map(|&(row, column)| row.abs_diff(column))
+            .max()
+            .unwrap()
+    };
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        let (distance, path) = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+
+        // a flat weight curve halves all local costs, which leaves the warp path unchanged
+        let (weighted_distance, weighted_path) = dtw_weighted(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            0f64,
+            distance_mode,
+        )
+        .unwrap();
+        let expected = match distance_mode {
+            DistanceMode::Manhattan => distance / 2f64,
+            DistanceMode::Euclidean => distance / 2f64.sqrt(),
+            _ => unreachable!(),
+        };
+        assert!((weighted_distance - expected).abs() < 1e-12);
+        assert_eq!(weighted_path, path);
+
+        // a steep weight curve keeps the warp path closer to the diagonal
+        let (_, steep_path) = dtw_weighted(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(rows, columns),
+            2f64,
+            distance_mode,
+        )
+        .unwrap();
+        assert!(max_phase_difference(&steep_path) < max_phase_difference(&path));
+    }
+
+    let empty = Array1::<f64>::zeros(0);
+    assert_eq!(
+        dtw_weighted(
+            &empty.view(),
+            &y.view(),
+            FullWindow::new(rows, 0),
+            1f64,
+            DistanceMode::Euclidean
+        ),
+        Err(DtwError::EmptyTimeSeries)
+    );
+}
+
+#[test]
+fn test_dtw_amerced() {
+    let x = Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 4f64)));
+    let y = Array1::from_iter((0..30).map(|i| f64::sin(i as f64 / 3f64 + 0.5)));
+    let size = x.len();
+
+    for distance_mode in [DistanceMode::Manhattan, DistanceMode::Euclidean] {
+        // without a penalty, ADTW is the standard DTW
+        let expected = dtw_ex(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(size, size),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        let result = dtw_amerced(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(size, size),
+            0f64,
+            distance_mode,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+
+        // an infinite penalty aligns the samples in lockstep
+        let (distance, path) = dtw_amerced(
+            &x.view(),
+            &y.view(),
+            FullWindow::new(size, size),
+            f64::INFINITY,
+            distance_mode,
+        )
+        .unwrap();
+        assert_eq!(path.to_vec(), (0..size).map(|i| (i, i)).collect::<Vec<_>>());
+        let lockstep = dtw_ex(
+            &x.view(),
+            &y.view(),
+            SakoeChibaWindow::new(size, size, 0),
+            distance_mode,
+            StorageStrategy::Auto,
+            f64::INFINITY,
+        )
+        .unwrap();
+        assert_eq!(distance, lockstep.0);
+
+        // the distance grows with the penalty, and the warp path gets shorter
+        let mut previous = (0f64, usize::MAX);
+        for penalty in [0f64, 0.01, 0.1, 1f64] {
+            let (distance, path) = dtw_amerced(
+                &x.view(),
+                &y.view(),
+                FullWindow::new(size, size),
+                penalty,
+                distance_mode,
+            )
+            .unwrap();
+            assert!(distance >= previous.0);
+            assert!(path.len() <= previous.1);
+            previous = (distance, path.len());
+        }
+    }
+}